@@ -0,0 +1,453 @@
+//! A [Debug Adapter Protocol][dap] server for the interpreter.
+//!
+//! This lets editors set breakpoints, step through and inspect\
+//! the variables of Diom programs whilst they're evaluated.
+//!
+//! [dap]: https://microsoft.github.io/debug-adapter-protocol/
+use diom_info_traits::{InfoMap, InfoRef};
+use diom_interpreter::{
+  hooks::{Hook, Hooked},
+  Error, Eval, Scope, Value,
+};
+use diom_lexer::recovery::recover_tokens;
+use diom_parser::expressions::parse_expression;
+use diom_syntax::expressions::{Call, Expression, Statement};
+use diom_tokens::{
+  source_map::{LineIndex, Unit},
+  SpanTokens,
+};
+use nom::{Offset, Parser as _};
+use nom_language::error::VerboseError;
+use serde_json::{json, Value as Json};
+use std::{
+  collections::BTreeSet,
+  io::{self, BufRead, Write},
+  ops::Range,
+};
+
+mod protocol;
+pub use protocol::Connection;
+
+type Span = Range<usize>;
+
+/// The only thread that Diom programs run on
+const THREAD_ID: u64 = 1;
+
+/// Lexes and parses `code` into an expression with character spans
+pub fn load(code: &str) -> Result<Expression<Span>, String> {
+  let (tokens, errors) = recover_tokens(code);
+  if let Some(error) = errors.first() {
+    let offset = code.offset(error.origin);
+    let (line, column) = LineIndex::new(code).position(offset, Unit::Char);
+    return Err(format!("{}:{}: {error}", line + 1, column + 1));
+  }
+
+  let (input, expr) = parse_expression::<VerboseError<_>>()
+    .parse(SpanTokens::new(&tokens, code))
+    .map_err(|err| format!("Input failed to parse: {err}"))?;
+  if !input.is_empty() {
+    return Err(format!(
+      "Input was not fully parsed, remaining input = `{input}`"
+    ));
+  }
+  Ok(expr.map(|src| unsafe { src.str_range(code) }.unwrap()))
+}
+
+/// How evaluation should proceed when the debugger is resumed
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Resume {
+  /// Run until the next breakpoint
+  Continue,
+  /// Stop at the first statement in the program
+  Entry,
+  /// Stop at the next statement on a different line or call depth
+  StepIn(usize, usize),
+  /// Stop at the next statement on a different line, within a given call depth
+  Next(usize, usize),
+  /// Stop at the next statement after leaving a given call depth
+  Out(usize),
+}
+
+/// A function call that's currently being evaluated
+struct Frame {
+  /// How the function was referred to when called
+  name: String,
+  /// The span of the statement being evaluated in this call
+  span: Span,
+}
+
+/// A loaded program for the debugger to run
+struct Program {
+  path: String,
+  index: LineIndex,
+}
+
+/// A debugging session with a single client
+pub struct Debugger<R, W> {
+  conn: Connection<R, W>,
+  program: Option<Program>,
+  /// The parsed program, taken once evaluation starts
+  expr: Option<Expression<Span>>,
+  /// The zero-indexed lines that breakpoints are set on
+  breakpoints: BTreeSet<usize>,
+  resume: Resume,
+  frames: Vec<Frame>,
+  /// The line of the last statement reached
+  line: Option<usize>,
+  /// Variables that the client can expand, indexed by `variablesReference - 1`
+  references: Vec<Vec<(String, Value<Span>)>>,
+  /// The number that the client's lines start at, usually `1`
+  line_base: usize,
+  /// The number that the client's columns start at, usually `1`
+  column_base: usize,
+  /// Whether the client has disconnected from the session
+  disconnected: bool,
+}
+
+/// Runs a debugging session over `input` and `output` until the client disconnects
+pub fn serve(input: impl BufRead, output: impl Write) -> io::Result<()> {
+  let mut debugger = Debugger::new(Connection::new(input, output));
+  while let Some(request) = debugger.conn.read()? {
+    match request["command"].as_str() {
+      Some("configurationDone") => {
+        debugger.conn.respond(&request, json!({}))?;
+        debugger.run()?;
+      }
+      Some("disconnect") => {
+        return debugger.conn.respond(&request, json!({}));
+      }
+      _ => debugger.handle(&request)?,
+    }
+    if debugger.disconnected {
+      break;
+    }
+  }
+  Ok(())
+}
+
+impl<R: BufRead, W: Write> Debugger<R, W> {
+  pub fn new(conn: Connection<R, W>) -> Self {
+    Self {
+      conn,
+      program: None,
+      expr: None,
+      breakpoints: BTreeSet::new(),
+      resume: Resume::Continue,
+      frames: vec![],
+      line: None,
+      references: vec![],
+      line_base: 1,
+      column_base: 1,
+      disconnected: false,
+    }
+  }
+
+  /// Handles requests that are valid whether or not the program is running
+  fn handle(&mut self, request: &Json) -> io::Result<()> {
+    let args = &request["arguments"];
+    match request["command"].as_str().unwrap_or_default() {
+      "initialize" => {
+        self.line_base = args["linesStartAt1"].as_bool().unwrap_or(true).into();
+        self.column_base = args["columnsStartAt1"].as_bool().unwrap_or(true).into();
+        self
+          .conn
+          .respond(request, json!({ "supportsConfigurationDoneRequest": true }))?;
+        self.conn.event("initialized", json!({}))
+      }
+      "launch" => {
+        let Some(path) = args["program"].as_str() else {
+          return self
+            .conn
+            .fail(request, "`program` path to launch is missing");
+        };
+        let loaded = std::fs::read_to_string(path)
+          .map_err(|err| err.to_string())
+          .and_then(|code| load(&code).map(|expr| (LineIndex::new(&code), expr)));
+        let (index, expr) = match loaded {
+          Ok(loaded) => loaded,
+          Err(message) => return self.conn.fail(request, message),
+        };
+
+        if args["stopOnEntry"].as_bool().unwrap_or(false) {
+          self.resume = Resume::Entry;
+        }
+        self.program = Some(Program {
+          path: path.into(),
+          index,
+        });
+        self.expr = Some(expr);
+        self.conn.respond(request, json!({}))
+      }
+      "setBreakpoints" => {
+        let lines: Vec<_> = args["breakpoints"]
+          .as_array()
+          .into_iter()
+          .flatten()
+          .filter_map(|bp| bp["line"].as_u64())
+          .map(|line| line as usize)
+          .collect();
+        let count = self.program.as_ref().map_or(0, |p| p.index.len());
+
+        self.breakpoints = lines
+          .iter()
+          .filter_map(|line| line.checked_sub(self.line_base))
+          .collect();
+        let breakpoints: Vec<_> = lines
+          .iter()
+          .map(|&line| {
+            let verified = (self.line_base..self.line_base + count).contains(&line);
+            json!({ "verified": verified, "line": line })
+          })
+          .collect();
+        self
+          .conn
+          .respond(request, json!({ "breakpoints": breakpoints }))
+      }
+      "threads" => self.conn.respond(
+        request,
+        json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] }),
+      ),
+      "stackTrace" => {
+        let frames: Vec<_> = (0..self.frames.len())
+          .rev()
+          .map(|id| self.frame_json(id))
+          .collect();
+        self.conn.respond(
+          request,
+          json!({ "stackFrames": frames, "totalFrames": frames.len() }),
+        )
+      }
+      "scopes" => {
+        // only the variables of the innermost call are tracked
+        let top = self.frames.len().checked_sub(1).map(|id| id as u64);
+        let is_top = top.is_some() && args["frameId"].as_u64() == top;
+        let reference = if is_top && !self.references.is_empty() {
+          1
+        } else {
+          0
+        };
+        self.conn.respond(
+          request,
+          json!({ "scopes": [{
+            "name": "Locals",
+            "variablesReference": reference,
+            "expensive": false,
+          }] }),
+        )
+      }
+      "variables" => {
+        let reference = args["variablesReference"].as_u64().unwrap_or(0) as usize;
+        let Some(entries) = reference
+          .checked_sub(1)
+          .and_then(|idx| self.references.get(idx))
+        else {
+          return self.conn.fail(request, "unknown `variablesReference`");
+        };
+
+        let mut variables = vec![];
+        for (name, value) in entries.clone() {
+          let children = children(&value);
+          let reference = if children.is_empty() {
+            0
+          } else {
+            self.references.push(children);
+            self.references.len()
+          };
+          variables.push(json!({
+            "name": name,
+            "value": describe(&value),
+            "variablesReference": reference,
+          }));
+        }
+        self
+          .conn
+          .respond(request, json!({ "variables": variables }))
+      }
+      "continue" | "next" | "stepIn" | "stepOut" => {
+        self.conn.fail(request, "the program is not paused")
+      }
+      command => self
+        .conn
+        .fail(request, format!("unsupported request `{command}`")),
+    }
+  }
+
+  /// The DAP representation of the frame at `id`
+  fn frame_json(&self, id: usize) -> Json {
+    let frame = &self.frames[id];
+    let Some(program) = &self.program else {
+      return json!({ "id": id, "name": frame.name, "line": 0, "column": 0 });
+    };
+    let (line, column) = program.index.char_position(frame.span.start, Unit::Utf16);
+    let (end_line, end_column) = program.index.char_position(frame.span.end, Unit::Utf16);
+    json!({
+      "id": id,
+      "name": frame.name,
+      "source": { "path": program.path },
+      "line": line + self.line_base,
+      "column": column + self.column_base,
+      "endLine": end_line + self.line_base,
+      "endColumn": end_column + self.column_base,
+    })
+  }
+
+  /// Evaluates the launched program, notifying the client once it's done
+  fn run(&mut self) -> io::Result<()> {
+    let Some(expr) = self.expr.take() else {
+      return self.conn.event("terminated", json!({}));
+    };
+    self.frames = vec![Frame {
+      name: "<program>".into(),
+      span: expr.info().clone(),
+    }];
+
+    let mut state = Hooked::new(&mut *self);
+    let result = expr.eval_with(&mut state);
+    self.frames.clear();
+    self.references.clear();
+    if self.disconnected {
+      return Ok(());
+    }
+
+    let (category, output, code) = match result {
+      Ok(value) => ("stdout", format!("{value:?}\n"), 0),
+      Err(err) => ("stderr", format!("{err:?}\n"), 1),
+    };
+    self
+      .conn
+      .event("output", json!({ "category": category, "output": output }))?;
+    self.conn.event("exited", json!({ "exitCode": code }))?;
+    self.conn.event("terminated", json!({}))
+  }
+
+  /// Called whenever evaluation reaches a new statement
+  fn reached(&mut self, span: &Span, scope: &Scope<Span>) {
+    let Some(program) = &self.program else { return };
+    if self.disconnected {
+      return;
+    }
+    let (line, _) = program.index.char_position(span.start, Unit::Char);
+    let depth = self.frames.len();
+    if let Some(frame) = self.frames.last_mut() {
+      frame.span = span.clone();
+    }
+
+    let reason = match self.resume {
+      Resume::Entry => Some("entry"),
+      Resume::StepIn(d, l) if depth != d || line != l => Some("step"),
+      Resume::Next(d, l) if depth < d || (depth == d && line != l) => Some("step"),
+      Resume::Out(d) if depth < d => Some("step"),
+      _ => None,
+    };
+    let is_new_line = self.line != Some(line);
+    self.line = Some(line);
+    let reason =
+      reason.or((is_new_line && self.breakpoints.contains(&line)).then_some("breakpoint"));
+
+    if let Some(reason) = reason {
+      if self.pause(reason, scope).is_err() {
+        self.disconnected = true;
+      }
+    }
+  }
+
+  /// Pauses evaluation, handling requests until the client resumes
+  fn pause(&mut self, reason: &str, scope: &Scope<Span>) -> io::Result<()> {
+    let mut locals: Vec<_> = scope
+      .iter()
+      .map(|(name, value)| (name.to_string(), value.clone()))
+      .collect();
+    locals.sort_by(|(n0, _), (n1, _)| n0.cmp(n1));
+    self.references = vec![locals];
+
+    self.conn.event(
+      "stopped",
+      json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true }),
+    )?;
+
+    let depth = self.frames.len();
+    let line = self.line.unwrap_or_default();
+    loop {
+      let Some(request) = self.conn.read()? else {
+        self.disconnected = true;
+        return Ok(());
+      };
+      self.resume = match request["command"].as_str() {
+        Some("continue") => Resume::Continue,
+        Some("next") => Resume::Next(depth, line),
+        Some("stepIn") => Resume::StepIn(depth, line),
+        Some("stepOut") => Resume::Out(depth),
+        Some("disconnect") => {
+          self.disconnected = true;
+          return self.conn.respond(&request, json!({}));
+        }
+        _ => {
+          self.handle(&request)?;
+          continue;
+        }
+      };
+      self.references.clear();
+      return self
+        .conn
+        .respond(&request, json!({ "allThreadsContinued": true }));
+    }
+  }
+}
+
+impl<R: BufRead, W: Write> Hook<Span> for Debugger<R, W> {
+  fn statement(&mut self, stmt: &Statement<Span>, scope: &Scope<Span>) {
+    self.reached(stmt.info(), scope)
+  }
+
+  fn call(&mut self, call: &Call<Span>, body: &Expression<Span>, scope: &Scope<Span>) {
+    self.frames.push(Frame {
+      name: call.value.to_string(),
+      span: body.info().clone(),
+    });
+    self.reached(body.info(), scope)
+  }
+
+  fn returned(&mut self, _: &Call<Span>, _: Result<&Value<Span>, &Error<Span>>) {
+    self.frames.pop();
+  }
+}
+
+/// A short description of `value` to display to the client
+fn describe(value: &Value<Span>) -> String {
+  match value {
+    Value::Unit => "()".into(),
+    Value::Float(f) => f.to_string(),
+    Value::Bool(b) => b.to_string(),
+    Value::Char(c) => format!("{c:?}"),
+    Value::Array(items) => format!("[{} items]", items.len()),
+    Value::Struct(fields) => format!("{{{} fields}}", fields.len()),
+    Value::Function(_) => "function".into(),
+    Value::Template(t) => format!("template {{{} values}}", t.values.len()),
+  }
+}
+
+/// The named values nested within `value`
+fn children(value: &Value<Span>) -> Vec<(String, Value<Span>)> {
+  match value {
+    Value::Array(items) => items
+      .iter()
+      .enumerate()
+      .map(|(i, item)| (i.to_string(), item.clone()))
+      .collect(),
+    Value::Struct(fields) => {
+      let mut fields: Vec<_> = fields
+        .iter()
+        .map(|(name, item)| (name.to_string(), item.clone()))
+        .collect();
+      fields.sort_by(|(n0, _), (n1, _)| n0.cmp(n1));
+      fields
+    }
+    Value::Template(t) => t
+      .values
+      .iter()
+      .enumerate()
+      .map(|(i, item)| (i.to_string(), item.clone()))
+      .collect(),
+    _ => vec![],
+  }
+}
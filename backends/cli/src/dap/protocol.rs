@@ -0,0 +1,102 @@
+//! Message framing for the Debug Adapter Protocol.
+//!
+//! Each message is a JSON object preceded by a `Content-Length` header:
+//!
+//! ```_
+//! Content-Length: 119\r\n
+//! \r\n
+//! {"seq": 1, "type": "request", "command": "initialize", ...}
+//! ```
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+/// A connection to a debugger client over a pair of streams
+pub struct Connection<R, W> {
+  input: R,
+  output: W,
+  /// The sequence number of the last message sent
+  seq: u64,
+}
+
+impl<R: BufRead, W: Write> Connection<R, W> {
+  pub fn new(input: R, output: W) -> Self {
+    Self {
+      input,
+      output,
+      seq: 0,
+    }
+  }
+
+  /// Reads the next message sent by the client.\
+  /// Returns `None` once the client has closed the stream.
+  pub fn read(&mut self) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+      let mut header = String::new();
+      if self.input.read_line(&mut header)? == 0 {
+        return Ok(None);
+      }
+      let header = header.trim_end();
+      if header.is_empty() {
+        break;
+      }
+      if let Some(value) = header.strip_prefix("Content-Length:") {
+        let value = value.trim().parse().map_err(io::Error::other)?;
+        length = Some(value);
+      }
+    }
+
+    let Some(length) = length else {
+      return Err(io::Error::other("missing `Content-Length` header"));
+    };
+    let mut content = vec![0; length];
+    self.input.read_exact(&mut content)?;
+    serde_json::from_slice(&content)
+      .map(Some)
+      .map_err(io::Error::other)
+  }
+
+  /// Sends a single message to the client
+  fn send(&mut self, mut message: Value) -> io::Result<()> {
+    self.seq += 1;
+    message["seq"] = self.seq.into();
+    let content = message.to_string();
+    write!(
+      self.output,
+      "Content-Length: {}\r\n\r\n{content}",
+      content.len()
+    )?;
+    self.output.flush()
+  }
+
+  /// Sends a successful response to `request`
+  pub fn respond(&mut self, request: &Value, body: Value) -> io::Result<()> {
+    self.send(json!({
+      "type": "response",
+      "request_seq": request["seq"],
+      "command": request["command"],
+      "success": true,
+      "body": body,
+    }))
+  }
+
+  /// Sends a failed response to `request`
+  pub fn fail(&mut self, request: &Value, message: impl AsRef<str>) -> io::Result<()> {
+    self.send(json!({
+      "type": "response",
+      "request_seq": request["seq"],
+      "command": request["command"],
+      "success": false,
+      "message": message.as_ref(),
+    }))
+  }
+
+  /// Sends an event to the client
+  pub fn event(&mut self, event: &str, body: Value) -> io::Result<()> {
+    self.send(json!({
+      "type": "event",
+      "event": event,
+      "body": body,
+    }))
+  }
+}
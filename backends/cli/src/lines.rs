@@ -0,0 +1,33 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+/// Annotates each line of `source` with the number of times it was hit.\
+/// `hits` maps zero-indexed line numbers to their hit counts.
+///
+/// ```_
+///    1        | (
+///    2      1 |   let x = 1;
+/// ```
+pub fn hit_report(source: &str, hits: &BTreeMap<usize, usize>) -> String {
+  let mut report = String::new();
+  for (i, line) in source.lines().enumerate() {
+    let count = hits.get(&i).map_or(String::new(), usize::to_string);
+    writeln!(report, "{:>4} {count:>6} | {line}", i + 1).unwrap();
+  }
+  report
+}
+
+#[cfg(test)]
+mod test {
+  use super::hit_report;
+  use std::collections::BTreeMap;
+
+  #[test]
+  fn hits() {
+    let hits = BTreeMap::from([(1, 2), (2, 1)]);
+    let report = hit_report("(\n  x;\n  y\n)", &hits);
+    assert_eq!(
+      report,
+      "   1        | (\n   2      2 |   x;\n   3      1 |   y\n   4        | )\n"
+    );
+  }
+}
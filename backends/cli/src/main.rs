@@ -1,26 +1,75 @@
 //! Diom code evaluation via the `interpreter` backend.
-use clap::{Args, Parser};
+use clap::{Args, Parser, Subcommand};
 use diom_fmt::DisplayAs;
 use diom_info_traits::InfoMap;
-use diom_interpreter::Eval;
-use diom_lexer::parse_tokens;
-use diom_parser::expressions::parse_expression;
-use diom_tokens::SpanTokens;
-use nom::{Err, Parser as _};
-use nom_language::error::VerboseError;
-use std::fs::read_to_string;
+use diom_interpreter::{
+  eval_module, hooks::Hooked, profile::Profiler, snapshot, Eval, Scope, Value,
+};
+use diom_lexer::{
+  confusables::ident_warnings,
+  highlight::{to_ansi, to_html},
+  recovery::recover_tokens,
+};
+use diom_parser::{diagnostics::Diagnostic, loader::Loader, recovery::recover_program};
+use diom_tokens::{
+  source_map::{SourceMap, Unit},
+  SpanTokens,
+};
+use std::fs::{read_to_string, write};
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::exit;
 use std::str::FromStr;
 
+mod dap;
+mod lines;
+
 /// Interprets and executes the Diom language
 #[derive(Debug, Parser)]
 #[command(version)]
 #[command(arg_required_else_help = true)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(subcommand_negates_reqs = true)]
 struct MainArgs {
   /// Program code to evaluate
   #[command(flatten)]
   source: SourceArgs,
+
+  /// Profiling output for the evaluation
+  #[command(flatten)]
+  profile: ProfileArgs,
+
+  /// Load variables from a session snapshot before evaluating,\
+  /// then save the variables back to it afterwards
+  #[arg(long, value_name = "PATH")]
+  session: Option<PathBuf>,
+
+  /// A directory to look for imported modules in,\
+  /// after the directory of the importing file
+  #[arg(short = 'I', long = "search", value_name = "DIR")]
+  search: Vec<PathBuf>,
+
+  #[command(subcommand)]
+  command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+  /// Runs a Debug Adapter Protocol server over stdio
+  Dap,
+  /// Prints the program with syntax highlighting
+  Highlight(HighlightArgs),
+}
+
+#[derive(Debug, Args)]
+struct HighlightArgs {
+  /// Program code to highlight
+  #[command(flatten)]
+  source: SourceArgs,
+
+  /// Highlight as HTML, instead of for the terminal
+  #[arg(long)]
+  html: bool,
 }
 
 #[derive(Debug, Args)]
@@ -38,6 +87,18 @@ struct SourceArgs {
   file: Option<String>,
 }
 
+#[derive(Debug, Args)]
+struct ProfileArgs {
+  /// Write a folded-stack profile of function call times to a file.\
+  /// This can be rendered with flamegraph tools.
+  #[arg(long, value_name = "PATH")]
+  profile: Option<PathBuf>,
+
+  /// Write the number of statements evaluated on each line to a file
+  #[arg(long, value_name = "PATH")]
+  hits: Option<PathBuf>,
+}
+
 /// The source to run the program from
 #[derive(Debug, Clone)]
 pub enum ProgramSource {
@@ -69,6 +130,22 @@ impl From<SourceArgs> for ProgramSource {
 }
 
 impl ProgramSource {
+  /// The name to refer to the program by in error messages
+  pub fn name(&self) -> &str {
+    match self {
+      ProgramSource::Eval(_) => "<eval>",
+      ProgramSource::File(path) => path,
+    }
+  }
+
+  /// The directory that relative imports are resolved from
+  pub fn dir(&self) -> &Path {
+    match self {
+      ProgramSource::Eval(_) => Path::new("."),
+      ProgramSource::File(path) => Path::new(path).parent().unwrap_or(Path::new(".")),
+    }
+  }
+
   pub fn fetch(self) -> io::Result<String> {
     use ProgramSource::*;
     match self {
@@ -80,40 +157,142 @@ impl ProgramSource {
 
 fn main() {
   let args = MainArgs::parse();
+  match args.command {
+    Some(Command::Dap) => {
+      dap::serve(io::stdin().lock(), io::stdout().lock()).unwrap();
+      return;
+    }
+    Some(Command::Highlight(HighlightArgs { source, html })) => {
+      let code = ProgramSource::from(source).fetch().unwrap();
+      let highlighted = if html { to_html(&code) } else { to_ansi(&code) };
+      println!("{highlighted}");
+      return;
+    }
+    None => {}
+  }
   let src = ProgramSource::from(args.source);
-  let code = src.fetch().unwrap();
-
-  let (input, tokens) = parse_tokens::<VerboseError<_>>()
-    .parse(code.as_str().into())
-    .unwrap();
-  assert!(
-    input.is_empty(),
-    "Input was not fulled lexed, remaining input = {input}",
-  );
+  let mut sources = SourceMap::default();
+  let file = sources.add(src.name(), &src.clone().fetch().unwrap());
+  let code = sources.file(file).source();
+
+  let (tokens, errors) = recover_tokens(code);
+  if !errors.is_empty() {
+    for error in &errors {
+      let (file, offset) = sources.find(error.origin).unwrap();
+      let (line, column) = sources.position(file, offset, Unit::Char);
+      let name = &sources.file(file).name;
+      eprintln!("{name}:{}:{}: {error}", line + 1, column + 1);
+    }
+    exit(1);
+  }
+  for warning in ident_warnings(&tokens) {
+    let (file, offset) = sources.find(warning.origin()).unwrap();
+    let (line, column) = sources.position(file, offset, Unit::Char);
+    let name = &sources.file(file).name;
+    eprintln!("{name}:{}:{}: warning: {warning}", line + 1, column + 1);
+  }
+  // spans are found from their tokens, so an empty program has none
+  if tokens.is_empty() {
+    eprintln!("{}: no code to evaluate", src.name());
+    exit(1);
+  }
   println!("\n# Lexed Tokens");
-  println!("{}", SpanTokens::new(&tokens, &code));
+  println!("{}", SpanTokens::new(&tokens, code));
 
-  let result = parse_expression::<VerboseError<_>>().parse(SpanTokens::new(&tokens, &code));
-  let (input, expr) = match result {
-    Ok(res) => res,
-    Err(Err::Error(err) | Err::Failure(err)) => {
-      panic!("Input failed to parse, with the errors:\n{}", err)
+  let (program, errors) = recover_program::<Diagnostic>(SpanTokens::new(&tokens, code));
+  for error in &errors {
+    let (file, offset) = sources.find(error.span.origin).unwrap();
+    let (line, column) = sources.position(file, offset, Unit::Char);
+    let name = &sources.file(file).name;
+    eprintln!("{name}:{}:{}: {error}", line + 1, column + 1);
+    for note in &error.notes {
+      let (file, offset) = sources.find(note.span.origin).unwrap();
+      let (line, column) = sources.position(file, offset, Unit::Char);
+      eprintln!(
+        "  note: in {} from {}:{}",
+        note.message,
+        line + 1,
+        column + 1
+      );
     }
-    Err(Err::Incomplete(num)) => panic!("Input required {:?} more tokens", num),
-  };
-  assert!(
-    input.is_empty(),
-    "Input was not fully parsed, remaining input = `{input}`",
-  );
-  let expr = expr.map(|src| unsafe { src.str_range(&code) }.unwrap());
+  }
+  let program = program.map(|src| unsafe { src.str_range(code) }.unwrap());
   println!("\n# Parsed Syntax");
   println!("{code}");
-  println!("{}", (&expr).display());
+  println!("{}", (&program).display());
 
   println!("\n# Displayed Nodes");
-  println!("{expr}");
+  println!("{program}");
+  if !errors.is_empty() {
+    exit(1);
+  }
+
+  let mut loader = Loader::new(args.search);
+  let imports = loader
+    .load_imports(src.dir(), &program)
+    .unwrap_or_else(|err| {
+      eprintln!("{err}");
+      exit(1);
+    });
+  // modules are evaluated after the modules they import
+  let mut exports: Vec<Value<_>> = vec![];
+  for module in loader.modules() {
+    let imports = module
+      .imports
+      .iter()
+      .map(|(name, id)| (name.clone(), exports[id.index()].clone()))
+      .collect();
+    let value = eval_module(&module.program, imports).unwrap_or_else(|err| {
+      eprintln!("{}: {err:?}", module.path.display());
+      exit(1);
+    });
+    exports.push(value);
+  }
+  let imports = imports
+    .into_iter()
+    .map(|(name, id)| (name, exports[id.index()].clone()))
+    .collect();
+
+  let mut scope = Scope::default();
+  if let Some(path) = args.session.as_ref().filter(|path| path.exists()) {
+    let saved = read_to_string(path).unwrap();
+    scope = snapshot::load(&saved).unwrap_or_else(|err| panic!("Failed to load session: {err}"));
+  }
 
-  let value = expr.eval().unwrap();
+  let ProfileArgs { profile, hits } = args.profile;
+  let value = if profile.is_none() && hits.is_none() {
+    let mut state = Hooked {
+      scope,
+      imports,
+      ..Hooked::new(())
+    };
+    let value = program.eval_with(&mut state);
+    scope = state.scope;
+    value
+  } else {
+    let mut state = Hooked {
+      scope,
+      imports,
+      ..Hooked::new(Profiler::new())
+    };
+    let value = program.eval_with(&mut state);
+    scope = state.scope;
+    let report = state.hook.finish();
+
+    if let Some(path) = profile {
+      write(path, report.folded()).unwrap();
+    }
+    if let Some(path) = hits {
+      let index = &sources.file(file).index;
+      let hits = report.line_hits(|span| index.char_position(span.start, Unit::Char).0);
+      write(path, lines::hit_report(code, &hits)).unwrap();
+    }
+    value
+  }
+  .unwrap();
+  if let Some(path) = args.session {
+    write(path, snapshot::save(&scope)).unwrap();
+  }
   println!("\n# Evaluated Value");
   println!("{value:?}");
 }
@@ -0,0 +1,247 @@
+//! Scripted Debug Adapter Protocol sessions against `diom dap`.
+use serde_json::{json, Value};
+use std::{
+  io::{BufRead, BufReader, Read, Write},
+  path::PathBuf,
+  process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+/// A client driving a `diom dap` process over stdio
+struct Session {
+  child: Child,
+  input: ChildStdin,
+  output: BufReader<ChildStdout>,
+  seq: u64,
+}
+
+impl Session {
+  fn start() -> Self {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_diom-cli"))
+      .arg("dap")
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .spawn()
+      .expect("`diom dap` to start");
+    let input = child.stdin.take().unwrap();
+    let output = BufReader::new(child.stdout.take().unwrap());
+    Self {
+      child,
+      input,
+      output,
+      seq: 0,
+    }
+  }
+
+  /// Sends a request and waits for its response
+  fn request(&mut self, command: &str, arguments: Value) -> Value {
+    self.seq += 1;
+    let content = json!({
+      "seq": self.seq,
+      "type": "request",
+      "command": command,
+      "arguments": arguments,
+    })
+    .to_string();
+    write!(
+      self.input,
+      "Content-Length: {}\r\n\r\n{content}",
+      content.len()
+    )
+    .unwrap();
+    self.input.flush().unwrap();
+
+    let seq = self.seq;
+    let response = self.wait_for(|msg| msg["type"] == "response" && msg["request_seq"] == seq);
+    assert_eq!(response["success"], true, "{command} failed: {response}");
+    response
+  }
+
+  /// Waits for an event with the given name
+  fn event(&mut self, event: &str) -> Value {
+    self.wait_for(|msg| msg["type"] == "event" && msg["event"] == event)
+  }
+
+  /// Reads messages until one matches `pred`
+  fn wait_for(&mut self, pred: impl Fn(&Value) -> bool) -> Value {
+    loop {
+      let msg = self.read();
+      if pred(&msg) {
+        return msg;
+      }
+    }
+  }
+
+  fn read(&mut self) -> Value {
+    let mut length = 0;
+    loop {
+      let mut header = String::new();
+      assert_ne!(
+        self.output.read_line(&mut header).unwrap(),
+        0,
+        "server closed"
+      );
+      let header = header.trim_end();
+      if header.is_empty() {
+        break;
+      }
+      if let Some(value) = header.strip_prefix("Content-Length:") {
+        length = value.trim().parse().unwrap();
+      }
+    }
+    let mut content = vec![0; length];
+    self.output.read_exact(&mut content).unwrap();
+    serde_json::from_slice(&content).unwrap()
+  }
+
+  /// Initialises the session and launches `code`, with breakpoints on `lines`
+  fn launch(&mut self, name: &str, code: &str, lines: &[u64], stop_on_entry: bool) {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, code).unwrap();
+
+    self.request("initialize", json!({ "adapterID": "diom" }));
+    self.event("initialized");
+    self.request(
+      "launch",
+      json!({ "program": path, "stopOnEntry": stop_on_entry }),
+    );
+    let breakpoints: Vec<_> = lines.iter().map(|line| json!({ "line": line })).collect();
+    let response = self.request(
+      "setBreakpoints",
+      json!({ "source": { "path": path }, "breakpoints": breakpoints }),
+    );
+    for bp in response["body"]["breakpoints"].as_array().unwrap() {
+      assert_eq!(bp["verified"], true);
+    }
+    self.request("configurationDone", json!({}));
+  }
+
+  /// Waits until the program stops, returning the reason and current frames
+  fn stopped(&mut self) -> (String, Vec<Value>) {
+    let event = self.event("stopped");
+    let trace = self.request("stackTrace", json!({ "threadId": 1 }));
+    let reason = event["body"]["reason"].as_str().unwrap().to_owned();
+    let frames = trace["body"]["stackFrames"].as_array().unwrap().clone();
+    (reason, frames)
+  }
+
+  /// The `(name, value)` pairs of variables in the innermost frame
+  fn locals(&mut self, frame: &Value) -> Vec<(String, String, u64)> {
+    let scopes = self.request("scopes", json!({ "frameId": frame["id"] }));
+    let reference = &scopes["body"]["scopes"][0]["variablesReference"];
+    self.variables(reference.as_u64().unwrap())
+  }
+
+  fn variables(&mut self, reference: u64) -> Vec<(String, String, u64)> {
+    let response = self.request("variables", json!({ "variablesReference": reference }));
+    response["body"]["variables"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|var| {
+        (
+          var["name"].as_str().unwrap().to_owned(),
+          var["value"].as_str().unwrap().to_owned(),
+          var["variablesReference"].as_u64().unwrap(),
+        )
+      })
+      .collect()
+  }
+
+  /// Waits for the program to finish and the server to exit
+  fn finish(mut self) -> String {
+    let output = self.event("output");
+    self.event("exited");
+    self.event("terminated");
+    self.request("disconnect", json!({}));
+    assert!(self.child.wait().unwrap().success());
+    output["body"]["output"].as_str().unwrap().to_owned()
+  }
+}
+
+const PROGRAM: &str = "(
+  let x = 1;
+  let y = (x + 2);
+  let z = [x, y];
+  z
+)";
+
+#[test]
+fn breakpoints() {
+  let mut session = Session::start();
+  session.launch("breakpoints.diom", PROGRAM, &[3], false);
+
+  let (reason, frames) = session.stopped();
+  assert_eq!(reason, "breakpoint");
+  assert_eq!(frames.len(), 1);
+  assert_eq!(frames[0]["line"], 3);
+  assert_eq!(frames[0]["column"], 3);
+  assert_eq!(frames[0]["endColumn"], 18);
+
+  let locals = session.locals(&frames[0]);
+  assert_eq!(locals, vec![("x".into(), "1".into(), 0)]);
+
+  session.request("continue", json!({ "threadId": 1 }));
+  assert_eq!(session.finish(), "Array([Float(1.0), Float(3.0)])\n");
+}
+
+#[test]
+fn stepping() {
+  let mut session = Session::start();
+  session.launch("stepping.diom", PROGRAM, &[], true);
+
+  let (reason, frames) = session.stopped();
+  assert_eq!(reason, "entry");
+  assert_eq!(frames[0]["line"], 2);
+
+  for line in [3, 4, 5] {
+    session.request("next", json!({ "threadId": 1 }));
+    let (reason, frames) = session.stopped();
+    assert_eq!(reason, "step");
+    assert_eq!(frames[0]["line"], line);
+  }
+
+  let locals = session.locals(&frames[0]);
+  assert_eq!(locals.len(), 3);
+  let (name, value, reference) = &locals[2];
+  assert_eq!((name.as_str(), value.as_str()), ("z", "[2 items]"));
+  let items = session.variables(*reference);
+  assert_eq!(
+    items,
+    vec![("0".into(), "1".into(), 0), ("1".into(), "3".into(), 0)]
+  );
+
+  session.request("continue", json!({ "threadId": 1 }));
+  session.finish();
+}
+
+#[test]
+fn function_calls() {
+  let code = "(
+  let f = (a) => a * 2;
+  let y = (f(3));
+  y
+)";
+  let mut session = Session::start();
+  session.launch("calls.diom", code, &[3], false);
+  let (reason, _) = session.stopped();
+  assert_eq!(reason, "breakpoint");
+
+  session.request("stepIn", json!({ "threadId": 1 }));
+  let (_, frames) = session.stopped();
+  assert_eq!(frames.len(), 2);
+  assert_eq!(frames[0]["name"], "f");
+  assert_eq!(frames[0]["line"], 2);
+  assert_eq!(frames[1]["name"], "<program>");
+  assert_eq!(frames[1]["line"], 3);
+
+  let locals = session.locals(&frames[0]);
+  assert!(locals.contains(&("a".into(), "3".into(), 0)));
+
+  session.request("stepOut", json!({ "threadId": 1 }));
+  let (_, frames) = session.stopped();
+  assert_eq!(frames.len(), 1);
+  assert_eq!(frames[0]["line"], 4);
+
+  session.request("continue", json!({ "threadId": 1 }));
+  assert_eq!(session.finish(), "Float(6.0)\n");
+}
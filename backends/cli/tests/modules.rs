@@ -0,0 +1,112 @@
+//! Evaluating files that import other modules with `use`.
+use std::{
+  fs::{create_dir_all, remove_dir_all, write},
+  path::{Path, PathBuf},
+  process::{Command, Output},
+};
+
+/// Creates an empty directory for a test's modules
+fn modules(name: &str) -> PathBuf {
+  let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+  let _ = remove_dir_all(&dir);
+  create_dir_all(dir.join("lib")).unwrap();
+  dir
+}
+
+fn run(dir: &Path, search: &[&str]) -> Output {
+  let mut command = Command::new(env!("CARGO_BIN_EXE_diom-cli"));
+  command.arg("--file").arg(dir.join("main.diom"));
+  for path in search {
+    command.arg("-I").arg(dir.join(path));
+  }
+  command.output().unwrap()
+}
+
+#[test]
+fn imports() {
+  let dir = modules("imports");
+  write(
+    dir.join("main.diom"),
+    "use \"./shapes.diom\";\nuse util;\nshapes.side * util.scale",
+  )
+  .unwrap();
+  write(
+    dir.join("shapes.diom"),
+    "use util;\nlet side = (util.scale + 1)",
+  )
+  .unwrap();
+  write(dir.join("lib/util.diom"), "let scale = 2").unwrap();
+
+  let output = run(&dir, &["lib"]);
+  assert!(output.status.success(), "{output:?}");
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  let (_, value) = stdout.rsplit_once("# Evaluated Value\n").unwrap();
+  assert_eq!(value.trim(), "Float(6.0)");
+}
+
+#[test]
+fn missing() {
+  let dir = modules("missing");
+  write(dir.join("main.diom"), "use util").unwrap();
+
+  let output = run(&dir, &[]);
+  assert!(!output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains("module util not found"), "{stderr}");
+}
+
+#[test]
+fn bindings() {
+  let dir = modules("bindings");
+  create_dir_all(dir.join("lib/a")).unwrap();
+  create_dir_all(dir.join("lib/b")).unwrap();
+  write(dir.join("lib/a/util.diom"), "let one = 1").unwrap();
+  write(dir.join("lib/b/util.diom"), "let two = 2").unwrap();
+  write(dir.join("main.diom"), "use a.util; use b.util; util.one").unwrap();
+
+  let output = run(&dir, &["lib"]);
+  assert!(!output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(
+    stderr.contains("modules a.util and b.util are both bound to util"),
+    "{stderr}"
+  );
+
+  write(dir.join("my-lib.diom"), "let three = 3").unwrap();
+  write(dir.join("main.diom"), "use \"./my-lib.diom\"; 3").unwrap();
+  let output = run(&dir, &[]);
+  assert!(!output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains("can't be bound to a name"), "{stderr}");
+}
+
+#[test]
+fn nested() {
+  let dir = modules("nested");
+  write(
+    dir.join("main.diom"),
+    "let f = () => (use util; util.two); f()",
+  )
+  .unwrap();
+  write(dir.join("lib/util.diom"), "let two = 2").unwrap();
+
+  let output = run(&dir, &["lib"]);
+  assert!(!output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  // modules can only be imported at the top level
+  assert!(stderr.contains("main.diom:1:16: "), "{stderr}");
+  assert!(!stderr.contains("panicked"), "{stderr}");
+}
+
+#[test]
+fn module_error() {
+  let dir = modules("module_error");
+  write(dir.join("main.diom"), "use util; util.two").unwrap();
+  write(dir.join("lib/util.diom"), "let two = missing").unwrap();
+
+  let output = run(&dir, &["lib"]);
+  assert!(!output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains("util.diom: MissingVar"), "{stderr}");
+  assert!(!stderr.contains("panicked"), "{stderr}");
+}
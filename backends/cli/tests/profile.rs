@@ -0,0 +1,70 @@
+//! Profiling output written by the CLI.
+use std::{fs::read_to_string, path::PathBuf, process::Command};
+
+const PROGRAM: &str = "(
+  let f = (a) => a * 2;
+  let x = (f(1));
+  (f(x))
+)";
+
+#[test]
+fn profile_and_hits() {
+  let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+  let (profile, hits) = (dir.join("profile.folded"), dir.join("profile.hits"));
+  let status = Command::new(env!("CARGO_BIN_EXE_diom-cli"))
+    .args(["--eval", PROGRAM, "--profile"])
+    .arg(&profile)
+    .arg("--hits")
+    .arg(&hits)
+    .output()
+    .unwrap()
+    .status;
+  assert!(status.success());
+
+  let profile = read_to_string(profile).unwrap();
+  let stacks: Vec<_> = profile
+    .lines()
+    .map(|line| line.rsplit_once(' ').unwrap())
+    .collect();
+  assert_eq!(stacks.len(), 2);
+  assert_eq!(stacks[0].0, "<program>");
+  assert_eq!(stacks[1].0, "<program>;f");
+  for (_, micros) in stacks {
+    micros.parse::<u128>().unwrap();
+  }
+
+  let hits = read_to_string(hits).unwrap();
+  assert_eq!(
+    hits,
+    "   1      1 | (
+   2      1 |   let f = (a) => a * 2;
+   3      2 |   let x = (f(1));
+   4      2 |   (f(x))
+   5        | )
+"
+  );
+}
+
+#[test]
+fn top_level_hits() {
+  // each top level statement of a program is a hit on the line it starts on
+  let hits = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("top_level.hits");
+  let status = Command::new(env!("CARGO_BIN_EXE_diom-cli"))
+    .args(["--eval", "let x = 1;\n\nlet y = (\n  x + 1\n); y", "--hits"])
+    .arg(&hits)
+    .output()
+    .unwrap()
+    .status;
+  assert!(status.success());
+
+  let hits = read_to_string(hits).unwrap();
+  assert_eq!(
+    hits,
+    "   1      1 | let x = 1;
+   2        | 
+   3      1 | let y = (
+   4      1 |   x + 1
+   5      1 | ); y
+"
+  );
+}
@@ -0,0 +1,71 @@
+//! Saving and restoring variables between evaluations with `--session`.
+use std::{
+  fs::{read_to_string, remove_file, write},
+  path::{Path, PathBuf},
+  process::{Command, Output},
+};
+
+fn run(session: &Path, code: &str) -> Output {
+  Command::new(env!("CARGO_BIN_EXE_diom-cli"))
+    .args(["--eval", code, "--session"])
+    .arg(session)
+    .output()
+    .unwrap()
+}
+
+/// The value printed at the end of a successful evaluation
+fn value(output: Output) -> String {
+  assert!(output.status.success(), "{output:?}");
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  let (_, value) = stdout.rsplit_once("# Evaluated Value\n").unwrap();
+  value.trim().to_owned()
+}
+
+fn session(name: &str) -> PathBuf {
+  let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+  let _ = remove_file(&path);
+  path
+}
+
+#[test]
+fn closures() {
+  let path = session("closures.json");
+  value(run(&path, "let x = 4"));
+  value(run(&path, "let f = (a) => a * x"));
+  value(run(&path, "let x = 1"));
+  // `f` still refers to the `x` it captured
+  assert_eq!(value(run(&path, "f(2)")), "Float(8.0)");
+  assert_eq!(value(run(&path, "x")), "Float(1.0)");
+}
+
+#[test]
+fn shared_functions() {
+  let path = session("shared.json");
+  value(run(&path, "let fs = (let f = (a) => a; [f, f])"));
+  // functions are compared by identity, which must survive reloading
+  assert_eq!(value(run(&path, "fs[0] == fs[1]")), "Bool(true)");
+}
+
+#[test]
+fn recursive_functions() {
+  let path = session("recursive.json");
+  value(run(&path, "let f = (a) => f"));
+  assert_eq!(value(run(&path, "f(1) == f")), "Bool(true)");
+}
+
+#[test]
+fn incompatible_version() {
+  let path = session("version.json");
+  value(run(&path, "let x = [1, 2]"));
+  let saved = read_to_string(&path).unwrap();
+  assert!(saved.starts_with(r#"{"format":"diom-snapshot","version":1,"#));
+
+  write(&path, saved.replace(r#""version":1"#, r#""version":0"#)).unwrap();
+  let output = run(&path, "x");
+  assert!(!output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(
+    stderr.contains("snapshot version 0 is incompatible"),
+    "{stderr}"
+  );
+}
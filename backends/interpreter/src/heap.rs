@@ -0,0 +1,123 @@
+//! Collection of reference cycles between function values.
+//!
+//! Functions own the scope they were defined in, which can refer back to the function,\
+//! i.e. the recursive function `let f = (a) => f(a)` captures itself.\
+//! Reference counting alone never frees these cycles, so functions are allocated on a
+//! `Heap` that periodically finds and breaks cycles that are no longer reachable.
+use crate::{FunctionPtr, FunctionValue, Value};
+use std::{
+  collections::{HashMap, HashSet},
+  rc::{Rc, Weak},
+};
+
+/// The number of functions to allocate before first collecting cycles
+const MIN_THRESHOLD: usize = 1024;
+
+/// Tracks allocated functions, so that unreachable cycles can be collected
+#[derive(Debug)]
+pub struct Heap<I> {
+  functions: Vec<Weak<FunctionValue<I>>>,
+  /// The number of tracked functions at which to next collect cycles
+  threshold: usize,
+}
+
+impl<I> Default for Heap<I> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<I> Heap<I> {
+  pub fn new() -> Self {
+    Self {
+      functions: vec![],
+      threshold: MIN_THRESHOLD,
+    }
+  }
+
+  /// The number of functions currently tracked by the heap
+  pub fn len(&self) -> usize {
+    self.functions.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.functions.is_empty()
+  }
+
+  /// Allocates `value`, collecting cycles first if enough functions have been allocated
+  pub fn alloc(&mut self, value: FunctionValue<I>) -> FunctionPtr<I> {
+    if self.functions.len() >= self.threshold {
+      self.collect();
+      self.threshold = MIN_THRESHOLD.max(2 * self.functions.len());
+    }
+    let ptr = Rc::new(value);
+    self.functions.push(Rc::downgrade(&ptr));
+    FunctionPtr(ptr)
+  }
+
+  /// Frees functions only reachable through reference cycles,\
+  /// returning the number of functions freed.
+  ///
+  /// This uses trial deletion: references from tracked functions are subtracted from
+  /// each function's reference count, so functions with references remaining must be
+  /// referred to from outside the heap. Anything those can't reach is garbage.
+  pub fn collect(&mut self) -> usize {
+    self.functions.retain(|f| f.strong_count() > 0);
+    let live: Vec<_> = self.functions.iter().filter_map(Weak::upgrade).collect();
+    let index: HashMap<_, _> = live
+      .iter()
+      .enumerate()
+      .map(|(i, f)| (Rc::as_ptr(f), i))
+      .collect();
+
+    // `live` holds one extra reference to each function
+    let mut external: Vec<_> = live.iter().map(|f| Rc::strong_count(f) - 1).collect();
+    let mut children = vec![vec![]; live.len()];
+    for (i, func) in live.iter().enumerate() {
+      for value in func.scope.borrow().values() {
+        functions_in(value, &mut |child| {
+          if let Some(&j) = index.get(&Rc::as_ptr(&child.0)) {
+            children[i].push(j);
+            external[j] = external[j].saturating_sub(1);
+          }
+        });
+      }
+    }
+
+    let mut reachable = HashSet::new();
+    let mut stack: Vec<_> = (0..live.len()).filter(|&i| external[i] > 0).collect();
+    while let Some(i) = stack.pop() {
+      if reachable.insert(i) {
+        stack.extend(children[i].iter().copied());
+      }
+    }
+
+    // clearing scopes breaks the cycles, letting reference counting free them
+    let garbage: Vec<_> = (0..live.len())
+      .filter(|i| !reachable.contains(i))
+      .map(|i| std::mem::take(&mut *live[i].scope.borrow_mut()))
+      .collect();
+    let freed = garbage.len();
+    drop(garbage);
+    drop(live);
+    self.functions.retain(|f| f.strong_count() > 0);
+    freed
+  }
+}
+
+impl<I> Drop for Heap<I> {
+  fn drop(&mut self) {
+    self.collect();
+  }
+}
+
+/// Calls `f` on each function directly contained within `value`
+fn functions_in<I>(value: &Value<I>, f: &mut impl FnMut(&FunctionPtr<I>)) {
+  match value {
+    Value::Array(items) => items.iter().for_each(|v| functions_in(v, f)),
+    Value::Struct(fields) => fields.values().for_each(|v| functions_in(v, f)),
+    Value::Function(func) => f(func),
+    Value::Template(t) => t.values.iter().for_each(|v| functions_in(v, f)),
+    _ => {}
+  }
+}
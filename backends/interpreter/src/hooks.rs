@@ -0,0 +1,90 @@
+//! Callbacks for observing the interpreter whilst it evaluates code.
+use crate::{heap::Heap, Error, Scope, Value};
+use diom_syntax::expressions::{Call, Expression, Statement};
+use std::collections::HashMap;
+
+/// Callbacks invoked by the interpreter during evaluation.\
+/// These can be used to build debuggers, profilers and tracers.
+///
+/// All methods default to doing nothing.
+pub trait Hook<I> {
+  /// Called before each statement within a block is evaluated
+  fn statement(&mut self, _stmt: &Statement<I>, _scope: &Scope<I>) {}
+
+  /// Called before any expression is evaluated
+  fn enter(&mut self, _expr: &Expression<I>, _scope: &Scope<I>) {}
+
+  /// Called after an expression has been evaluated
+  fn exit(&mut self, _expr: &Expression<I>, _result: Result<&Value<I>, &Error<I>>) {}
+
+  /// Called once a function's arguments have been bound,\
+  /// just before the body of the function is evaluated
+  fn call(&mut self, _call: &Call<I>, _body: &Expression<I>, _scope: &Scope<I>) {}
+
+  /// Called once the body of a called function has been evaluated
+  fn returned(&mut self, _call: &Call<I>, _result: Result<&Value<I>, &Error<I>>) {}
+}
+
+impl<I> Hook<I> for () {}
+
+impl<I, H: Hook<I>> Hook<I> for &mut H {
+  fn statement(&mut self, stmt: &Statement<I>, scope: &Scope<I>) {
+    (**self).statement(stmt, scope)
+  }
+
+  fn enter(&mut self, expr: &Expression<I>, scope: &Scope<I>) {
+    (**self).enter(expr, scope)
+  }
+
+  fn exit(&mut self, expr: &Expression<I>, result: Result<&Value<I>, &Error<I>>) {
+    (**self).exit(expr, result)
+  }
+
+  fn call(&mut self, call: &Call<I>, body: &Expression<I>, scope: &Scope<I>) {
+    (**self).call(call, body, scope)
+  }
+
+  fn returned(&mut self, call: &Call<I>, result: Result<&Value<I>, &Error<I>>) {
+    (**self).returned(call, result)
+  }
+}
+
+/// The evaluation state of the interpreter with a `Hook` attached
+#[derive(Debug)]
+pub struct Hooked<I, H = ()> {
+  /// The variables currently in scope
+  pub scope: Scope<I>,
+  /// The hook to call whilst evaluating
+  pub hook: H,
+  /// The heap that functions are allocated on
+  pub heap: Heap<I>,
+  /// The exports of modules that `use` statements can bind,\
+  /// keyed by how the module is referred to, i.e. `math.vector` or `"./shapes.diom"`
+  pub imports: HashMap<Box<str>, Value<I>>,
+}
+
+impl<I, H> Hooked<I, H> {
+  /// Attaches `hook` to an empty scope
+  pub fn new(hook: H) -> Self {
+    Self {
+      scope: Scope::default(),
+      hook,
+      heap: Heap::new(),
+      imports: HashMap::new(),
+    }
+  }
+
+  /// Runs `f` within `scope`, restoring the current scope afterwards
+  pub(crate) fn within<T>(&mut self, scope: Scope<I>, f: impl FnOnce(&mut Self) -> T) -> T {
+    let outer = std::mem::replace(&mut self.scope, scope);
+    let result = f(self);
+    self.scope = outer;
+    result
+  }
+}
+
+impl<I, H: Default> Default for Hooked<I, H> {
+  fn default() -> Self {
+    Self::new(H::default())
+  }
+}
@@ -1,18 +1,44 @@
 use diom_syntax::{
   expressions::{Call, Expression, Function, FunctionArm, Infix, Prefix, Statement},
   idents::{LitName, Method, Name, Op, Symbol},
+  modules::Use,
   patterns::Pattern,
+  program::Program,
   Ptr,
 };
-use std::{collections::HashMap, ops::Deref, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, ops::Deref, rc::Rc};
+
+pub mod heap;
+pub mod hooks;
+use hooks::{Hook, Hooked};
+pub mod profile;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
 
-#[derive(Debug)]
 pub struct FunctionValue<I> {
-  scope: Scope<I>,
+  /// The scope the function was defined in.\
+  /// This is mutable so that recursive functions can capture themselves.
+  scope: RefCell<Scope<I>>,
   names: Vec<LitName>,
   returned: Ptr<Expression<I>>,
 }
 
+/// Only the names in the captured scope are shown,\
+/// as recursive functions capture themselves.
+impl<I: std::fmt::Debug> std::fmt::Debug for FunctionValue<I> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let mut debug = f.debug_struct("FunctionValue");
+    match self.scope.try_borrow() {
+      Ok(scope) => debug.field("scope", &scope.keys().collect::<Vec<_>>()),
+      Err(_) => debug.field("scope", &format_args!("<borrowed>")),
+    };
+    debug
+      .field("names", &self.names)
+      .field("returned", &self.returned)
+      .finish()
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct FunctionPtr<I>(Rc<FunctionValue<I>>);
 
@@ -42,6 +68,7 @@ pub enum Value<I> {
   Array(Array<I>),
   Struct(Struct<I>),
   Function(FunctionPtr<I>),
+  Template(Template<I>),
 }
 
 impl<I> PartialEq for Value<I> {
@@ -54,6 +81,7 @@ impl<I> PartialEq for Value<I> {
       (Self::Array(v0), Self::Array(v1)) => v0 == v1,
       (Self::Struct(v0), Self::Struct(v1)) => v0 == v1,
       (Self::Function(v0), Self::Function(v1)) => v0 == v1,
+      (Self::Template(v0), Self::Template(v1)) => v0 == v1,
       _ => false,
     }
   }
@@ -62,7 +90,63 @@ impl<I> PartialEq for Value<I> {
 type Array<I> = Vec<Value<I>>;
 type Struct<I> = HashMap<Name, Value<I>>;
 
-type Scope<I> = HashMap<LitName, Value<I>>;
+/// A template, with its interpolated values kept apart from the text around them.\
+/// Templates are only flattened into characters when explicitly converted.
+#[derive(Debug, Clone)]
+pub struct Template<I> {
+  /// The text around `values`, with one more item than `values`
+  pub strings: Vec<LitName>,
+  pub values: Vec<Value<I>>,
+}
+
+impl<I> PartialEq for Template<I> {
+  fn eq(&self, other: &Self) -> bool {
+    self.strings == other.strings && self.values == other.values
+  }
+}
+
+impl<I> Template<I> {
+  /// Flattens the template into its characters
+  ///
+  /// ```_
+  /// f"{'a'} + {"bc"} = {2}" # flattens to "a + bc = 2"
+  /// ```
+  pub fn flatten(&self) -> Result<Vec<char>, Error<I>> {
+    let mut chars = vec![];
+    self.flatten_into(&mut chars)?;
+    Ok(chars)
+  }
+
+  fn flatten_into(&self, chars: &mut Vec<char>) -> Result<(), Error<I>> {
+    chars.extend(self.strings[0].chars());
+    for (value, text) in self.values.iter().zip(&self.strings[1..]) {
+      match value {
+        Value::Char(c) => chars.push(*c),
+        Value::Float(f) => chars.extend(f.to_string().chars()),
+        Value::Bool(b) => chars.extend(b.to_string().chars()),
+        Value::Template(t) => t.flatten_into(chars)?,
+        Value::Array(items) => {
+          for item in items {
+            let Value::Char(c) = item else {
+              return Err(Error::Type("Interpolated arrays must only contain `char`s"));
+            };
+            chars.push(*c);
+          }
+        }
+        Value::Unit | Value::Struct(_) | Value::Function(_) => {
+          return Err(Error::Type(
+            "Only strings, `char`s, `float`s and `bool`s can be interpolated",
+          ))
+        }
+      }
+      chars.extend(text.chars());
+    }
+    Ok(())
+  }
+}
+
+/// The variables accessible whilst evaluating an expression
+pub type Scope<I> = HashMap<LitName, Value<I>>;
 
 #[derive(Debug)]
 pub enum Error<I> {
@@ -78,10 +162,11 @@ pub enum Error<I> {
   IndexOutsideBounds(Vec<Value<I>>, usize, usize),
   TooFewArgs(Vec<LitName>, Vec<Expression<I>>),
   TooManyArgs(Vec<LitName>, Vec<Expression<I>>),
+  MissingModule(Use<I>),
 }
 
 /// A type that can be evaluated to a given value when given a starting state
-pub trait Eval<S: Default = ()> {
+pub trait Eval<S = ()> {
   /// The output value produced when the type is evaluated
   type Output;
   /// Errors produced when evaluating `Self`
@@ -91,16 +176,44 @@ pub trait Eval<S: Default = ()> {
   fn eval_with(&self, state: &mut S) -> Result<Self::Output, Self::Error>;
 
   /// Evaluate `self` with the default "empty" starting state
-  fn eval(&self) -> Result<Self::Output, Self::Error> {
+  fn eval(&self) -> Result<Self::Output, Self::Error>
+  where
+    S: Default,
+  {
     self.eval_with(&mut S::default())
   }
 }
 
+/// Evaluates `value` within `state` without any hooks attached
+fn unhooked<I, T>(value: &T, state: &mut Scope<I>) -> Result<Value<I>, Error<I>>
+where
+  T: Eval<Hooked<I>, Output = Value<I>, Error = Error<I>>,
+{
+  let mut hooked = Hooked {
+    scope: std::mem::take(state),
+    hook: (),
+    heap: Default::default(),
+    imports: Default::default(),
+  };
+  let result = value.eval_with(&mut hooked);
+  *state = hooked.scope;
+  result
+}
+
 impl<I: Clone> Eval<Scope<I>> for Prefix<I> {
   type Output = Value<I>;
   type Error = Error<I>;
 
   fn eval_with(&self, state: &mut Scope<I>) -> Result<Self::Output, Self::Error> {
+    unhooked(self, state)
+  }
+}
+
+impl<I: Clone, H: Hook<I>> Eval<Hooked<I, H>> for Prefix<I> {
+  type Output = Value<I>;
+  type Error = Error<I>;
+
+  fn eval_with(&self, state: &mut Hooked<I, H>) -> Result<Self::Output, Self::Error> {
     use Value::*;
     let Prefix {
       name: Op { sym, .. },
@@ -146,6 +259,15 @@ impl<I: Clone> Eval<Scope<I>> for Infix<I> {
   type Error = Error<I>;
 
   fn eval_with(&self, state: &mut Scope<I>) -> Result<Self::Output, Self::Error> {
+    unhooked(self, state)
+  }
+}
+
+impl<I: Clone, H: Hook<I>> Eval<Hooked<I, H>> for Infix<I> {
+  type Output = Value<I>;
+  type Error = Error<I>;
+
+  fn eval_with(&self, state: &mut Hooked<I, H>) -> Result<Self::Output, Self::Error> {
     use Value::*;
     let Infix {
       value,
@@ -229,42 +351,126 @@ impl<I: Clone> Eval<Scope<I>> for Statement<I> {
   type Error = Error<I>;
 
   fn eval_with(&self, state: &mut Scope<I>) -> Result<Self::Output, Self::Error> {
+    unhooked(self, state)
+  }
+}
+
+impl<I: Clone, H: Hook<I>> Eval<Hooked<I, H>> for Statement<I> {
+  type Output = Value<I>;
+  type Error = Error<I>;
+
+  fn eval_with(&self, state: &mut Hooked<I, H>) -> Result<Self::Output, Self::Error> {
     use Statement::*;
     match self {
       TypeDef(_) => Err(Error::Unsupported("Types")),
       Expression(expr) => expr.eval_with(state),
+      Use(import) => {
+        let module = state.imports.get(&*import.module.to_string());
+        let (Some(binding), Some(exports)) = (import.module.binding(), module) else {
+          return Err(Error::MissingModule(import.clone()));
+        };
+        state.scope.insert(binding.into(), exports.clone());
+        Ok(Value::Bool(true))
+      }
     }
   }
 }
 
-impl<I: Clone> Eval<Scope<I>> for Expression<I> {
+impl<I: Clone> Eval<Scope<I>> for Program<I> {
   type Output = Value<I>;
   type Error = Error<I>;
 
   fn eval_with(&self, state: &mut Scope<I>) -> Result<Self::Output, Self::Error> {
+    unhooked(self, state)
+  }
+}
+
+impl<I: Clone, H: Hook<I>> Eval<Hooked<I, H>> for Program<I> {
+  type Output = Value<I>;
+  type Error = Error<I>;
+
+  /// Evaluates each statement in the outer scope, returning the value of the last
+  fn eval_with(&self, state: &mut Hooked<I, H>) -> Result<Self::Output, Self::Error> {
+    self.statements.iter().try_fold(Value::Unit, |_, stmt| {
+      state.hook.statement(stmt, &state.scope);
+      stmt.eval_with(state)
+    })
+  }
+}
+
+/// Evaluates a module in a fresh scope, with `imports` available to its `use` statements,\
+/// returning its top level bindings as a struct
+pub fn eval_module<I: Clone>(
+  program: &Program<I>,
+  imports: HashMap<Box<str>, Value<I>>,
+) -> Result<Value<I>, Error<I>> {
+  let mut state = Hooked {
+    imports,
+    ..Hooked::new(())
+  };
+  program.eval_with(&mut state)?;
+  let exports = state.scope.into_iter();
+  Ok(Value::Struct(exports.map(|(k, v)| (k.into(), v)).collect()))
+}
+
+impl<I: Clone> Eval<Scope<I>> for Expression<I> {
+  type Output = Value<I>;
+  type Error = Error<I>;
+
+  fn eval_with(&self, state: &mut Scope<I>) -> Result<Value<I>, Error<I>> {
+    unhooked(self, state)
+  }
+}
+
+impl<I: Clone, H: Hook<I>> Eval<Hooked<I, H>> for Expression<I> {
+  type Output = Value<I>;
+  type Error = Error<I>;
+
+  fn eval_with(&self, state: &mut Hooked<I, H>) -> Result<Value<I>, Error<I>> {
+    state.hook.enter(self, &state.scope);
+    let result = self.eval_node(state);
+    state.hook.exit(self, result.as_ref());
+    result
+  }
+}
+
+/// Evaluation of a single syntax node, without notifying hooks
+trait EvalNode<S> {
+  type Output;
+  type Error;
+
+  fn eval_node(&self, state: &mut S) -> Result<Self::Output, Self::Error>;
+}
+
+impl<I: Clone, H: Hook<I>> EvalNode<Hooked<I, H>> for Expression<I> {
+  type Output = Value<I>;
+  type Error = Error<I>;
+
+  fn eval_node(&self, state: &mut Hooked<I, H>) -> Result<Value<I>, Error<I>> {
     match self {
       Self::Char(c) => Ok(Value::Char(c.value)),
       Self::Float(f) => Ok(Value::Float(f.value)),
+      // strings are sugar for arrays of characters
+      Self::String(s) => Ok(Value::Array(s.value.chars().map(Value::Char).collect())),
       Self::Var(v) => {
-        let Some(value) = state.get(&v.name) else {
+        let Some(value) = state.scope.get(&v.name) else {
           return Err(Error::MissingVar(v.clone().into()));
         };
         Ok(value.clone())
       }
       Self::Group(group) => group.value.eval_with(state),
-      Self::Block(block) => {
-        let mut inner = state.clone();
-        block
-          .statements
-          .iter()
-          .try_fold(Value::Unit, |_, stmt| stmt.eval_with(&mut inner))
-      }
+      Self::Block(block) => state.within(state.scope.clone(), |inner| {
+        block.statements.iter().try_fold(Value::Unit, |_, stmt| {
+          inner.hook.statement(stmt, &inner.scope);
+          stmt.eval_with(inner)
+        })
+      }),
       Self::Assign(a) => {
-        let Expression::Var(v) = a.reference.deref() else {
+        let Self::Var(v) = a.reference.deref() else {
           return Err(Error::Unsupported("Assignments to non-variables"));
         };
         let value = a.value.eval_with(state)?;
-        let Some(entry) = state.get_mut(&v.name) else {
+        let Some(entry) = state.scope.get_mut(&v.name) else {
           return Err(Error::Unsupported("Assignment to non-existant variables"));
         };
         // @todo this breaks reference updates, i.e. this fails:
@@ -278,7 +484,12 @@ impl<I: Clone> Eval<Scope<I>> for Expression<I> {
           return Err(Error::Unsupported("`let` for non-variables"));
         };
         let value = d.value.eval_with(state)?;
-        state.insert(v.name.clone(), value);
+        // functions declared with `let` can refer to themselves
+        if let (Self::Function(_), Value::Function(func)) = (d.value.deref(), &value) {
+          let mut scope = func.scope.borrow_mut();
+          scope.insert(v.name.clone(), value.clone());
+        }
+        state.scope.insert(v.name.clone(), value);
         Ok(Value::Bool(true)) // pattern matching succeeded
 
         // @todo I probably need to consider what `let` statements / pattern matching
@@ -326,22 +537,28 @@ impl<I: Clone> Eval<Scope<I>> for Expression<I> {
 
         // @todo Clone the scope and a expression reference
         // Err(Error::Unsupported("Functions"))
-        Ok(Value::Function(
-          FunctionValue {
-            scope: state.clone(),
-            names,
-            returned: returned.clone(),
-          }
-          .into(),
-        ))
+        Ok(Value::Function(state.heap.alloc(FunctionValue {
+          scope: RefCell::new(state.scope.clone()),
+          names,
+          returned: returned.clone(),
+        })))
       }
+      Self::Template(template) => Ok(Value::Template(Template {
+        strings: template.strings.clone(),
+        values: template
+          .values
+          .iter()
+          .map(|e| e.eval_with(state))
+          .collect::<Result<_, _>>()?,
+      })),
       Self::Struct(data) => data
         .fields
         .iter()
         .map(|(ident, item)| item.eval_with(state).map(|val| (ident.name.clone(), val)))
         .collect::<Result<HashMap<Name, Value<I>>, _>>()
         .map(Value::Struct),
-      Self::Call(Call { value, args, .. }) => {
+      Self::Call(call) => {
+        let Call { value, args, .. } = call;
         let Value::Function(func) = value.eval_with(state)? else {
           return Err(Error::Type("Non functions cannot be called"));
         };
@@ -362,10 +579,15 @@ impl<I: Clone> Eval<Scope<I>> for Expression<I> {
           .into_iter()
           .map(|arg| arg.eval_with(state))
           .collect::<Result<Vec<_>, _>>()?;
-        let mut scope = scope.clone();
+        let mut scope = scope.borrow().clone();
         scope.extend(names.into_iter().cloned().zip(values));
 
-        returned.eval_with(&mut scope)
+        state.within(scope, |inner| {
+          inner.hook.call(call, returned, &inner.scope);
+          let result = returned.eval_with(inner);
+          inner.hook.returned(call, result.as_ref());
+          result
+        })
       }
       Self::Field(field) => {
         let value = field.value.eval_with(state)?;
@@ -407,6 +629,7 @@ impl<I: Clone> Eval<Scope<I>> for Expression<I> {
       Self::Monad(_) => Err(Error::Unsupported("Monads")),
       Self::Result(_) => Err(Error::Unsupported("Monads")),
       Self::Prefix(prefix) => prefix.eval_with(state),
+      Self::Error(_) => Err(Error::Unsupported("Expressions that failed to parse")),
     }
   }
 }
@@ -0,0 +1,176 @@
+//! Instrumentation for finding where evaluation time is spent.
+use crate::{hooks::Hook, Error, Scope, Value};
+use diom_info_traits::InfoRef;
+use diom_syntax::expressions::{Call, Expression, Statement};
+use std::{
+  collections::{BTreeMap, HashMap},
+  fmt::Write,
+  hash::Hash,
+  time::{Duration, Instant},
+};
+
+/// How often, and for how long, something was evaluated
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Stats {
+  /// The number of times it was evaluated
+  pub count: usize,
+  /// The total wall time spent evaluating it, including nested evaluations
+  pub time: Duration,
+}
+
+impl Stats {
+  fn record(&mut self, time: Duration) {
+    self.count += 1;
+    self.time += time;
+  }
+}
+
+/// The results of profiling an evaluation
+#[derive(Debug, Clone)]
+pub struct Profile<I> {
+  /// Stats for each function, keyed by the expression it was called with
+  pub functions: HashMap<String, Stats>,
+  /// Stats for each evaluated expression, keyed by its info
+  pub spans: HashMap<I, Stats>,
+  /// The number of times each statement was evaluated, keyed by its info
+  pub statements: HashMap<I, usize>,
+  /// The time spent in each stack of function calls, excluding nested calls.\
+  /// Stacks are stored as function names separated by `;`
+  pub stacks: BTreeMap<String, Duration>,
+}
+
+impl<I> Profile<I> {
+  /// Formats the call stacks in the "folded" format used by flamegraph tools.\
+  /// Each line contains a stack, followed by the microseconds spent in it:
+  ///
+  /// ```_
+  /// <program>;f;g 120
+  /// ```
+  pub fn folded(&self) -> String {
+    let mut output = String::new();
+    for (stack, time) in &self.stacks {
+      writeln!(output, "{stack} {}", time.as_micros()).unwrap();
+    }
+    output
+  }
+
+  /// The number of statements evaluated on each line,\
+  /// where `line_of` finds the line that a statement starts on
+  pub fn line_hits(&self, mut line_of: impl FnMut(&I) -> usize) -> BTreeMap<usize, usize> {
+    let mut hits = BTreeMap::new();
+    for (info, count) in &self.statements {
+      *hits.entry(line_of(info)).or_default() += count;
+    }
+    hits
+  }
+}
+
+/// A function call that's currently being evaluated
+struct Frame {
+  name: String,
+  start: Instant,
+  /// The time spent in calls made from this frame
+  nested: Duration,
+}
+
+impl Frame {
+  fn new(name: String) -> Self {
+    Self {
+      name,
+      start: Instant::now(),
+      nested: Duration::ZERO,
+    }
+  }
+}
+
+/// A `Hook` that counts evaluations and measures their wall time
+pub struct Profiler<I> {
+  profile: Profile<I>,
+  frames: Vec<Frame>,
+  /// The times that expressions currently being evaluated started at
+  starts: Vec<Instant>,
+}
+
+impl<I> Default for Profiler<I> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<I> Profiler<I> {
+  /// The name given to the outermost frame of a profile
+  pub const ROOT: &'static str = "<program>";
+
+  pub fn new() -> Self {
+    Self {
+      profile: Profile {
+        functions: HashMap::new(),
+        spans: HashMap::new(),
+        statements: HashMap::new(),
+        stacks: BTreeMap::new(),
+      },
+      frames: vec![Frame::new(Self::ROOT.into())],
+      starts: vec![],
+    }
+  }
+
+  /// Closes the current frame, recording the time spent in it
+  fn pop_frame(&mut self) -> Option<(String, Duration)> {
+    let stack = self.stack();
+    let frame = self.frames.pop()?;
+    let time = frame.start.elapsed();
+    *self.profile.stacks.entry(stack).or_default() += time.saturating_sub(frame.nested);
+    if let Some(parent) = self.frames.last_mut() {
+      parent.nested += time;
+    }
+    Some((frame.name, time))
+  }
+
+  /// The names of the frames currently being evaluated, separated by `;`
+  fn stack(&self) -> String {
+    let names: Vec<_> = self.frames.iter().map(|f| f.name.as_str()).collect();
+    names.join(";")
+  }
+
+  /// Stops profiling, returning the collected results
+  pub fn finish(mut self) -> Profile<I> {
+    while self.pop_frame().is_some() {}
+    self.profile
+  }
+}
+
+impl<I: Clone + Eq + Hash> Hook<I> for Profiler<I> {
+  fn statement(&mut self, stmt: &Statement<I>, _: &Scope<I>) {
+    let info = stmt.info().clone();
+    *self.profile.statements.entry(info).or_default() += 1;
+  }
+
+  fn enter(&mut self, _: &Expression<I>, _: &Scope<I>) {
+    self.starts.push(Instant::now());
+  }
+
+  fn exit(&mut self, expr: &Expression<I>, _: Result<&Value<I>, &Error<I>>) {
+    let Some(start) = self.starts.pop() else {
+      return;
+    };
+    let info = expr.info().clone();
+    self
+      .profile
+      .spans
+      .entry(info)
+      .or_default()
+      .record(start.elapsed());
+  }
+
+  fn call(&mut self, call: &Call<I>, _: &Expression<I>, _: &Scope<I>) {
+    // `;` separates frames in folded stacks, so can't appear in names
+    let name = call.value.to_string().replace(';', ",");
+    self.frames.push(Frame::new(name));
+  }
+
+  fn returned(&mut self, _: &Call<I>, _: Result<&Value<I>, &Error<I>>) {
+    if let Some((name, time)) = self.pop_frame() {
+      self.profile.functions.entry(name).or_default().record(time);
+    }
+  }
+}
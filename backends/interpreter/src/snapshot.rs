@@ -0,0 +1,254 @@
+//! Saving and restoring the variables in a `Scope`.
+//!
+//! Snapshots are stored as JSON, starting with a format name and version:
+//!
+//! ```_
+//! {"format":"diom-snapshot","version":1,"functions":[...],"bindings":[...]}
+//! ```
+//!
+//! Functions are stored once each in `functions` and referred to by index,\
+//! so values that shared a function before saving still share it once loaded,\
+//! and recursive functions can refer to themselves.
+use crate::{FunctionPtr, FunctionValue, Scope, Template, Value};
+use diom_syntax::{
+  expressions::Expression,
+  idents::{LitName, Name},
+  Ptr,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::HashMap, fmt, rc::Rc};
+
+/// The name snapshots are tagged with
+pub const FORMAT: &str = "diom-snapshot";
+/// The version of the snapshot format.\
+/// This should be bumped whenever the format, or the syntax tree, changes.
+pub const VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+  /// The snapshot isn't valid JSON, or doesn't match the snapshot format
+  Json(serde_json::Error),
+  /// The snapshot was tagged with a different format name
+  Format(String),
+  /// The snapshot was saved with an incompatible version
+  Version(u32),
+  /// A value refers to a function that isn't stored
+  MissingFunction(usize),
+}
+
+impl fmt::Display for SnapshotError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Json(err) => write!(f, "invalid snapshot: {err}"),
+      Self::Format(name) => write!(f, "expected a `{FORMAT}`, found `{name}`"),
+      Self::Version(version) => write!(
+        f,
+        "snapshot version {version} is incompatible with version {VERSION}"
+      ),
+      Self::MissingFunction(idx) => write!(f, "function {idx} isn't stored"),
+    }
+  }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<serde_json::Error> for SnapshotError {
+  fn from(err: serde_json::Error) -> Self {
+    Self::Json(err)
+  }
+}
+
+/// The start of every snapshot, checked before reading the rest
+#[derive(Serialize, Deserialize)]
+struct Header {
+  format: String,
+  version: u32,
+}
+
+/// A value, with functions replaced by indices into `Snapshot::functions`
+#[derive(Serialize, Deserialize)]
+enum Stored {
+  Unit,
+  Float(f64),
+  Bool(bool),
+  Char(char),
+  Array(Vec<Stored>),
+  Struct(Vec<(Name, Stored)>),
+  Function(usize),
+  Template(Vec<LitName>, Vec<Stored>),
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredFunction<I> {
+  scope: Vec<(LitName, Stored)>,
+  names: Vec<LitName>,
+  returned: Ptr<Expression<I>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot<I> {
+  #[serde(flatten)]
+  header: Header,
+  functions: Vec<StoredFunction<I>>,
+  bindings: Vec<(LitName, Stored)>,
+}
+
+/// Saves the variables in `scope` to a JSON snapshot
+pub fn save<I: Clone + Serialize>(scope: &Scope<I>) -> String {
+  let mut saver = Saver {
+    indices: HashMap::new(),
+    functions: vec![],
+  };
+  let bindings = saver.scope(scope);
+  let snapshot = Snapshot {
+    header: Header {
+      format: FORMAT.into(),
+      version: VERSION,
+    },
+    functions: saver.functions,
+    bindings,
+  };
+  serde_json::to_string(&snapshot).expect("snapshots should always serialise")
+}
+
+/// Loads the variables saved in a JSON `snapshot`
+pub fn load<I: Clone + DeserializeOwned>(snapshot: &str) -> Result<Scope<I>, SnapshotError> {
+  let Header { format, version } = serde_json::from_str(snapshot)?;
+  if format != FORMAT {
+    return Err(SnapshotError::Format(format));
+  }
+  if version != VERSION {
+    return Err(SnapshotError::Version(version));
+  }
+
+  let snapshot: Snapshot<I> = serde_json::from_str(snapshot)?;
+  let (scopes, functions): (Vec<_>, Vec<FunctionPtr<I>>) = snapshot
+    .functions
+    .into_iter()
+    .map(
+      |StoredFunction {
+         scope,
+         names,
+         returned,
+       }| {
+        let value = FunctionValue {
+          scope: Default::default(),
+          names,
+          returned,
+        };
+        (scope, value.into())
+      },
+    )
+    .unzip();
+  // scopes are filled in once every function exists, as they can form cycles
+  for (scope, func) in scopes.into_iter().zip(&functions) {
+    *func.scope.borrow_mut() = restore_scope(scope, &functions)?;
+  }
+  restore_scope(snapshot.bindings, &functions)
+}
+
+/// Collects the functions used by values whilst saving them
+struct Saver<I> {
+  /// The index each function has been stored at, keyed by its address
+  indices: HashMap<*const FunctionValue<I>, usize>,
+  functions: Vec<StoredFunction<I>>,
+}
+
+impl<I: Clone> Saver<I> {
+  /// Saves a scope, sorted by name so snapshots of equal scopes are equal
+  fn scope(&mut self, scope: &Scope<I>) -> Vec<(LitName, Stored)> {
+    let mut names: Vec<_> = scope.keys().collect();
+    names.sort();
+    names
+      .into_iter()
+      .map(|name| (name.clone(), self.value(&scope[name])))
+      .collect()
+  }
+
+  fn value(&mut self, value: &Value<I>) -> Stored {
+    match value {
+      Value::Unit => Stored::Unit,
+      Value::Float(f) => Stored::Float(*f),
+      Value::Bool(b) => Stored::Bool(*b),
+      Value::Char(c) => Stored::Char(*c),
+      Value::Array(items) => Stored::Array(items.iter().map(|v| self.value(v)).collect()),
+      Value::Struct(fields) => {
+        let mut fields: Vec<_> = fields
+          .iter()
+          .map(|(name, v)| (name.clone(), self.value(v)))
+          .collect();
+        fields.sort_by_key(|(name, _)| name.to_string());
+        Stored::Struct(fields)
+      }
+      Value::Function(func) => Stored::Function(self.function(func)),
+      Value::Template(t) => Stored::Template(
+        t.strings.clone(),
+        t.values.iter().map(|v| self.value(v)).collect(),
+      ),
+    }
+  }
+
+  fn function(&mut self, func: &FunctionPtr<I>) -> usize {
+    let addr = Rc::as_ptr(&func.0);
+    if let Some(idx) = self.indices.get(&addr) {
+      return *idx;
+    }
+    // the index is reserved before storing the scope, which may refer back to `func`
+    let idx = self.functions.len();
+    self.indices.insert(addr, idx);
+    self.functions.push(StoredFunction {
+      scope: vec![],
+      names: func.names.clone(),
+      returned: func.returned.clone(),
+    });
+    self.functions[idx].scope = self.scope(&func.scope.borrow());
+    idx
+  }
+}
+
+fn restore_scope<I: Clone>(
+  bindings: Vec<(LitName, Stored)>,
+  functions: &[FunctionPtr<I>],
+) -> Result<Scope<I>, SnapshotError> {
+  bindings
+    .into_iter()
+    .map(|(name, value)| Ok((name, restore(value, functions)?)))
+    .collect()
+}
+
+fn restore<I: Clone>(
+  value: Stored,
+  functions: &[FunctionPtr<I>],
+) -> Result<Value<I>, SnapshotError> {
+  Ok(match value {
+    Stored::Unit => Value::Unit,
+    Stored::Float(f) => Value::Float(f),
+    Stored::Bool(b) => Value::Bool(b),
+    Stored::Char(c) => Value::Char(c),
+    Stored::Array(items) => Value::Array(
+      items
+        .into_iter()
+        .map(|v| restore(v, functions))
+        .collect::<Result<_, SnapshotError>>()?,
+    ),
+    Stored::Struct(fields) => Value::Struct(
+      fields
+        .into_iter()
+        .map(|(name, v)| Ok((name, restore(v, functions)?)))
+        .collect::<Result<_, SnapshotError>>()?,
+    ),
+    Stored::Template(strings, values) => Value::Template(Template {
+      strings,
+      values: values
+        .into_iter()
+        .map(|v| restore(v, functions))
+        .collect::<Result<_, SnapshotError>>()?,
+    }),
+    Stored::Function(idx) => Value::Function(
+      functions
+        .get(idx)
+        .cloned()
+        .ok_or(SnapshotError::MissingFunction(idx))?,
+    ),
+  })
+}
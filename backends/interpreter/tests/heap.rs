@@ -0,0 +1,48 @@
+//! Collection of cycles between recursive functions.
+use diom_interpreter::{hooks::Hooked, Eval, Value};
+use diom_parser::testing::parse_expr;
+
+#[test]
+fn recursive_functions() {
+  let expr = parse_expr("let f = (a) => f");
+  let mut state = Hooked::new(());
+  expr.eval_with(&mut state).unwrap();
+  let f = state.scope["f"].clone();
+  let Value::Function(_) = &f else {
+    panic!("expected a function, found {f:?}")
+  };
+  // `f` refers to itself, so returns itself
+  let returned = parse_expr("f(1)").eval_with(&mut state).unwrap();
+  assert_eq!(returned, f);
+}
+
+#[test]
+fn debug_recursive_function() {
+  let expr = parse_expr("(let f = (a) => f; f)");
+  let f = expr.eval_with(&mut Hooked::new(())).unwrap();
+  // the captured scope contains `f` itself, so only its names are shown
+  let debug = format!("{f:?}");
+  assert!(debug.contains("scope: [\"f\"]"), "{debug}");
+}
+
+#[test]
+fn collects_cycles() {
+  let expr = parse_expr("let f = (a) => f(a)");
+  let mut state = Hooked::new(());
+  expr.eval_with(&mut state).unwrap();
+  assert_eq!(state.heap.collect(), 0);
+  state.scope.clear();
+  assert_eq!(state.heap.collect(), 1);
+  assert!(state.heap.is_empty());
+}
+
+#[test]
+fn million_closures() {
+  let expr = parse_expr("let f = (a) => f(a)");
+  let mut state = Hooked::new(());
+  for _ in 0..1_000_000 {
+    expr.eval_with(&mut state).unwrap();
+    // each `let` drops the last cycle, which must eventually be collected
+    assert!(state.heap.len() <= 2048, "{} functions", state.heap.len());
+  }
+}
@@ -0,0 +1,62 @@
+//! Evaluation of programs, where each statement shares the outer scope.
+use diom_interpreter::{eval_module, hooks::Hooked, Error, Eval, Scope, Value};
+use diom_parser::testing::parse_prog;
+use diom_syntax::idents::{LitName, Name};
+use std::collections::HashMap;
+
+#[test]
+fn last_value() {
+  let program = parse_prog("let x = 2;\nlet double = (y) => y * 2;\ndouble(x) + 1;");
+  assert_eq!(
+    program.eval_with(&mut Scope::default()).unwrap(),
+    Value::Float(5.0)
+  );
+}
+
+#[test]
+fn shared_scope() {
+  let mut scope = Scope::default();
+  parse_prog("let x = 1; let y = (x + 1)")
+    .eval_with(&mut scope)
+    .unwrap();
+  assert_eq!(scope.get(&LitName::from("y")), Some(&Value::Float(2.0)));
+}
+
+#[test]
+fn empty() {
+  assert_eq!(
+    parse_prog("").eval_with(&mut Scope::default()).unwrap(),
+    Value::Unit
+  );
+}
+
+#[test]
+fn module_exports() {
+  let exports = eval_module(
+    &parse_prog("let one = 1; let two = (one + 1)"),
+    HashMap::new(),
+  )
+  .unwrap();
+  let Value::Struct(fields) = exports else {
+    panic!("expected the exports to be a struct");
+  };
+  assert_eq!(
+    fields.get(&Name::from(LitName::from("two"))),
+    Some(&Value::Float(2.0))
+  );
+}
+
+#[test]
+fn imports() {
+  let math = eval_module(&parse_prog("let two = 2"), HashMap::new()).unwrap();
+  let mut state = Hooked::new(());
+  state.imports.insert("lib.math".into(), math);
+  let value = parse_prog("use lib.math; math.two * 3").eval_with(&mut state);
+  assert_eq!(value.unwrap(), Value::Float(6.0));
+}
+
+#[test]
+fn missing_module() {
+  let value = parse_prog("use math").eval_with(&mut Scope::default());
+  assert!(matches!(value, Err(Error::MissingModule(_))));
+}
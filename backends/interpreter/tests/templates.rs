@@ -0,0 +1,37 @@
+//! Evaluation of templates, which stay structured until flattened.
+use diom_interpreter::{hooks::Hooked, Eval, Value};
+use diom_parser::testing::parse_expr;
+use std::ops::Range;
+
+fn eval(code: &str) -> Value<Range<usize>> {
+  parse_expr(code).eval_with(&mut Hooked::new(())).unwrap()
+}
+
+#[test]
+fn structured() {
+  let Value::Template(template) = eval(r#"f"{"a"} and {'b'}""#) else {
+    panic!("expected a template")
+  };
+  assert_eq!(template.strings, vec!["".into(), " and ".into(), "".into()]);
+  assert_eq!(
+    template.values,
+    vec![Value::Array(vec![Value::Char('a')]), Value::Char('b')]
+  );
+}
+
+#[test]
+fn flatten() {
+  let Value::Template(template) = eval(r#"f"{1 + 1} is {f"{"two"}"}, {2 == 2}""#) else {
+    panic!("expected a template")
+  };
+  let flat: String = template.flatten().unwrap().into_iter().collect();
+  assert_eq!(flat, "2 is two, true");
+}
+
+#[test]
+fn flatten_non_text() {
+  let Value::Template(template) = eval(r#"f"{ {a: 1} }""#) else {
+    panic!("expected a template")
+  };
+  assert!(template.flatten().is_err());
+}
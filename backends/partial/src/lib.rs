@@ -0,0 +1,432 @@
+//! Partial evaluation of Diom expressions.
+//!
+//! Given a set of variables with known values, this simplifies expressions by:
+//!
+//! 1. folding arithmetic on float literals, i.e. `2 * 3` into `6`
+//! 2. inlining `let` bindings of known values
+//! 3. beta-reducing calls to known functions with a single arm
+//!
+//! Nodes in the simplified expression keep the info of the nodes they came from,\
+//! so spans from the parser still point at the original source.
+use diom_syntax::{
+  expressions::{
+    Array, Assign, Block, Call, Char, Declare, Expression, Field, Float, Function, FunctionArm,
+    Group, Index, Infix, MonadResult, MonadThen, Prefix, Return, Statement, String, Struct,
+    Template,
+  },
+  idents::{LitName, Name, Symbol},
+  patterns::Pattern,
+  Ptr,
+};
+use std::collections::{HashMap, HashSet};
+
+mod names;
+use names::{assigned, for_each_binding, free};
+
+#[cfg(test)]
+mod tests;
+
+/// Variables with values known before evaluation
+pub type Bindings<I> = HashMap<LitName, Expression<I>>;
+
+/// Partially evaluates `expr`, given the values of the `known` variables
+pub fn partial_eval<I: Clone>(expr: &Expression<I>, known: Bindings<I>) -> Expression<I> {
+  Partial::new(known).eval(expr)
+}
+
+/// A partial evaluator for expressions
+#[derive(Debug, Clone)]
+pub struct Partial<I> {
+  /// The variables that are known in the current scope
+  known: Bindings<I>,
+  /// The number of nested function calls currently being inlined
+  depth: usize,
+  /// The maximum number of nested function calls to inline.\
+  /// This stops inlining from looping forever on i.e. `((f) => f(f))((f) => f(f))`
+  pub max_depth: usize,
+}
+
+impl<I> Default for Partial<I> {
+  fn default() -> Self {
+    Self::new(Bindings::new())
+  }
+}
+
+/// Whether `expr` can be freely copied without changing its meaning,\
+/// i.e. a literal or function that doesn't refer to any outside variables
+fn is_value<I>(expr: &Expression<I>) -> bool {
+  match expr {
+    Expression::Char(_) | Expression::Float(_) | Expression::String(_) => true,
+    Expression::Array(a) => a.contents.iter().all(is_value),
+    Expression::Struct(s) => s.fields.iter().all(|(_, e)| is_value(e)),
+    Expression::Function(_) => {
+      let mut names = HashSet::new();
+      free(expr, &mut vec![], &mut names);
+      names.is_empty()
+    }
+    _ => false,
+  }
+}
+
+/// Moves a literal `expr` to the position given by `info`
+fn respan<I>(expr: Expression<I>, info: &I) -> Expression<I>
+where
+  I: Clone,
+{
+  match expr {
+    Expression::Char(c) => Expression::Char(Char {
+      info: info.clone(),
+      ..c
+    }),
+    Expression::Float(f) => Expression::Float(Float {
+      info: info.clone(),
+      ..f
+    }),
+    Expression::String(s) => Expression::String(String {
+      info: info.clone(),
+      ..s
+    }),
+    expr => expr,
+  }
+}
+
+impl<I> Partial<I> {
+  pub fn new(known: Bindings<I>) -> Self {
+    Self {
+      known,
+      depth: 0,
+      max_depth: 32,
+    }
+  }
+
+  /// Forgets the values of variables bound by `pattern`
+  fn unbind(&mut self, pattern: &Pattern<I>) {
+    for_each_binding(pattern, &mut |name| {
+      self.known.remove(name);
+    })
+  }
+}
+
+impl<I: Clone> Partial<I> {
+  /// Partially evaluates `expr` within the current scope
+  pub fn eval(&mut self, expr: &Expression<I>) -> Expression<I> {
+    use Expression::*;
+    let eval = |this: &mut Self, e: &Ptr<Expression<I>>| Ptr::new(this.eval(e));
+    match expr {
+      Char(_) | Float(_) | String(_) | Error(_) => expr.clone(),
+      Var(v) => match self.known.get(&v.name) {
+        Some(value) => respan(value.clone(), &v.info),
+        None => expr.clone(),
+      },
+      Group(g) => {
+        let value = self.eval(&g.value);
+        if matches!(value, Char(_) | Float(_) | String(_)) {
+          return respan(value, &g.info);
+        }
+        Group(self::Group {
+          value: Ptr::new(value),
+          info: g.info.clone(),
+        })
+      }
+      Block(b) => self.block(b),
+      Assign(a) => {
+        let value = eval(self, &a.value);
+        let reference = match a.reference.as_ref() {
+          Var(v) => {
+            self.known.remove(&v.name);
+            a.reference.clone()
+          }
+          _ => eval(self, &a.reference),
+        };
+        Assign(self::Assign {
+          reference,
+          value,
+          info: a.info.clone(),
+        })
+      }
+      Declare(d) => {
+        let value = eval(self, &d.value);
+        self.unbind(&d.pattern);
+        Declare(self::Declare { value, ..d.clone() })
+      }
+      Return(r) => Return(self::Return {
+        value: eval(self, &r.value),
+        info: r.info.clone(),
+      }),
+      Array(a) => Array(self::Array {
+        contents: a.contents.iter().map(|e| self.eval(e)).collect(),
+        info: a.info.clone(),
+      }),
+      Function(f) => Function(self.function(f)),
+      Struct(s) => Struct(self::Struct {
+        fields: s
+          .fields
+          .iter()
+          .map(|(name, e)| (name.clone(), self.eval(e)))
+          .collect(),
+        info: s.info.clone(),
+      }),
+      Template(t) => Template(self::Template {
+        strings: t.strings.clone(),
+        values: t.values.iter().map(|e| self.eval(e)).collect(),
+        info: t.info.clone(),
+      }),
+      Call(c) => self.call(c),
+      Field(f) => Field(self::Field {
+        value: eval(self, &f.value),
+        name: f.name.clone(),
+        info: f.info.clone(),
+      }),
+      Index(i) => Index(self::Index {
+        value: eval(self, &i.value),
+        keys: i.keys.iter().map(|e| self.eval(e)).collect(),
+        info: i.info.clone(),
+      }),
+      Infix(i) => self.infix(i),
+      Monad(m) => Monad(MonadThen {
+        value: eval(self, &m.value),
+        info: m.info.clone(),
+      }),
+      Prefix(p) => self.prefix(p),
+      Result(r) => Result(MonadResult {
+        value: eval(self, &r.value),
+        info: r.info.clone(),
+      }),
+    }
+  }
+
+  fn block(&mut self, block: &Block<I>) -> Expression<I> {
+    let outer = self.known.clone();
+    let mut reassigned = HashSet::new();
+    for stmt in &block.statements {
+      if let Statement::Expression(e) = stmt {
+        assigned(e, &mut reassigned);
+      }
+    }
+
+    let last = block.statements.len().saturating_sub(1);
+    let mut statements = vec![];
+    for (i, stmt) in block.statements.iter().enumerate() {
+      let expr = match stmt {
+        Statement::TypeDef(_) => {
+          statements.push(stmt.clone());
+          continue;
+        }
+        Statement::Use(import) => {
+          // the imported module's exports are only known at runtime
+          if let Some(binding) = import.module.binding() {
+            self.known.remove(binding);
+          }
+          statements.push(stmt.clone());
+          continue;
+        }
+        Statement::Expression(Expression::Declare(d)) => {
+          let value = self.eval(&d.value);
+          self.unbind(&d.pattern);
+
+          let Pattern::Var(v) = &d.pattern else {
+            statements.push(Statement::Expression(Expression::Declare(Declare {
+              value: Ptr::new(value),
+              ..d.clone()
+            })));
+            continue;
+          };
+          if is_value(&value) && !reassigned.contains(&v.name) {
+            self.known.insert(v.name.clone(), value.clone());
+            // the declaration's only needed for its type or as the block's value
+            if d.annotation.is_none() && i != last {
+              continue;
+            }
+          }
+          Expression::Declare(Declare {
+            value: Ptr::new(value),
+            ..d.clone()
+          })
+        }
+        Statement::Expression(e) => self.eval(e),
+      };
+
+      // values that aren't returned from a block have no effect
+      if i != last && is_value(&expr) {
+        continue;
+      }
+      statements.push(Statement::Expression(expr));
+    }
+    self.known = outer;
+
+    if let [Statement::Expression(expr)] = statements.as_slice() {
+      match expr {
+        Expression::Char(_) | Expression::Float(_) | Expression::String(_) => {
+          return respan(expr.clone(), &block.info)
+        }
+        // a block that only contains another block has the same scope as it
+        Expression::Block(inner) => {
+          return Expression::Block(Block {
+            statements: inner.statements.clone(),
+            info: block.info.clone(),
+          })
+        }
+        _ => {}
+      }
+    }
+    Expression::Block(Block {
+      statements,
+      info: block.info.clone(),
+    })
+  }
+
+  fn function(&mut self, func: &Function<I>) -> Function<I> {
+    let arms = func
+      .arms
+      .iter()
+      .map(|arm| {
+        let outer = self.known.clone();
+        for param in &arm.parameters.parameters {
+          self.unbind(&param.pattern);
+        }
+        let returned = Ptr::new(self.eval(&arm.returned));
+        self.known = outer;
+        FunctionArm {
+          returned,
+          ..arm.clone()
+        }
+      })
+      .collect();
+    Function {
+      arms,
+      info: func.info.clone(),
+    }
+  }
+
+  fn call(&mut self, call: &Call<I>) -> Expression<I> {
+    let value = self.eval(&call.value);
+    let args: Vec<_> = call.args.iter().map(|e| self.eval(e)).collect();
+
+    let Some(arm) = self.inlinable(&value, &args) else {
+      return Expression::Call(Call {
+        value: Ptr::new(value),
+        args,
+        info: call.info.clone(),
+      });
+    };
+
+    // Arguments that aren't values are bound with `let`,
+    // to preserve when and how often they're evaluated.
+    let mut known = Bindings::new();
+    let mut statements = vec![];
+    for (param, arg) in arm.parameters.parameters.iter().zip(args) {
+      let Pattern::Var(v) = &param.pattern else {
+        unreachable!("only functions with variable parameters are inlined")
+      };
+      if is_value(&arg) {
+        known.insert(v.name.clone(), arg);
+        continue;
+      }
+      statements.push(Statement::Expression(Expression::Declare(Declare {
+        doc: None,
+        pattern: param.pattern.clone(),
+        annotation: param.annotation.clone(),
+        info: param.info.clone(),
+        value: Ptr::new(arg),
+      })));
+    }
+
+    let outer = std::mem::replace(&mut self.known, known);
+    self.depth += 1;
+    let returned = self.eval(&arm.returned);
+    self.depth -= 1;
+    self.known = outer;
+
+    if statements.is_empty() {
+      return returned;
+    }
+    statements.push(Statement::Expression(returned));
+    Expression::Block(Block {
+      statements,
+      info: call.info.clone(),
+    })
+  }
+
+  /// The arm of `value` to inline, if `value` is a function that can be inlined with `args`
+  fn inlinable(&self, value: &Expression<I>, args: &[Expression<I>]) -> Option<FunctionArm<I>> {
+    let Expression::Function(func) = value else {
+      return None;
+    };
+    let [arm] = func.arms.as_slice() else {
+      return None;
+    };
+    let is_vars = arm
+      .parameters
+      .parameters
+      .iter()
+      .all(|param| matches!(param.pattern, Pattern::Var(_)));
+    // arguments are bound in order, so one referring to a parameter's name would see that parameter
+    let mut params = HashSet::new();
+    for param in &arm.parameters.parameters {
+      for_each_binding(&param.pattern, &mut |name| {
+        params.insert(name.clone());
+      });
+    }
+    let captured = args.iter().any(|arg| {
+      let mut names = HashSet::new();
+      free(arg, &mut vec![], &mut names);
+      !names.is_disjoint(&params)
+    });
+    let ok = self.depth < self.max_depth
+      && arm.parameters.parameters.len() == args.len()
+      && is_vars
+      && !captured
+      && is_value(value);
+    ok.then(|| arm.clone())
+  }
+
+  fn infix(&mut self, infix: &Infix<I>) -> Expression<I> {
+    let value = self.eval(&infix.value);
+    let other = self.eval(&infix.other);
+    if let (Expression::Float(lhs), Expression::Float(rhs), Name::Symbol(sym)) =
+      (&value, &other, &infix.name.name)
+    {
+      let folded = match sym {
+        Symbol::Plus => Some(lhs.value + rhs.value),
+        Symbol::Minus => Some(lhs.value - rhs.value),
+        Symbol::Times => Some(lhs.value * rhs.value),
+        Symbol::Divide => Some(lhs.value / rhs.value),
+        _ => None,
+      };
+      if let Some(value) = folded {
+        return Expression::Float(Float {
+          value,
+          info: infix.info.clone(),
+        });
+      }
+    }
+    Expression::Infix(Infix {
+      value: Ptr::new(value),
+      name: infix.name.clone(),
+      other: Ptr::new(other),
+      info: infix.info.clone(),
+    })
+  }
+
+  fn prefix(&mut self, prefix: &Prefix<I>) -> Expression<I> {
+    let value = self.eval(&prefix.value);
+    if let Expression::Float(f) = &value {
+      let folded = match prefix.name.sym {
+        Symbol::Plus => Some(f.value),
+        Symbol::Minus => Some(-f.value),
+        _ => None,
+      };
+      if let Some(value) = folded {
+        return Expression::Float(Float {
+          value,
+          info: prefix.info.clone(),
+        });
+      }
+    }
+    Expression::Prefix(Prefix {
+      name: prefix.name.clone(),
+      value: Ptr::new(value),
+      info: prefix.info.clone(),
+    })
+  }
+}
@@ -0,0 +1,109 @@
+//! Finding the variables that expressions and patterns refer to.
+use diom_syntax::{
+  expressions::{Expression, Statement},
+  idents::LitName,
+  patterns::{arrays::ArrayItem, structs::StructItem, tuples::TupleItem, Pattern},
+};
+use std::collections::HashSet;
+
+/// Calls `f` on each expression directly nested within `expr`
+pub fn for_each_child<I>(expr: &Expression<I>, mut f: impl FnMut(&Expression<I>)) {
+  use Expression::*;
+  match expr {
+    Char(_) | Float(_) | String(_) | Var(_) | Error(_) => {}
+    Group(g) => f(&g.value),
+    Block(b) => b.statements.iter().for_each(|stmt| match stmt {
+      Statement::Expression(e) => f(e),
+      Statement::TypeDef(_) | Statement::Use(_) => {}
+    }),
+    Assign(a) => {
+      f(&a.reference);
+      f(&a.value)
+    }
+    Declare(d) => f(&d.value),
+    Return(r) => f(&r.value),
+    Array(a) => a.contents.iter().for_each(f),
+    Function(func) => func.arms.iter().for_each(|arm| f(&arm.returned)),
+    Struct(s) => s.fields.iter().for_each(|(_, e)| f(e)),
+    Template(t) => t.values.iter().for_each(f),
+    Call(c) => {
+      f(&c.value);
+      c.args.iter().for_each(f)
+    }
+    Field(field) => f(&field.value),
+    Index(i) => {
+      f(&i.value);
+      i.keys.iter().for_each(f)
+    }
+    Infix(i) => {
+      f(&i.value);
+      f(&i.other)
+    }
+    Monad(m) => f(&m.value),
+    Prefix(p) => f(&p.value),
+    Result(r) => f(&r.value),
+  }
+}
+
+/// Calls `f` on the name of each variable bound by `pattern`
+pub fn for_each_binding<I>(pattern: &Pattern<I>, f: &mut impl FnMut(&LitName)) {
+  match pattern {
+    Pattern::Var(v) => f(&v.name),
+    Pattern::Ignored(_) | Pattern::Error(_) => {}
+    Pattern::Tagged(t) => for_each_binding(&t.value, f),
+    Pattern::Array(a) => a.items.iter().for_each(|item| match item {
+      ArrayItem::Item(p) => for_each_binding(p, f),
+      ArrayItem::Rest(r) => r.name.iter().for_each(|n| f(&n.name)),
+    }),
+    Pattern::Tuple(t) => t.fields.iter().for_each(|item| match item {
+      TupleItem::Field(p) => for_each_binding(p, f),
+      TupleItem::Rest(r) => r.name.iter().for_each(|n| f(&n.name)),
+    }),
+    Pattern::Struct(s) => s.fields.iter().for_each(|item| match item {
+      StructItem::Field(field) => for_each_binding(&field.pattern, f),
+      StructItem::Rest(r) => r.name.iter().for_each(|n| f(&n.name)),
+    }),
+  }
+}
+
+/// Collects the names of variables assigned to anywhere within `expr`
+pub fn assigned<I>(expr: &Expression<I>, names: &mut HashSet<LitName>) {
+  if let Expression::Assign(a) = expr {
+    if let Expression::Var(v) = a.reference.as_ref() {
+      names.insert(v.name.clone());
+    }
+  }
+  for_each_child(expr, |child| assigned(child, names))
+}
+
+/// Collects the variables used in `expr` that aren't bound within it
+pub fn free<I>(expr: &Expression<I>, bound: &mut Vec<LitName>, names: &mut HashSet<LitName>) {
+  match expr {
+    Expression::Var(v) => {
+      if !bound.contains(&v.name) {
+        names.insert(v.name.clone());
+      }
+    }
+    // blocks and function arms introduce their own scopes
+    Expression::Block(_) => {
+      let len = bound.len();
+      for_each_child(expr, |child| free(child, bound, names));
+      bound.truncate(len);
+    }
+    Expression::Function(func) => {
+      for arm in &func.arms {
+        let len = bound.len();
+        for param in &arm.parameters.parameters {
+          for_each_binding(&param.pattern, &mut |name| bound.push(name.clone()));
+        }
+        free(&arm.returned, bound, names);
+        bound.truncate(len);
+      }
+    }
+    Expression::Declare(d) => {
+      free(&d.value, bound, names);
+      for_each_binding(&d.pattern, &mut |name| bound.push(name.clone()));
+    }
+    _ => for_each_child(expr, |child| free(child, bound, names)),
+  }
+}
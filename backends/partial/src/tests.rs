@@ -0,0 +1,75 @@
+use crate::{partial_eval, Bindings};
+use diom_info_traits::InfoRef;
+use diom_parser::testing::parse_expr;
+use diom_syntax::expressions::{Expression, Float};
+
+/// Partially evaluates `code`, returning the displayed result
+fn fold(code: &str) -> String {
+  partial_eval(&parse_expr(code), Bindings::new()).to_string()
+}
+
+#[test]
+fn arithmetic() {
+  assert_eq!(fold("1 + 2"), "3");
+  assert_eq!(fold("(2 * 3) - 4"), "2");
+  assert_eq!(fold("- (1 + 2)"), "-3");
+  assert_eq!(fold("(1 + 2) * x"), "3 * x");
+}
+
+#[test]
+fn known_bindings() {
+  let known = Bindings::from([(
+    "x".into(),
+    Expression::Float(Float {
+      value: 4.0,
+      info: 0..0,
+    }),
+  )]);
+  let expr = partial_eval(&parse_expr("(x * 2) + y"), known);
+  assert_eq!(expr.to_string(), "8 + y");
+}
+
+#[test]
+fn inline_lets() {
+  assert_eq!(fold("(let x = 2; let y = (x * 3); y + z)"), "(6 + z)");
+  assert_eq!(fold("(let x = 2; x)"), "2");
+  // assigned variables aren't inlined
+  assert_eq!(fold("(let x = 2; x = 3; x)"), "(let x=2;x=3;x)");
+  // nor are shadowed variables
+  assert_eq!(fold("(let x = 2; (f) => x + f)"), "({(f)=>2 + f})");
+  assert_eq!(fold("(let x = 2; (x) => x + 1)"), "({(x)=>x + 1})");
+}
+
+#[test]
+fn beta_reduction() {
+  assert_eq!(fold("(let f = (a) => a * 2; (f(3)))"), "6");
+  assert_eq!(fold("(let f = (a) => a * 2; (f(y)))"), "(let a=y;a * 2)");
+  // functions referring to unknown variables aren't inlined
+  assert_eq!(
+    fold("(let f = (a) => a * y; (f(3)))"),
+    "(let f={(a)=>a * y};(f(3)))"
+  );
+  // arguments referring to the parameters' names would be captured by their bindings
+  assert_eq!(
+    fold("(let f = (a: Float b: Float) => b; f(g(), c))"),
+    "(let a:Float=(g());let b:Float=c;b)"
+  );
+  assert_eq!(
+    fold("(let f = (a: Float b: Float) => b; f(g(), a))"),
+    "({(a:Float,b:Float)=>b}(g(),a))"
+  );
+  // recursive inlining is limited
+  fold("((f) => f(f))((f) => f(f))");
+}
+
+#[test]
+fn preserves_spans() {
+  let code = "(let x = 2; x * 3) + y";
+  let expr = partial_eval(&parse_expr(code), Bindings::new());
+  let Expression::Infix(infix) = &expr else {
+    panic!("expected infix, found {expr}")
+  };
+  assert_eq!(expr.info(), &(0..22));
+  assert_eq!(infix.value.info(), &(0..18));
+  assert_eq!(&code[infix.other.info().clone()], "y");
+}
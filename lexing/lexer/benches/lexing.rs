@@ -0,0 +1,63 @@
+//! Benchmarks lexing large generated sources with both lexers.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use diom_lexer::{fast::parse_tokens_fast, parse_tokens};
+use nom::{error::Error, Parser};
+use std::fmt::Write;
+
+/// Generates a config file, as a struct of `entries` nested structs
+fn config(entries: usize) -> String {
+  let mut code = String::from("let config = {\n");
+  for i in 0..entries {
+    writeln!(
+      code,
+      "  entry_{i}: {{ name: \"entry {i}\", id: 0x{i:x}, weight: {}.5, enabled: '{}', tags: [1_000, 2e3] }},",
+      i % 97,
+      if i % 2 == 0 { 'y' } else { 'n' },
+    )
+    .unwrap();
+  }
+  code.push_str("};\n");
+  code
+}
+
+/// Generates a program of `functions` small functions
+fn program(functions: usize) -> String {
+  let mut code = String::new();
+  for i in 0..functions {
+    writeln!(
+      code,
+      "# function number {i}\nlet f_{i} = (x, y) => (\n  let z = x * {i} + y / 2;\n  z >= 10 & z != 42\n);"
+    )
+    .unwrap();
+  }
+  code
+}
+
+fn bench_lexers(c: &mut Criterion) {
+  let mut group = c.benchmark_group("lexing");
+  let sources = [
+    ("config", config(1_000)),
+    ("config", config(10_000)),
+    ("program", program(1_000)),
+    ("program", program(10_000)),
+  ];
+
+  for (name, code) in &sources {
+    let id = format!("{name}/{}KiB", code.len() / 1024);
+    group.throughput(Throughput::Bytes(code.len() as u64));
+    group.bench_with_input(BenchmarkId::new("nom", &id), code, |b, code| {
+      b.iter(|| parse_tokens::<Error<_>>().parse(code.as_str()).unwrap())
+    });
+    group.bench_with_input(BenchmarkId::new("fast", &id), code, |b, code| {
+      b.iter(|| {
+        parse_tokens_fast::<Error<_>>()
+          .parse(code.as_str())
+          .unwrap()
+      })
+    });
+  }
+  group.finish();
+}
+
+criterion_group!(benches, bench_lexers);
+criterion_main!(benches);
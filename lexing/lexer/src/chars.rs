@@ -1,13 +1,13 @@
 //! Character parsers, mostly written from the [nom string example](https://github.com/rust-bakery/nom/blob/main/examples/string.rs)
-use diom_tokens::{SpanToken, Token};
 use nom::{
   branch::alt,
-  bytes::complete::{tag, take_while_m_n},
-  character::complete::{char, multispace1, none_of},
-  combinator::{consumed, recognize, value},
+  bytes::complete::{tag, take_while, take_while_m_n},
+  character::complete::{anychar, char, line_ending, multispace1, none_of, space0},
+  combinator::value,
+  error::ErrorKind,
   multi::many0,
   sequence::{delimited, preceded, terminated},
-  Parser,
+  Err, Parser,
 };
 
 use crate::{errors::TokensError, In};
@@ -23,7 +23,8 @@ fn unicode_char<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = char,
 }
 
 /// Parses an escaped character, of the form `\t`, `\n` or `\u{fe0e}`
-fn escaped_char<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = char, Error = E> {
+pub(crate) fn escaped_char<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = char, Error = E>
+{
   let parse_datum = alt((
     unicode_char(),
     value('\0', char('0')),
@@ -39,7 +40,8 @@ fn escaped_char<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = char,
 }
 
 /// Parses whitespace escaped with a `\`
-fn escaped_eol<'a, E: TokensError<'a>>() -> impl Parser<&'a str, Output = (), Error = E> {
+pub(crate) fn escaped_eol<'a, E: TokensError<'a>>() -> impl Parser<&'a str, Output = (), Error = E>
+{
   value((), preceded(char('\\'), multispace1))
 }
 
@@ -69,7 +71,7 @@ pub fn enclosed_char<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = c
   delimited(char('\''), parse_contents, char('\''))
 }
 
-/// Parses a string as an array of characters:
+/// Parses the characters within a string, handling escapes:
 /// ```_,ignore
 /// assert "hello world" == ['h','e','l','l','o',' ','w','o','r','l','d',];
 /// assert "foo\
@@ -97,51 +99,87 @@ pub fn parse_string<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = Ve
   delimited(char('"'), parse_content, char('"'))
 }
 
-pub fn parse_span_string<'a, E: TokensError<'a>>(
-) -> impl Parser<In<'a>, Output = Vec<SpanToken<'a>>, Error = E> {
-  let parse_single = consumed(alt((
-    escaped_char(),
-    value('"', tag("\\\"")),
-    none_of("\""),
-  )))
-  .map(|(origin, ch)| SpanToken {
-    token: Token::Char(ch),
-    origin,
-  });
+/// Whether `input` starts with the opening of a raw string, i.e. `r#"`
+pub(crate) fn is_raw_string(input: In<'_>) -> bool {
+  input
+    .strip_prefix('r')
+    .is_some_and(|rest| rest.trim_start_matches('#').starts_with('"'))
+}
+
+/// Parses a raw string, where backslashes aren't escapes, using the syntax:
+/// ```_
+/// r"C:\path\to\file";
+/// r#"{"key": "value"}"#;
+/// r##"a "# within a raw string"##;
+/// ```
+/// The string ends at the first `"` followed by as many `#`s as it started with.
+pub fn parse_raw_string<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = &'a str, Error = E>
+{
+  move |input: In<'a>| {
+    let (rest, hashes) = delimited(char('r'), take_while(|c| c == '#'), char('"')).parse(input)?;
+    let close = format!("\"{hashes}");
+    match rest.find(&close) {
+      Some(end) => Ok((&rest[end + close.len()..], &rest[..end])),
+      None => Err(Err::Failure(E::from_error_kind(input, ErrorKind::Char))),
+    }
+  }
+}
 
-  let parse_content = preceded(
+/// Parses a multi-line text block, using the syntax:
+/// ```_
+/// let json = """
+///   {
+///     "key": "value"
+///   }
+///   """;
+/// ```
+/// The opening `"""` ends its line, and the closing `"""` starts its own line.\
+/// The indentation of the closing `"""` is stripped from each line,
+/// and escapes are handled the same as in strings.
+pub fn parse_text_block<'a, E: TokensError<'a>>(
+) -> impl Parser<In<'a>, Output = std::string::String, Error = E> {
+  move |input: In<'a>| {
+    let (mut rest, _) = (tag("\"\"\""), space0, line_ending).parse(input)?;
+    let mut lines = vec![];
+    loop {
+      let line = rest.split('\n').next().unwrap_or(rest);
+      let trimmed = line.trim_start_matches([' ', '\t']);
+      if let Some(after) = trimmed.strip_prefix("\"\"\"") {
+        let indent = &line[..line.len() - trimmed.len()];
+        let content: Vec<_> = lines
+          .into_iter()
+          .map(|line: In<'a>| line.strip_suffix('\r').unwrap_or(line))
+          // lines with less indentation than the closing `"""` lose all of it
+          .map(|line| line.strip_prefix(indent).unwrap_or(line.trim_start()))
+          .collect();
+        let rest = &rest[line.len() - after.len()..];
+        return Ok((rest, unescape(&content.join("\n"))));
+      }
+      lines.push(line);
+      rest = match rest.get(line.len() + 1..) {
+        Some(next) => next,
+        None => return Err(Err::Failure(E::from_error_kind(input, ErrorKind::Tag))),
+      };
+    }
+  }
+}
+
+/// Handles escapes in the content of a text block, the same as within a string
+fn unescape(content: In<'_>) -> std::string::String {
+  let parse_single = alt((escaped_char::<()>(), value('"', tag("\\\"")), anychar));
+  let mut parse_content = terminated(
+    many0(preceded(many0(escaped_eol()), parse_single)),
     many0(escaped_eol()),
-    many0(
-      parse_single.and(recognize(many0(escaped_eol())).map(|origin| SpanToken {
-        token: Token::Comma,
-        origin,
-      })),
-    ),
   );
-
-  let parse_lbrac = recognize(char('"')).map(|origin| SpanToken {
-    token: Token::LBrace,
-    origin,
-  });
-  let parse_rbrac = recognize(char('"')).map(|origin| SpanToken {
-    token: Token::RBrace,
-    origin,
-  });
-
-  parse_lbrac
-    .and(parse_content)
-    .and(parse_rbrac)
-    .map(|((lbrac, content), rbrac)| {
-      std::iter::once(lbrac)
-        .chain(content.into_iter().flat_map(|(tok, com)| [tok, com]))
-        .chain(std::iter::once(rbrac))
-        .collect::<Vec<_>>()
-    })
+  let (_, chars) = parse_content
+    .parse(content)
+    .expect("every character is valid in a text block");
+  chars.into_iter().collect()
 }
 
 #[cfg(test)]
 mod test {
-  use super::{enclosed_char, parse_string};
+  use super::{enclosed_char, parse_raw_string, parse_string, parse_text_block};
   use crate::tests::TestResult;
 
   mod char {
@@ -286,4 +324,83 @@ mod test {
       Ok(())
     }
   }
+
+  mod raw_string {
+    use super::*;
+    use nom::{combinator::all_consuming, Parser};
+
+    #[test]
+    fn no_hashes() -> TestResult<'static, ()> {
+      let (_, res) = all_consuming(parse_raw_string()).parse(r#"r"C:\temp\n""#)?;
+      assert_eq!(res, r"C:\temp\n");
+      Ok(())
+    }
+
+    #[test]
+    fn hashes() -> TestResult<'static, ()> {
+      let (_, res) = all_consuming(parse_raw_string()).parse(r###"r##"a "# b"##"###)?;
+      assert_eq!(res, r##"a "# b"##);
+      Ok(())
+    }
+
+    #[test]
+    fn multi_line() -> TestResult<'static, ()> {
+      let (_, res) = all_consuming(parse_raw_string()).parse("r\"a\n  b\"")?;
+      assert_eq!(res, "a\n  b");
+      Ok(())
+    }
+
+    #[test]
+    fn unterminated() {
+      let res = parse_raw_string::<nom::error::Error<_>>().parse(r##"r#"abc" + 1"##);
+      assert!(matches!(res, Err(nom::Err::Failure(_))));
+    }
+  }
+
+  mod text_block {
+    use super::*;
+    use nom::{combinator::all_consuming, Parser};
+
+    #[test]
+    fn unindented() -> TestResult<'static, ()> {
+      let input = "\"\"\"\nline 1\nline 2\n\"\"\"";
+      let (_, res) = all_consuming(parse_text_block()).parse(input)?;
+      assert_eq!(res, "line 1\nline 2");
+      Ok(())
+    }
+
+    #[test]
+    fn indented() -> TestResult<'static, ()> {
+      let input = r#""""
+        {
+          "key": "value"
+
+        }
+        """"#;
+      let (_, res) = all_consuming(parse_text_block()).parse(input)?;
+      assert_eq!(res, "{\n  \"key\": \"value\"\n\n}");
+      Ok(())
+    }
+
+    #[test]
+    fn escapes() -> TestResult<'static, ()> {
+      let input = "\"\"\"  \r\n  a\\tb \\\"\"\"\r\n  c\\\n  d\r\n  \"\"\" + 1";
+      let (rest, res) = parse_text_block().parse(input)?;
+      assert_eq!(rest, " + 1");
+      assert_eq!(res, "a\tb \"\"\"\ncd");
+      Ok(())
+    }
+
+    #[test]
+    fn unterminated() {
+      let res = parse_text_block::<nom::error::Error<_>>().parse("\"\"\"\n  a\n  \"");
+      assert!(matches!(res, Err(nom::Err::Failure(_))));
+    }
+
+    #[test]
+    fn not_a_block() {
+      let res = parse_text_block::<nom::error::Error<_>>().parse("\"\"\"a\"\"\"");
+      assert!(matches!(res, Err(nom::Err::Error(_))));
+    }
+  }
 }
@@ -1,9 +1,11 @@
 use nom::{
   branch::alt,
-  bytes::complete::{is_not, tag, take_until},
-  character::complete::{char, line_ending},
+  bytes::complete::{is_not, tag},
+  character::complete::{char, line_ending, not_line_ending},
+  combinator::eof,
+  error::ErrorKind,
   sequence::delimited,
-  Parser,
+  Err, Parser,
 };
 
 use crate::{errors::TokensError, In};
@@ -14,21 +16,54 @@ use crate::{errors::TokensError, In};
 /// let i = 0;
 /// ```
 fn line_comment<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = &'a str, Error = E> {
-  delimited(char('#'), is_not("\r\n"), line_ending)
+  delimited(char('#'), is_not("\r\n"), alt((line_ending, eof)))
 }
 
 /// Parses a comment that spans from an opening "bracket" to a closing "bracket"
-/// potentially over multiple lines.
+/// potentially over multiple lines.\
+/// Block comments can be nested, so that code containing them can be commented out.
 /// ```_
 /// #(
 /// The loop index starts at 0
 /// and every loop iteration it will be incremented
 /// the sum will be calculated up until the loop index  
+/// #( nested comments are closed first )#
 /// )#
 /// let i = 0;
 /// ```
 fn parse_block<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = &'a str, Error = E> {
-  delimited(tag("#("), take_until(")#"), tag(")#"))
+  move |input: In<'a>| {
+    let (rest, _) = tag("#(").parse(input)?;
+    let mut depth = 1usize;
+    let mut i = 0;
+    // both brackets are ascii, so byte offsets are always at character boundaries
+    while let Some(pair) = rest.as_bytes().get(i..i + 2) {
+      match pair {
+        b"#(" => depth += 1,
+        b")#" => depth -= 1,
+        _ => {
+          i += 1;
+          continue;
+        }
+      }
+      if depth == 0 {
+        return Ok((&rest[i + 2..], &rest[..i]));
+      }
+      i += 2;
+    }
+    // once `#(` is found, this can't fall back to a line comment
+    Err(Err::Failure(E::from_error_kind(rest, ErrorKind::TakeUntil)))
+  }
+}
+
+/// Parses a doc comment, documenting the declaration or type definition after it
+/// ```_
+/// ## The number of loop iterations
+/// let n = 10;
+/// ```
+pub fn parse_doc_comment<'a, E: TokensError<'a>>(
+) -> impl Parser<In<'a>, Output = &'a str, Error = E> {
+  delimited(tag("##"), not_line_ending, alt((line_ending, eof)))
 }
 
 /// Parses either a `# line comment`, or a `#( block comment )#`
@@ -38,7 +73,7 @@ pub fn parse_comment<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = &
 
 #[cfg(test)]
 mod test {
-  use super::parse_comment;
+  use super::{parse_comment, parse_doc_comment};
   use crate::tests::TestResult;
   use nom::Parser;
 
@@ -92,6 +127,7 @@ mod test {
     \"\n\
     \'\n\
     )#\n\
+    )#\n\
     log.info(\"end of comment\")\
     ";
     let (rest, comment) = parse_comment().parse(input)?;
@@ -108,8 +144,32 @@ mod test {
         )\n\
         \"\n\
         \'\n\
+        )#\n\
         "
     );
     Ok(())
   }
+
+  #[test]
+  fn block_nested() -> TestResult<'static, ()> {
+    let input = "#( outer #( inner )# still outer )# x";
+    let (rest, comment) = parse_comment().parse(input)?;
+    assert_eq!(rest, " x");
+    assert_eq!(comment, " outer #( inner )# still outer ");
+    Ok(())
+  }
+
+  #[test]
+  fn block_unterminated() {
+    let result = parse_comment::<nom::error::Error<_>>().parse("#( outer #( inner )# x");
+    assert!(matches!(result, Err(nom::Err::Failure(_))));
+  }
+
+  #[test]
+  fn doc() -> TestResult<'static, ()> {
+    let (rest, doc) = parse_doc_comment().parse("## The answer\nlet x = 42;")?;
+    assert_eq!(rest, "let x = 42;");
+    assert_eq!(doc, " The answer");
+    Ok(())
+  }
 }
@@ -0,0 +1,97 @@
+//! Warnings for identifiers that could be mistaken for other identifiers.
+//!
+//! These follow the checks from Unicode Technical Standard #39:
+//! - identifiers that mix scripts, other than characters common to all scripts
+//! - distinct identifiers with the same confusable skeleton, where either isn't ASCII
+use crate::errors::LexWarning;
+use diom_tokens::{SpanToken, Token};
+use std::collections::HashMap;
+use unicode_security::{skeleton, MixedScript};
+
+/// Finds identifiers in `tokens` that could be mistaken for another identifier
+///
+/// ```
+/// # use diom_lexer::{confusables::ident_warnings, errors::LexWarning, parse_tokens};
+/// # use nom::Parser;
+/// // the first `а` is Cyrillic
+/// let code = "let pаth = path";
+/// let (_, tokens) = parse_tokens::<nom::error::Error<_>>().parse(code).unwrap();
+/// assert_eq!(
+///   ident_warnings(&tokens),
+///   vec![
+///     LexWarning::MixedScript { origin: "pаth" },
+///     LexWarning::Confusable { origin: "path", other: "pаth" },
+///   ]
+/// );
+/// ```
+pub fn ident_warnings<'a>(tokens: &[SpanToken<'a>]) -> Vec<LexWarning<'a>> {
+  let mut warnings = vec![];
+  // the first identifier seen with each skeleton
+  let mut skeletons: HashMap<String, (&str, &'a str)> = HashMap::new();
+  for token in tokens {
+    let Token::StringIdent(name) = &token.token else {
+      continue;
+    };
+    let origin = token.origin;
+    if !name.as_ref().is_single_script() {
+      warnings.push(LexWarning::MixedScript { origin });
+    }
+    let (other_name, other) = *skeletons
+      .entry(skeleton(name).collect())
+      .or_insert((name, origin));
+    if other_name != name.as_ref() && !(other_name.is_ascii() && name.is_ascii()) {
+      warnings.push(LexWarning::Confusable { origin, other });
+    }
+  }
+  warnings
+}
+
+#[cfg(test)]
+mod test {
+  use super::ident_warnings;
+  use crate::{errors::LexWarning, parse_tokens, tests::LexError};
+  use nom::Parser;
+
+  fn warnings(code: &str) -> Vec<LexWarning<'_>> {
+    let (_, tokens) = parse_tokens::<LexError>().parse(code).unwrap();
+    ident_warnings(&tokens)
+  }
+
+  #[test]
+  fn single_script() {
+    assert_eq!(warnings("let größe = μ + λ + 変数"), vec![]);
+  }
+
+  #[test]
+  fn mixed_script() {
+    // the `о` is Cyrillic
+    assert_eq!(
+      warnings("let nоne = 1"),
+      vec![LexWarning::MixedScript { origin: "nоne" }]
+    );
+  }
+
+  #[test]
+  fn confusable() {
+    // `аре` is all Cyrillic, so is a single script
+    assert_eq!(
+      warnings("ape + аре"),
+      vec![LexWarning::Confusable {
+        origin: "аре",
+        other: "ape"
+      }]
+    );
+  }
+
+  #[test]
+  fn ascii_confusable() {
+    // `rn` and `m` are confusable, but ASCII names are never warned about
+    assert_eq!(warnings("rn + m"), vec![]);
+  }
+
+  #[test]
+  fn normalised() {
+    // the same name in different normal forms isn't confusable
+    assert_eq!(warnings("caf\u{e9} + cafe\u{301}"), vec![]);
+  }
+}
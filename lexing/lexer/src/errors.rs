@@ -15,3 +15,79 @@ impl<
   > TokensError<'a> for E
 {
 }
+
+/// The kinds of problems found whilst lexing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexErrorKind {
+  /// Characters that don't start any token
+  Unrecognised,
+  /// A character literal without a closing `'`
+  UnterminatedChar,
+  /// A string without a closing `"`
+  UnterminatedString,
+  /// A number with invalid digits, i.e. `0b12`, or directly followed by letters, i.e. `1st`
+  InvalidNumber,
+  /// A number too large to be represented, i.e. `0x1_0000_0000_0000_0000`
+  NumberOverflow,
+  /// A template without a closing `"`, or with an invalid interpolated value
+  InvalidTemplate,
+  /// A `#(` block comment without a closing `)#`
+  UnterminatedComment,
+}
+
+/// A problem found whilst lexing, along with the source it applies to
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexError<'a> {
+  pub kind: LexErrorKind,
+  pub origin: In<'a>,
+}
+
+impl std::fmt::Display for LexError<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self.kind {
+      LexErrorKind::Unrecognised => write!(f, "unrecognised characters `{}`", self.origin),
+      LexErrorKind::UnterminatedChar => f.write_str("unterminated character, expected a `'`"),
+      LexErrorKind::UnterminatedString => f.write_str("unterminated string, expected a `\"`"),
+      LexErrorKind::InvalidNumber => write!(f, "invalid number `{}`", self.origin),
+      LexErrorKind::NumberOverflow => write!(f, "number `{}` is too large", self.origin),
+      LexErrorKind::InvalidTemplate => {
+        f.write_str("invalid template, expected `{values}` and a closing `\"`")
+      }
+      LexErrorKind::UnterminatedComment => f.write_str("unterminated block comment, expected `)#`"),
+    }
+  }
+}
+
+/// Suspicious, but valid, source found whilst lexing
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexWarning<'a> {
+  /// An identifier that mixes characters from different scripts, i.e. a Cyrillic `а` in `pаth`
+  MixedScript { origin: In<'a> },
+  /// An identifier that looks the same as a different identifier, i.e. `pаth` and `path`
+  Confusable { origin: In<'a>, other: In<'a> },
+}
+
+impl<'a> LexWarning<'a> {
+  /// The source the warning applies to
+  pub fn origin(&self) -> In<'a> {
+    match self {
+      LexWarning::MixedScript { origin } | LexWarning::Confusable { origin, .. } => origin,
+    }
+  }
+}
+
+impl std::fmt::Display for LexWarning<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      LexWarning::MixedScript { origin } => {
+        write!(
+          f,
+          "identifier `{origin}` mixes characters from different scripts"
+        )
+      }
+      LexWarning::Confusable { origin, other } => {
+        write!(f, "identifier `{origin}` can be confused with `{other}`")
+      }
+    }
+  }
+}
@@ -0,0 +1,154 @@
+//! A faster lexer, that picks how to lex each token from its first byte.
+//!
+//! `parse_token` tries each kind of token in turn, so a token late in its `alt` chain,\
+//! like a number, is only lexed after every other kind of token has failed.\
+//! Instead, this looks at the first byte of each token to find the only kind it can be:
+//! - punctuation, operators and identifiers are lexed by hand
+//! - numbers, strings, comments and templates use the same parsers as `parse_token`
+//!
+//! The tokens lexed are identical to those from `parse_tokens`.
+use crate::{
+  chars::{enclosed_char, is_raw_string, parse_raw_string, parse_string, parse_text_block},
+  comments::{parse_comment, parse_doc_comment},
+  errors::TokensError,
+  idents::{ident_len, ident_token},
+  numbers::parse_number,
+  span_wrap,
+  templates::parse_template,
+  In,
+};
+use diom_tokens::{SpanToken, Token};
+use nom::{Err, Parser};
+
+/// Parses whitespace separated tokens, the same as `parse_tokens`, but faster
+///
+/// ```
+/// # use diom_lexer::{fast::parse_tokens_fast, parse_tokens};
+/// # use nom::Parser;
+/// let code = "let x = { key: 0x1f, name: \"x\" }";
+/// assert_eq!(
+///   parse_tokens_fast::<nom::error::Error<_>>().parse(code),
+///   parse_tokens().parse(code),
+/// );
+/// ```
+pub fn parse_tokens_fast<'a, E: TokensError<'a>>(
+) -> impl Parser<In<'a>, Output = Vec<SpanToken<'a>>, Error = E> {
+  move |input: In<'a>| {
+    let mut tokens = vec![];
+    let mut end = skip_whitespace(input);
+    let mut input = end;
+    // whitespace after the last token is left unconsumed
+    while let Some(rest) = lex_token(input, &mut tokens)? {
+      end = rest;
+      input = skip_whitespace(rest);
+    }
+    Ok((end, tokens))
+  }
+}
+
+fn skip_whitespace(input: In<'_>) -> In<'_> {
+  input.trim_start_matches([' ', '\t', '\r', '\n'])
+}
+
+/// Lexes the token at the start of `input` into `tokens`,\
+/// returning the input after it, or `None` when no token starts `input`.
+fn lex_token<'a, E: TokensError<'a>>(
+  input: In<'a>,
+  tokens: &mut Vec<SpanToken<'a>>,
+) -> Result<Option<In<'a>>, Err<E>> {
+  let bytes = input.as_bytes();
+  let Some(&first) = bytes.first() else {
+    return Ok(None);
+  };
+  let second = bytes.get(1).copied();
+
+  let (token, len) = match (first, second) {
+    // Brackets
+    (b'(', _) => (Token::LParen, 1),
+    (b')', _) => (Token::RParen, 1),
+    (b'{', _) => (Token::LCurly, 1),
+    (b'}', _) => (Token::RCurly, 1),
+    (b'[', _) => (Token::LBrace, 1),
+    (b']', _) => (Token::RBrace, 1),
+    // Functions
+    (b'=', Some(b'>')) => (Token::Function, 2),
+    // Operators
+    (b'!', Some(b'=')) => (Token::Ne, 2),
+    (b'=', Some(b'=')) => (Token::Eq, 2),
+    (b'<', Some(b'=')) => (Token::LtEq, 2),
+    (b'>', Some(b'=')) => (Token::GtEq, 2),
+    (b'<', _) => (Token::Lt, 1),
+    (b'>', _) => (Token::Gt, 1),
+    (b'!', _) => (Token::Not, 1),
+    (b'&', _) => (Token::And, 1),
+    (b'|', _) => (Token::Or, 1),
+    (b'+', _) => (Token::Plus, 1),
+    (b'-', _) => (Token::Minus, 1),
+    (b'*', _) => (Token::Times, 1),
+    (b'/', _) => (Token::Divide, 1),
+    (b'?', _) => (Token::Monad, 1),
+    // Punctuation
+    (b'.', _) if input.starts_with("...") => (Token::Ellipses, 3),
+    (b'.', _) => (Token::Dot, 1),
+    (b';', _) => (Token::Semi, 1),
+    (b':', _) => (Token::Colon, 1),
+    (b',', _) => (Token::Comma, 1),
+    (b'=', _) => (Token::Assign, 1),
+    // String-like
+    (b'\'', _) => return lex_with(input, tokens, enclosed_char().map(Token::Char)),
+    (b'"', _) => {
+      if input.starts_with("\"\"\"") {
+        let parser = parse_text_block().map(|text| Token::String(text.into()));
+        if let Some(rest) = lex_with(input, tokens, parser)? {
+          return Ok(Some(rest));
+        }
+      }
+      let parser = parse_string().map(|chars| Token::String(chars.into_iter().collect()));
+      return lex_with(input, tokens, parser);
+    }
+    (b'r', Some(b'"' | b'#')) if is_raw_string(input) => {
+      let parser = parse_raw_string().map(|text| Token::String(text.into()));
+      return lex_with(input, tokens, parser);
+    }
+    (b'#', Some(b'#')) => {
+      let parser = parse_doc_comment().map(Box::from).map(Token::DocComment);
+      return lex_with(input, tokens, parser);
+    }
+    (b'#', _) => {
+      let parser = parse_comment().map(Box::from).map(Token::Comment);
+      return lex_with(input, tokens, parser);
+    }
+    (b'f', Some(b'"')) => {
+      let (rest, template) = parse_template(input)?;
+      tokens.extend(template);
+      return Ok(Some(rest));
+    }
+    (b'a'..=b'z' | b'A'..=b'Z' | b'_' | 0x80.., _) => match ident_len(input) {
+      0 => return Ok(None),
+      len => (ident_token(&input[..len]), len),
+    },
+    // Value-like
+    (b'0'..=b'9', _) => return lex_with(input, tokens, parse_number()),
+    _ => return Ok(None),
+  };
+
+  let (origin, rest) = input.split_at(len);
+  tokens.push(SpanToken::new(token, origin));
+  Ok(Some(rest))
+}
+
+/// Lexes the token at the start of `input` into `tokens`, with a `parser` for its kind
+fn lex_with<'a, E: TokensError<'a>>(
+  input: In<'a>,
+  tokens: &mut Vec<SpanToken<'a>>,
+  parser: impl Parser<In<'a>, Output = Token, Error = E>,
+) -> Result<Option<In<'a>>, Err<E>> {
+  match span_wrap(parser).parse(input) {
+    Ok((rest, token)) => {
+      tokens.push(token);
+      Ok(Some(rest))
+    }
+    Err(Err::Error(_)) => Ok(None),
+    Err(err) => Err(err),
+  }
+}
@@ -0,0 +1,191 @@
+//! Syntax highlighting of source, consistent with how it's lexed.
+//!
+//! Source is lexed with `recover_tokens`, so code with lexing errors still highlights,\
+//! with the invalid sections classed as `Class::Error`.
+use crate::{recovery::recover_tokens, In};
+use diom_tokens::highlight::{classify, Class};
+use nom::Offset;
+use std::fmt::Write;
+
+/// A section of source, along with how it should be highlighted
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segment<'a> {
+  pub text: In<'a>,
+  /// The class of the segment, or `None` for whitespace between tokens
+  pub class: Option<Class>,
+}
+
+/// Splits all of `source` into segments to highlight
+///
+/// ```
+/// # use diom_lexer::highlight::{highlight, Segment};
+/// # use diom_tokens::highlight::{Class, Role};
+/// let segments = highlight("f(x) $");
+/// assert_eq!(segments[0], Segment { text: "f", class: Some(Class::Ident(Role::Function)) });
+/// assert_eq!(segments[4], Segment { text: " ", class: None });
+/// assert_eq!(segments[5], Segment { text: "$", class: Some(Class::Error) });
+/// ```
+pub fn highlight(source: In<'_>) -> Vec<Segment<'_>> {
+  let (tokens, errors) = recover_tokens(source);
+  let mut classed: Vec<_> = tokens
+    .iter()
+    .map(|t| t.origin)
+    .zip(classify(&tokens))
+    .chain(errors.iter().map(|e| (e.origin, Class::Error)))
+    .collect();
+  classed.sort_by_key(|(text, _)| source.offset(text));
+
+  let mut segments = vec![];
+  let mut end = 0;
+  for (text, class) in classed {
+    let start = source.offset(text);
+    if start > end {
+      segments.push(Segment {
+        text: &source[end..start],
+        class: None,
+      });
+    }
+    segments.push(Segment {
+      text,
+      class: Some(class),
+    });
+    end = start + text.len();
+  }
+  if end < source.len() {
+    segments.push(Segment {
+      text: &source[end..],
+      class: None,
+    });
+  }
+  segments
+}
+
+/// Highlights `source` as HTML, with each token in a `<span>` with the CSS class from `Class::css_class`
+///
+/// ```
+/// # use diom_lexer::highlight::to_html;
+/// assert_eq!(
+///   to_html("x < 1"),
+///   r#"<span class="dm-variable">x</span> <span class="dm-operator">&lt;</span> <span class="dm-literal">1</span>"#
+/// );
+/// ```
+pub fn to_html(source: In<'_>) -> String {
+  let mut html = String::new();
+  for segment in highlight(source) {
+    let text = escape_html(segment.text);
+    match segment.class {
+      Some(class) => write!(html, r#"<span class="{}">{text}</span>"#, class.css_class()).unwrap(),
+      None => html.push_str(&text),
+    }
+  }
+  html
+}
+
+/// Escapes characters with special meaning in HTML
+fn escape_html(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+/// Highlights `source` for terminals, using ANSI escape codes with the styles from `Class::style`
+///
+/// ```
+/// # use diom_lexer::highlight::to_ansi;
+/// assert_eq!(to_ansi("let x"), "\x1b[35mlet\x1b[39m x");
+/// ```
+pub fn to_ansi(source: In<'_>) -> String {
+  let mut ansi = String::new();
+  for segment in highlight(source) {
+    match segment.class.map(Class::style) {
+      Some(style) if !style.is_plain() => {
+        write!(ansi, "{}{}{}", style.apply(), segment.text, style.clear()).unwrap()
+      }
+      _ => ansi.push_str(segment.text),
+    }
+  }
+  ansi
+}
+
+#[cfg(test)]
+mod test {
+  use super::{highlight, to_html};
+  use diom_tokens::highlight::{Class, Role};
+
+  fn classes(source: &str) -> Vec<(&str, Class)> {
+    highlight(source)
+      .into_iter()
+      .filter_map(|s| Some((s.text, s.class?)))
+      .collect()
+  }
+
+  #[test]
+  fn lossless() {
+    let source = "let f = (x: Int) => x.y; # comment\n## doc\n$ f\"{x}\" 'a";
+    let text: String = highlight(source).into_iter().map(|s| s.text).collect();
+    assert_eq!(text, source);
+  }
+
+  #[test]
+  fn roles() {
+    assert_eq!(
+      classes("let f: Fn = x.y(z)"),
+      vec![
+        ("let", Class::Keyword),
+        ("f", Class::Ident(Role::Variable)),
+        (":", Class::Punctuation),
+        ("Fn", Class::Ident(Role::Type)),
+        ("=", Class::Punctuation),
+        ("x", Class::Ident(Role::Variable)),
+        (".", Class::Punctuation),
+        ("y", Class::Ident(Role::Function)),
+        ("(", Class::Bracket),
+        ("z", Class::Ident(Role::Variable)),
+        (")", Class::Bracket),
+      ]
+    );
+  }
+
+  #[test]
+  fn comments_and_templates() {
+    assert_eq!(
+      classes("## doc\nf\"{a}\" # end"),
+      vec![
+        ("## doc\n", Class::DocComment),
+        ("f\"{", Class::Literal),
+        ("a", Class::Ident(Role::Variable)),
+        ("}\"", Class::Literal),
+        ("# end", Class::Comment),
+      ]
+    );
+  }
+
+  #[test]
+  fn errors() {
+    assert_eq!(
+      classes("x @ \"open"),
+      vec![
+        ("x", Class::Ident(Role::Variable)),
+        ("@", Class::Error),
+        ("\"open", Class::Error),
+      ]
+    );
+  }
+
+  #[test]
+  fn html_escapes() {
+    assert_eq!(
+      to_html("'<'"),
+      r#"<span class="dm-literal">&#39;&lt;&#39;</span>"#
+    );
+  }
+}
@@ -1,25 +1,56 @@
-use nom::{
-  branch::alt,
-  bytes::complete::tag,
-  character::complete::{alpha1, alphanumeric1},
-  combinator::recognize,
-  multi::many0,
-  Parser,
-};
-
+//! Identifiers, following the default identifier syntax of Unicode Standard Annex #31.
+//!
+//! Identifiers start with an `XID_Start` character or `_`, followed by `XID_Continue` characters.\
+//! Names are normalised to NFC, so that `é` and `e\u{301}` are the same name.
 use crate::{errors::TokensError, In};
+use diom_tokens::{keywords::keyword, Token};
+use nom::{error::ErrorKind, Err, Parser};
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+use unicode_xid::UnicodeXID;
 
 /// Parses an identifier, used for variable and type creation
 pub fn parse_ident<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = &'a str, Error = E> {
-  let parse_first = alt((alpha1, tag("_")));
-  let parse_rest = alt((alphanumeric1, tag("_")));
-  recognize(parse_first.and(many0(parse_rest)))
+  move |input: In<'a>| match ident_len(input) {
+    0 => Err(Err::Error(E::from_error_kind(input, ErrorKind::Alpha))),
+    len => Ok((&input[len..], &input[..len])),
+  }
+}
+
+/// The length in bytes of the identifier at the start of `input`, or `0` if it doesn't start one
+pub(crate) fn ident_len(input: In<'_>) -> usize {
+  let mut chars = input.char_indices();
+  match chars.next() {
+    Some((_, c)) if c == '_' || c.is_xid_start() => {}
+    _ => return 0,
+  }
+  chars
+    .find(|(_, c)| !c.is_xid_continue())
+    .map_or(input.len(), |(i, _)| i)
+}
+
+/// The token for an identifier, either a keyword or a name normalised to NFC
+pub fn ident_token(ident: &str) -> Token {
+  keyword(ident).unwrap_or_else(|| Token::StringIdent(normalise(ident)))
+}
+
+/// Whether all of `name` is lexed as a single name, rather than a keyword or anything else
+pub fn is_name(name: &str) -> bool {
+  !name.is_empty() && ident_len(name) == name.len() && keyword(name).is_none()
+}
+
+/// Normalises a name to NFC, skipping the work for names that are already normalised
+pub fn normalise(name: &str) -> Box<str> {
+  match is_nfc_quick(name.chars()) {
+    IsNormalized::Yes => name.into(),
+    _ => name.nfc().collect(),
+  }
 }
 
 #[cfg(test)]
 mod test {
-  use super::parse_ident;
+  use super::{ident_token, is_name, parse_ident};
   use crate::tests::TestResult;
+  use diom_tokens::Token;
   use nom::Parser;
 
   #[test]
@@ -42,4 +73,36 @@ mod test {
     assert_eq!(res, ("): Option<T> = ...", "Bool"));
     Ok(())
   }
+
+  #[test]
+  fn unicode() -> TestResult<'static, ()> {
+    let res = parse_ident().parse("größe_μ2 = 1")?;
+    assert_eq!(res, (" = 1", "größe_μ2"));
+    Ok(())
+  }
+
+  #[test]
+  fn not_xid_start() {
+    let res = parse_ident::<nom::error::Error<_>>().parse("2x");
+    assert!(res.is_err());
+    let res = parse_ident::<nom::error::Error<_>>().parse("\u{301}x");
+    assert!(res.is_err());
+  }
+
+  #[test]
+  fn normalised() {
+    let composed = ident_token("caf\u{e9}");
+    let decomposed = ident_token("cafe\u{301}");
+    assert_eq!(composed, Token::StringIdent("caf\u{e9}".into()));
+    assert_eq!(decomposed, composed);
+    assert_eq!(ident_token("let"), Token::Let);
+  }
+
+  #[test]
+  fn names() {
+    assert!(is_name("my_lib"));
+    assert!(!is_name("my-lib"));
+    assert!(!is_name("let"));
+    assert!(!is_name(""));
+  }
 }
@@ -0,0 +1,228 @@
+//! Incremental relexing, so that small edits to source don't re-lex the whole file.
+//!
+//! Only the tokens around an edit are lexed again, starting a token before the edit\
+//! and continuing until the lexer reaches the start of a token that was lexed before the edit.\
+//! Tokens after that point are reused, as lexing from the same text always gives the same tokens.
+use crate::{errors::TokensError, parse_token, span_wrap, templates::parse_template, In};
+use diom_tokens::{SpanToken, Token};
+use nom::{character::complete::multispace0, Err, Offset, Parser};
+use std::ops::Range;
+
+/// A replacement of a section of source with new text
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextEdit<'e> {
+  /// The byte range of the source that's replaced
+  pub range: Range<usize>,
+  /// The text that replaces the range
+  pub text: &'e str,
+}
+
+impl TextEdit<'_> {
+  /// Returns `source` with this edit applied
+  ///
+  /// ```
+  /// # use diom_lexer::incremental::TextEdit;
+  /// let edit = TextEdit { range: 4..5, text: "xs" };
+  /// assert_eq!(edit.apply("let x = 1"), "let xs = 1");
+  /// ```
+  pub fn apply(&self, source: &str) -> String {
+    [
+      &source[..self.range.start],
+      self.text,
+      &source[self.range.end..],
+    ]
+    .concat()
+  }
+
+  /// The byte range of the replacement text, once the edit is applied
+  fn new_range(&self) -> Range<usize> {
+    self.range.start..self.range.start + self.text.len()
+  }
+}
+
+/// The tokens of source after an edit
+#[derive(Clone, Debug, PartialEq)]
+pub struct Relexed<'a> {
+  pub tokens: Vec<SpanToken<'a>>,
+  /// The range of `tokens` that were lexed again,\
+  /// all other tokens were reused from before the edit.
+  pub changed: Range<usize>,
+}
+
+/// Re-lexes only the tokens affected by `edit`, where:
+/// - `tokens` were lexed from `old`, with `parse_tokens`
+/// - `new` is `old` with `edit` applied
+///
+/// The reused tokens are moved over to `new`, without any trivia.\
+/// Source after the edit that can't be lexed is an error, like it would be when lexing `new` from scratch.
+///
+/// ```
+/// # use diom_lexer::incremental::{relex, TextEdit};
+/// # use diom_lexer::parse_tokens;
+/// # use nom::Parser;
+/// let old = "let x = 1 + 2";
+/// let (_, tokens) = parse_tokens::<nom::error::Error<_>>().parse(old).unwrap();
+///
+/// let edit = TextEdit { range: 8..9, text: "10" };
+/// let new = edit.apply(old);
+/// let relexed = relex::<nom::error::Error<_>>(&tokens, old, &new, &edit).unwrap();
+///
+/// let (_, expected) = parse_tokens::<nom::error::Error<_>>().parse(&new).unwrap();
+/// assert_eq!(relexed.tokens, expected);
+/// assert_eq!(relexed.changed, 2..4);
+/// ```
+///
+/// # Panics
+///
+/// When `edit` isn't within `old`, or doesn't start and end at character boundaries.
+pub fn relex<'a, E: TokensError<'a>>(
+  tokens: &[SpanToken<'_>],
+  old: &str,
+  new: In<'a>,
+  edit: &TextEdit,
+) -> Result<Relexed<'a>, Err<E>> {
+  let starts: Vec<_> = tokens.iter().map(|t| old.offset(t.origin)).collect();
+  // tokens within templates can't be lexed on their own
+  let mut depth = 0usize;
+  let depths: Vec<_> = tokens
+    .iter()
+    .map(|t| {
+      let prev = depth;
+      match t.token {
+        Token::TemplateStart(_) => depth += 1,
+        Token::TemplateEnd(_) => depth -= 1,
+        _ => {}
+      }
+      prev
+    })
+    .collect();
+
+  let touching = (0..tokens.len())
+    .find(|&i| starts[i] + tokens[i].origin.len() >= edit.range.start)
+    .unwrap_or(tokens.len());
+  // the token before the edit can also change, i.e. inserting `5` into `1.` lexes `1.5`
+  let mut first = touching.saturating_sub(1);
+  while first > 0 && depths[first] > 0 {
+    first -= 1;
+  }
+
+  let mut relexed: Vec<_> = (0..first)
+    .map(|i| moved(&tokens[i], new, starts[i]))
+    .collect();
+  let start = if first == 0 { 0 } else { starts[first] };
+  let edited = edit.new_range();
+  let shifted = |i: usize| (starts[i] + edited.end).checked_sub(edit.range.end);
+
+  let mut parse_token = span_wrap(parse_token());
+  let mut input = &new[start..];
+  let mut reuse = first;
+  loop {
+    (input, _) = multispace0(input)?;
+    let pos = new.len() - input.len();
+
+    if pos >= edited.end {
+      // tokens that start within the edit are always skipped, as they start before `edited.end`
+      while reuse < tokens.len() && shifted(reuse).map_or(true, |s| s < pos) {
+        reuse += 1;
+      }
+      if reuse < tokens.len() && shifted(reuse) == Some(pos) && depths[reuse] == 0 {
+        let changed = first..relexed.len();
+        relexed.extend((reuse..tokens.len()).map(|i| moved(&tokens[i], new, shifted(i).unwrap())));
+        return Ok(Relexed {
+          tokens: relexed,
+          changed,
+        });
+      }
+    }
+
+    input = match parse_template(input) {
+      Ok((rest, template)) => {
+        relexed.extend(template);
+        rest
+      }
+      Err(Err::Error(_)) => match parse_token.parse(input) {
+        Ok((rest, token)) => {
+          relexed.push(token);
+          rest
+        }
+        Err(Err::Error(_)) if input.is_empty() => break,
+        Err(err) => return Err(err),
+      },
+      Err(err) => return Err(err),
+    };
+  }
+
+  Ok(Relexed {
+    changed: first..relexed.len(),
+    tokens: relexed,
+  })
+}
+
+/// Moves `token` to the same length of `source` at `start`
+fn moved<'a>(token: &SpanToken<'_>, source: In<'a>, start: usize) -> SpanToken<'a> {
+  let origin = &source[start..start + token.origin.len()];
+  SpanToken::new(token.token.clone(), origin)
+}
+
+#[cfg(test)]
+mod test {
+  use super::{relex, Relexed, TextEdit};
+  use crate::{parse_tokens, tests::LexError};
+  use diom_tokens::Token::*;
+  use nom::Parser;
+
+  fn edit(old: &str, range: std::ops::Range<usize>, text: &str) -> Relexed<'static> {
+    let (_, tokens) = parse_tokens::<LexError>().parse(old).unwrap();
+    let edit = TextEdit { range, text };
+    let new: &'static str = edit.apply(old).leak();
+    let relexed = relex::<LexError>(&tokens, old, new, &edit).unwrap();
+
+    let (_, expected) = parse_tokens::<LexError>().parse(new).unwrap();
+    assert_eq!(relexed.tokens, expected);
+    relexed
+  }
+
+  #[test]
+  fn rename() {
+    let relexed = edit("let x = y + z", 8..9, "ys");
+    assert_eq!(relexed.changed, 2..4);
+  }
+
+  #[test]
+  fn merge_tokens() {
+    let relexed = edit("let x = 1.field", 10..15, "5");
+    assert_eq!(relexed.tokens[3].token, Float(1.5));
+  }
+
+  #[test]
+  fn open_string() {
+    let relexed = edit("a + b # \"", 0..0, "\"");
+    assert_eq!(relexed.tokens[0].token, String("a + b # ".into()));
+    assert_eq!(relexed.changed, 0..1);
+  }
+
+  #[test]
+  fn open_comment() {
+    let relexed = edit("a + b; c )# + d", 4..4, "#(");
+    assert_eq!(relexed.tokens[2].token, Comment("b; c ".into()));
+    assert_eq!(relexed.changed, 1..5);
+  }
+
+  #[test]
+  fn unterminated_string() {
+    let old = "a + b; c";
+    let (_, tokens) = parse_tokens::<LexError>().parse(old).unwrap();
+    let edit = TextEdit {
+      range: 4..4,
+      text: "\"",
+    };
+    let new = edit.apply(old);
+    assert!(relex::<LexError>(&tokens, old, &new, &edit).is_err());
+  }
+
+  #[test]
+  fn within_template() {
+    let relexed = edit("x + f\"{a + b}\" + y", 11..12, "c");
+    assert_eq!(relexed.changed, 2..7);
+  }
+}
@@ -1,25 +1,34 @@
 //! Reserved keywords in the language.
 //! This includes both plain text keywords, function arrows, and assignment operators.
-use nom::{bytes::complete::tag, error::Error, Compare, Input, Parser};
+//!
+//! Keywords are looked up in the table from `diom_tokens::keywords`,\
+//! and only match whole identifiers, so `let` won't match the start of `letter`.
+use crate::{errors::TokensError, idents::parse_ident, In};
+use nom::{bytes::complete::tag, combinator::verify, Parser};
+
+pub use diom_tokens::keywords::{is_keyword, keyword, KEYWORDS};
+
+/// Parses the keyword `word`, as long as it isn't the start of a longer identifier
+fn whole_word<'a, E: TokensError<'a>>(
+  word: &'static str,
+) -> impl Parser<In<'a>, Output = In<'a>, Error = E> {
+  verify(parse_ident(), move |name: &str| name == word)
+}
 
 /// The let keyword, used for initial creation and assignment of variables
-pub fn let_keyword<I: Input + (for<'a> Compare<&'a str>)>(
-) -> impl Parser<I, Output = I, Error = Error<I>> {
-  tag("let")
+pub fn let_keyword<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = In<'a>, Error = E> {
+  whole_word("let")
 }
 /// The type keyword, used to define type aliases and data structures
-pub fn type_keyword<I: Input + (for<'a> Compare<&'a str>)>(
-) -> impl Parser<I, Output = I, Error = Error<I>> {
-  tag("type")
+pub fn type_keyword<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = In<'a>, Error = E> {
+  whole_word("type")
 }
 /// The return keyword, used to early return from blocks
-pub fn return_keyword<I: Input + (for<'a> Compare<&'a str>)>(
-) -> impl Parser<I, Output = I, Error = Error<I>> {
-  tag("return")
+pub fn return_keyword<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = In<'a>, Error = E> {
+  whole_word("return")
 }
 
 /// The function arrow, used to define anonymous functions
-pub fn function_arrow<I: Input + (for<'a> Compare<&'a str>)>(
-) -> impl Parser<I, Output = I, Error = Error<I>> {
+pub fn function_arrow<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = In<'a>, Error = E> {
   tag("=>")
 }
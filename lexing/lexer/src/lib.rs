@@ -4,33 +4,40 @@ use nom::{
   bytes::complete::tag,
   character::{complete::char, complete::multispace0},
   combinator::consumed,
-  multi::separated_list0,
-  number::complete::double,
-  sequence::preceded,
-  Parser,
+  Err, IResult, Parser,
 };
 
 pub mod chars;
 pub mod comments;
+pub mod confusables;
 pub mod errors;
+pub mod fast;
+pub mod highlight;
 pub mod idents;
+pub mod incremental;
 pub mod keywords;
+pub mod numbers;
 pub mod operators;
 pub mod parentheses;
 pub mod punctuation;
+pub mod recovery;
+pub mod stream;
 pub mod structure;
+pub mod templates;
+pub mod trivia;
 
 #[cfg(test)]
 mod tests;
 
 use crate::errors::TokensError;
-use chars::{enclosed_char, parse_span_string};
-use comments::parse_comment;
-use idents::parse_ident;
+use chars::{enclosed_char, parse_raw_string, parse_string, parse_text_block};
+use comments::{parse_comment, parse_doc_comment};
+use idents::{ident_token, parse_ident};
+use numbers::parse_number;
+use templates::parse_template;
 
 type In<'a> = &'a str;
 
-/// @note parses everything but strings, as they parse to a vector of `Token`s
 pub fn parse_token<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = Token, Error = E> {
   alt((
     // Brackets
@@ -42,12 +49,6 @@ pub fn parse_token<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = Tok
       char('[').map(|_| Token::LBrace),
       char(']').map(|_| Token::RBrace),
     )),
-    // Reserved keywords
-    alt((
-      tag("let").map(|_| Token::Let),
-      tag("type").map(|_| Token::Type),
-      tag("return").map(|_| Token::Return),
-    )),
     // Functions
     tag("=>").map(|_| Token::Function),
     // Operators
@@ -79,26 +80,74 @@ pub fn parse_token<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = Tok
     // String-like
     alt((
       enclosed_char().map(Token::Char),
+      // text blocks are lexed first, so `"""` isn't an empty string followed by a `"`
+      parse_text_block().map(|text| Token::String(text.into())),
+      parse_string().map(|chars| Token::String(chars.into_iter().collect())),
+      // raw strings are lexed before identifiers, so `r"..."` isn't an identifier `r`
+      parse_raw_string().map(|text| Token::String(text.into())),
+      // doc comments are lexed first, so `##` isn't a line comment starting with `#`
+      parse_doc_comment().map(Box::from).map(Token::DocComment),
       parse_comment().map(Box::from).map(Token::Comment),
-      parse_ident().map(Box::from).map(Token::StringIdent),
+      // keywords are lexed as identifiers first, so `letter` isn't split into `let ter`
+      parse_ident().map(ident_token),
     )),
     // Value-like
-    double.map(Token::Float),
+    parse_number(),
   ))
 }
 
-fn span_wrap<'a, E: TokensError<'a>>(
+pub(crate) fn span_wrap<'a, E: TokensError<'a>>(
   parser: impl Parser<In<'a>, Output = Token, Error = E>,
 ) -> impl Parser<In<'a>, Output = SpanToken<'a>, Error = E> {
-  consumed(parser).map(|(origin, token)| SpanToken { token, origin })
+  consumed(parser).map(|(origin, token)| SpanToken::new(token, origin))
 }
 
 pub fn parse_tokens<'a, E: TokensError<'a>>(
 ) -> impl Parser<In<'a>, Output = Vec<SpanToken<'a>>, Error = E> {
-  let parse_item = alt((
-    span_wrap(parse_token()).map(|tok| vec![tok]),
-    parse_span_string(),
-  ));
-  preceded(multispace0, separated_list0(multispace0, parse_item))
-    .map(|itemss| itemss.into_iter().flatten().collect())
+  move |input| {
+    let mut tokens = vec![];
+    let (input, _) = lex_into(input, false, &mut tokens)?;
+    Ok((input, tokens))
+  }
+}
+
+/// Lexes whitespace separated tokens into `tokens`, including those within templates.\
+/// Within a template's interpolated value, this stops at a `}` that closes the value.
+pub(crate) fn lex_into<'a, E: TokensError<'a>>(
+  input: In<'a>,
+  in_template: bool,
+  tokens: &mut Vec<SpanToken<'a>>,
+) -> IResult<In<'a>, (), E> {
+  let mut parse_token = span_wrap(parse_token());
+  let mut depth = 0usize;
+  let (mut input, _) = multispace0(input)?;
+  let mut end = input;
+  loop {
+    if in_template && depth == 0 && input.starts_with('}') {
+      return Ok((input, ()));
+    }
+    let rest = match parse_template(input) {
+      Ok((rest, template)) => {
+        tokens.extend(template);
+        rest
+      }
+      Err(Err::Error(_)) => match parse_token.parse(input) {
+        Ok((rest, token)) => {
+          match token.token {
+            Token::LCurly => depth += 1,
+            Token::RCurly => depth = depth.saturating_sub(1),
+            _ => {}
+          }
+          tokens.push(token);
+          rest
+        }
+        // whitespace after the last token is left unconsumed
+        Err(Err::Error(_)) => return Ok((end, ())),
+        Err(err) => return Err(err),
+      },
+      Err(err) => return Err(err),
+    };
+    end = rest;
+    (input, _) = multispace0(rest)?;
+  }
 }
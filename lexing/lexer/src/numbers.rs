@@ -0,0 +1,164 @@
+//! Numeric literals, i.e. `42`, `0xff`, `0b1010`, `1_000_000`, `2.5e-3`.
+//!
+//! Integers and floats lex to separate tokens, so integers are kept exact:
+//! - integers are decimal, or prefixed with `0x` (hex), `0o` (octal) or `0b` (binary)
+//! - floats are decimal, with a fraction (`2.5`) and / or an exponent (`25e-1`)
+//! - digits can be separated with `_`, after the first digit
+//!
+//! Numbers directly followed by letters or digits they can't contain, i.e. `0b12` or `1st`,
+//! are rejected instead of being split into several tokens.
+use crate::{errors::LexErrorKind, errors::TokensError, In};
+use diom_tokens::Token;
+use nom::{error::ErrorKind, Err, Parser};
+
+/// Parses an integer or float literal
+pub fn parse_number<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = Token, Error = E> {
+  move |input: In<'a>| match scan_number(input) {
+    None => Err(Err::Error(E::from_error_kind(input, ErrorKind::Digit))),
+    Some((Ok(token), len)) => Ok((&input[len..], token)),
+    // once a digit is found, this can't fall back to another token
+    Some((Err(LexErrorKind::NumberOverflow), _)) => {
+      Err(Err::Failure(E::from_error_kind(input, ErrorKind::TooLarge)))
+    }
+    Some((Err(_), _)) => Err(Err::Failure(E::from_error_kind(input, ErrorKind::Digit))),
+  }
+}
+
+/// Scans the numeric literal at the start of `input`, if there is one,\
+/// returning its token, or why it's invalid, along with its length in bytes.
+pub(crate) fn scan_number(input: In<'_>) -> Option<(Result<Token, LexErrorKind>, usize)> {
+  if !input.starts_with(|c: char| c.is_ascii_digit()) {
+    return None;
+  }
+
+  let radix = match input.get(..2) {
+    Some("0x") => Some(16),
+    Some("0o") => Some(8),
+    Some("0b") => Some(2),
+    _ => None,
+  };
+  if let Some(radix) = radix {
+    let len = 2 + word_len(&input[2..]);
+    let digits: String = input[2..len].chars().filter(|&c| c != '_').collect();
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+      return Some((Err(LexErrorKind::InvalidNumber), len));
+    }
+    return Some((integer(&digits, radix), len));
+  }
+
+  let mut len = digits_len(input);
+  let mut is_float = false;
+  // a `.` must be followed by a digit, so that `1.field` is still a field access
+  let rest = &input[len..];
+  if rest.starts_with('.') && rest[1..].starts_with(|c: char| c.is_ascii_digit()) {
+    len += 1 + digits_len(&rest[1..]);
+    is_float = true;
+  }
+  let rest = &input[len..];
+  if rest.starts_with(['e', 'E']) {
+    let sign = usize::from(rest[1..].starts_with(['+', '-']));
+    let exponent = &rest[1 + sign..];
+    if !exponent.starts_with(|c: char| c.is_ascii_digit()) {
+      return Some((
+        Err(LexErrorKind::InvalidNumber),
+        len + 1 + sign + word_len(exponent),
+      ));
+    }
+    len += 1 + sign + digits_len(exponent);
+    is_float = true;
+  }
+
+  let trailing = word_len(&input[len..]);
+  if trailing > 0 {
+    return Some((Err(LexErrorKind::InvalidNumber), len + trailing));
+  }
+
+  let digits: String = input[..len].chars().filter(|&c| c != '_').collect();
+  if !is_float {
+    return Some((integer(&digits, 10), len));
+  }
+  let value: f64 = digits
+    .parse()
+    .expect("we've only scanned valid float syntax");
+  if value.is_infinite() {
+    return Some((Err(LexErrorKind::NumberOverflow), len));
+  }
+  Some((Ok(Token::Float(value)), len))
+}
+
+fn integer(digits: &str, radix: u32) -> Result<Token, LexErrorKind> {
+  u64::from_str_radix(digits, radix)
+    .map(Token::Int)
+    .map_err(|_| LexErrorKind::NumberOverflow)
+}
+
+/// The length of the digits and separators at the start of `input`
+fn digits_len(input: In<'_>) -> usize {
+  input
+    .find(|c: char| !(c.is_ascii_digit() || c == '_'))
+    .unwrap_or(input.len())
+}
+
+/// The length of the letters, digits and separators at the start of `input`
+fn word_len(input: In<'_>) -> usize {
+  input
+    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+    .unwrap_or(input.len())
+}
+
+#[cfg(test)]
+mod test {
+  use super::scan_number;
+  use crate::errors::LexErrorKind::*;
+  use diom_tokens::Token::*;
+
+  #[test]
+  fn integers() {
+    assert_eq!(scan_number("42"), Some((Ok(Int(42)), 2)));
+    assert_eq!(scan_number("1_000_000"), Some((Ok(Int(1_000_000)), 9)));
+    assert_eq!(scan_number("0xff"), Some((Ok(Int(255)), 4)));
+    assert_eq!(scan_number("0o17"), Some((Ok(Int(15)), 4)));
+    assert_eq!(scan_number("0b1010"), Some((Ok(Int(10)), 6)));
+    assert_eq!(scan_number("0x_FF_FF"), Some((Ok(Int(0xffff)), 8)));
+  }
+
+  #[test]
+  fn floats() {
+    assert_eq!(scan_number("2.5"), Some((Ok(Float(2.5)), 3)));
+    assert_eq!(scan_number("25e-1"), Some((Ok(Float(2.5)), 5)));
+    assert_eq!(scan_number("1_0.2_5E+1"), Some((Ok(Float(102.5)), 10)));
+  }
+
+  #[test]
+  fn boundaries() {
+    assert_eq!(scan_number("1.x"), Some((Ok(Int(1)), 1)));
+    assert_eq!(scan_number("1+2"), Some((Ok(Int(1)), 1)));
+    assert_eq!(scan_number("x1"), None);
+  }
+
+  #[test]
+  fn invalid() {
+    assert_eq!(scan_number("0x"), Some((Err(InvalidNumber), 2)));
+    assert_eq!(scan_number("0b102"), Some((Err(InvalidNumber), 5)));
+    assert_eq!(scan_number("1st"), Some((Err(InvalidNumber), 3)));
+    assert_eq!(scan_number("1e"), Some((Err(InvalidNumber), 2)));
+    assert_eq!(scan_number("1e+x"), Some((Err(InvalidNumber), 4)));
+  }
+
+  #[test]
+  fn overflow() {
+    assert_eq!(
+      scan_number("18446744073709551615"),
+      Some((Ok(Int(u64::MAX)), 20))
+    );
+    assert_eq!(
+      scan_number("18446744073709551616"),
+      Some((Err(NumberOverflow), 20))
+    );
+    assert_eq!(
+      scan_number("0x1_0000_0000_0000_0000").unwrap().0,
+      Err(NumberOverflow)
+    );
+    assert_eq!(scan_number("1e309"), Some((Err(NumberOverflow), 5)));
+  }
+}
@@ -0,0 +1,178 @@
+//! Lexing that continues past invalid input.
+//!
+//! Editors need tokens for the whole of a buffer, even whilst it's being edited,\
+//! so instead of stopping at the first problem, problems are collected alongside
+//! the tokens and lexing resumes after the invalid input.
+use crate::{
+  chars::is_raw_string,
+  errors::{LexError, LexErrorKind},
+  numbers::scan_number,
+  templates::parse_template,
+  In,
+};
+use diom_tokens::SpanToken;
+use nom::{Offset, Parser};
+
+/// Lexes all of `input`, returning the tokens found and any problems in between
+///
+/// ```
+/// # use diom_lexer::{recovery::recover_tokens, errors::LexErrorKind};
+/// let (tokens, errors) = recover_tokens("x $ 2");
+/// assert_eq!(tokens.len(), 2);
+/// assert_eq!(errors[0].kind, LexErrorKind::Unrecognised);
+/// assert_eq!(errors[0].origin, "$");
+/// ```
+pub fn recover_tokens(input: In<'_>) -> (Vec<SpanToken<'_>>, Vec<LexError<'_>>) {
+  let mut tokens = vec![];
+  let mut errors: Vec<LexError> = vec![];
+  let mut parse_token = crate::span_wrap(crate::parse_token::<()>());
+
+  let mut rest = input;
+  loop {
+    rest = rest.trim_start_matches([' ', '\t', '\r', '\n']);
+    if rest.is_empty() {
+      break;
+    }
+    // invalid templates mustn't be lexed as an identifier `f` followed by a string
+    if rest.starts_with("f\"") {
+      if let Ok((after, template)) = parse_template::<()>(rest) {
+        tokens.extend(template);
+        rest = after;
+        continue;
+      }
+    } else if let Ok((after, token)) = parse_token.parse(rest) {
+      tokens.push(token);
+      rest = after;
+      continue;
+    }
+
+    let (kind, len) = diagnose(rest);
+    let (origin, after) = rest.split_at(len);
+    rest = after;
+    // merge runs of unrecognised characters into a single error
+    if let Some(last) = errors.last_mut() {
+      let end = input.offset(last.origin) + last.origin.len();
+      if kind == LexErrorKind::Unrecognised && last.kind == kind && end == input.offset(origin) {
+        last.origin = &input[input.offset(last.origin)..end + len];
+        continue;
+      }
+    }
+    errors.push(LexError { kind, origin });
+  }
+  (tokens, errors)
+}
+
+/// Finds why no token could be lexed at the start of `input`,\
+/// and how many bytes to skip before lexing can continue.
+fn diagnose(input: In<'_>) -> (LexErrorKind, usize) {
+  if input.starts_with("#(") {
+    // without a closing `)#` the rest of the input is commented out
+    return (LexErrorKind::UnterminatedComment, input.len());
+  }
+  if let Some((Err(kind), len)) = scan_number(input) {
+    return (kind, len);
+  }
+  if input.starts_with("f\"") {
+    // the end of an invalid template can't be found reliably
+    return (LexErrorKind::InvalidTemplate, input.len());
+  }
+  if input.starts_with('"') || is_raw_string(input) {
+    return (LexErrorKind::UnterminatedString, input.len());
+  }
+  if input.starts_with('\'') {
+    // character literals can't span lines, so resume on the next line
+    let len = input.find(['\r', '\n']).unwrap_or(input.len());
+    return (LexErrorKind::UnterminatedChar, len);
+  }
+  let len = input.chars().next().map_or(0, char::len_utf8);
+  (LexErrorKind::Unrecognised, len)
+}
+
+#[cfg(test)]
+mod test {
+  use super::recover_tokens;
+  use crate::errors::LexErrorKind::*;
+  use diom_tokens::Token;
+
+  #[test]
+  fn valid() {
+    let (tokens, errors) = recover_tokens("let x = 'a'; # trailing comment");
+    assert_eq!(tokens.len(), 6);
+    assert_eq!(errors, vec![]);
+  }
+
+  #[test]
+  fn unrecognised() {
+    let (tokens, errors) = recover_tokens("x $@ y ` z");
+    let tokens: Vec<_> = tokens.into_iter().map(|t| t.token).collect();
+    assert_eq!(
+      tokens,
+      vec![
+        Token::StringIdent("x".into()),
+        Token::StringIdent("y".into()),
+        Token::StringIdent("z".into()),
+      ]
+    );
+    let errors: Vec<_> = errors.iter().map(|e| (e.kind, e.origin)).collect();
+    assert_eq!(errors, vec![(Unrecognised, "$@"), (Unrecognised, "`")]);
+  }
+
+  #[test]
+  fn unterminated_char() {
+    let (tokens, errors) = recover_tokens("let c = 'a;\nlet d = 2");
+    assert_eq!(tokens.len(), 7);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+      (errors[0].kind, errors[0].origin),
+      (UnterminatedChar, "'a;")
+    );
+  }
+
+  #[test]
+  fn unterminated_string() {
+    let (tokens, errors) = recover_tokens("x + \"abc");
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+      (errors[0].kind, errors[0].origin),
+      (UnterminatedString, "\"abc")
+    );
+  }
+
+  #[test]
+  fn invalid_numbers() {
+    let (tokens, errors) = recover_tokens("1st + 0b12 + 99999999999999999999 + 0xff");
+    assert_eq!(tokens.len(), 4);
+    let errors: Vec<_> = errors.iter().map(|e| (e.kind, e.origin)).collect();
+    assert_eq!(
+      errors,
+      vec![
+        (InvalidNumber, "1st"),
+        (InvalidNumber, "0b12"),
+        (NumberOverflow, "99999999999999999999")
+      ]
+    );
+  }
+
+  #[test]
+  fn invalid_template() {
+    let (tokens, errors) = recover_tokens("x + f\"{y $}\"");
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+      (errors[0].kind, errors[0].origin),
+      (InvalidTemplate, "f\"{y $}\"")
+    );
+  }
+
+  #[test]
+  fn unterminated_comment() {
+    let (tokens, errors) = recover_tokens("x #( y\nz");
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+      (errors[0].kind, errors[0].origin),
+      (UnterminatedComment, "#( y\nz")
+    );
+  }
+}
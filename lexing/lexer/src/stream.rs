@@ -0,0 +1,413 @@
+//! Lexing of sources that arrive in chunks, such as from a file or socket.
+//!
+//! Source is buffered until whitespace, and tokens are lexed once the whitespace after them is read.\
+//! Only the source up to that whitespace can change how a token is lexed, except for tokens that span it,
+//! like strings, comments and templates, which are held back until they're closed.\
+//! Tokens are yielded as owned `StreamToken`s, with byte offsets from the start of the stream.
+use crate::{
+  errors::{LexError, LexErrorKind},
+  recovery::recover_tokens,
+};
+use diom_tokens::Token;
+use nom::Offset;
+use std::{
+  collections::VecDeque,
+  fmt::Display,
+  io::{self, Read},
+  ops::Range,
+};
+
+/// A token lexed from a stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamToken {
+  pub token: Token,
+  /// The byte range of the token, from the start of the stream
+  pub span: Range<usize>,
+}
+
+/// A problem found whilst lexing a stream
+#[derive(Debug)]
+pub enum StreamError {
+  /// The stream couldn't be read, or wasn't valid UTF-8
+  Io(io::Error),
+  /// Source that couldn't be lexed, lexing continues after it
+  Lex {
+    kind: LexErrorKind,
+    /// The byte range of the source, from the start of the stream
+    span: Range<usize>,
+    origin: Box<str>,
+  },
+}
+
+impl Display for StreamError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      StreamError::Io(err) => err.fmt(f),
+      StreamError::Lex { kind, origin, .. } => LexError {
+        kind: *kind,
+        origin,
+      }
+      .fmt(f),
+    }
+  }
+}
+
+impl std::error::Error for StreamError {}
+
+/// The default number of bytes read from a reader at a time
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Reads text from a reader in chunks, keeping characters split between reads whole
+#[derive(Debug)]
+pub struct ReadChunks<R> {
+  reader: R,
+  size: usize,
+  /// Bytes of a character that's only been partly read
+  partial: Vec<u8>,
+}
+
+impl<R: Read> ReadChunks<R> {
+  /// Reads chunks of up to `size` bytes from `reader`
+  pub fn new(reader: R, size: usize) -> Self {
+    Self {
+      reader,
+      size: size.max(1),
+      partial: vec![],
+    }
+  }
+}
+
+impl<R: Read> Iterator for ReadChunks<R> {
+  type Item = io::Result<String>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let mut buf = vec![0; self.size];
+    loop {
+      let read = match self.reader.read(&mut buf) {
+        Ok(read) => read,
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+        Err(err) => return Some(Err(err)),
+      };
+      if read == 0 {
+        if self.partial.is_empty() {
+          return None;
+        }
+        self.partial.clear();
+        return Some(Err(invalid_utf8()));
+      }
+
+      let mut bytes = std::mem::take(&mut self.partial);
+      bytes.extend_from_slice(&buf[..read]);
+      match String::from_utf8(bytes) {
+        Ok(chunk) => return Some(Ok(chunk)),
+        // the end of the chunk is the start of a character
+        Err(err) if err.utf8_error().error_len().is_none() => {
+          let valid = err.utf8_error().valid_up_to();
+          let mut bytes = err.into_bytes();
+          self.partial = bytes.split_off(valid);
+          if valid > 0 {
+            return Some(Ok(String::from_utf8(bytes).unwrap()));
+          }
+        }
+        Err(_) => return Some(Err(invalid_utf8())),
+      }
+    }
+  }
+}
+
+fn invalid_utf8() -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, "stream isn't valid UTF-8")
+}
+
+/// Lexes tokens from chunks of source, as they're needed
+#[derive(Debug)]
+pub struct TokenStream<C> {
+  chunks: C,
+  /// Source that hasn't been lexed into tokens yet
+  buffer: String,
+  /// The offset of the start of `buffer` from the start of the stream
+  offset: usize,
+  lexed: VecDeque<Result<StreamToken, StreamError>>,
+  /// The text that closes the token held back at the start of `buffer`
+  held: Option<&'static str>,
+  done: bool,
+}
+
+impl<C: Iterator<Item = io::Result<String>>> TokenStream<C> {
+  pub fn new(chunks: C) -> Self {
+    Self {
+      chunks,
+      buffer: String::new(),
+      offset: 0,
+      lexed: VecDeque::new(),
+      held: None,
+      done: false,
+    }
+  }
+
+  /// Lexes the buffered source up to `end`, leaving any tokens that could continue past it.\
+  /// The source after `end` is still lexed, so that tokens spanning `end` can be found.
+  fn lex_until(&mut self, end: usize) {
+    let (tokens, errors) = recover_tokens(&self.buffer);
+    let span = |origin: &str| {
+      let start = self.buffer.offset(origin);
+      start..start + origin.len()
+    };
+    let mut lexed: Vec<_> = tokens
+      .into_iter()
+      .map(|t| (span(t.origin), Ok(t.token)))
+      .chain(errors.into_iter().map(|e| (span(e.origin), Err(e.kind))))
+      .collect();
+    lexed.sort_by_key(|(span, _)| span.start);
+
+    // tokens within templates can't be lexed on their own
+    let mut depth = 0usize;
+    let (mut count, mut consumed) = (0, 0);
+    for (i, (span, token)) in lexed.iter().enumerate() {
+      if span.end > end {
+        break;
+      }
+      match token {
+        Ok(Token::TemplateStart(_)) => depth += 1,
+        Ok(Token::TemplateEnd(_)) => depth -= 1,
+        _ => {}
+      }
+      if depth == 0 {
+        (count, consumed) = (i + 1, span.end);
+      }
+    }
+
+    let offset = self.offset;
+    let buffer = &self.buffer;
+    self
+      .lexed
+      .extend(lexed.into_iter().take(count).map(|(span, token)| {
+        let absolute = span.start + offset..span.end + offset;
+        match token {
+          Ok(token) => Ok(StreamToken {
+            token,
+            span: absolute,
+          }),
+          Err(kind) => Err(StreamError::Lex {
+            kind,
+            span: absolute,
+            origin: buffer[span].into(),
+          }),
+        }
+      }));
+    self.buffer.drain(..consumed);
+    self.offset += consumed;
+    self.held = closing(&self.buffer);
+  }
+
+  /// Lexes the buffered source up to its last whitespace, if the text read from `from` could complete a token.\
+  /// Held back tokens are only lexed again once their closing text is read,
+  /// so streaming them takes time in proportion to their length.
+  fn flush(&mut self, from: usize) {
+    let complete = match self.held {
+      Some(close) => {
+        // the closing text could have been split between chunks
+        let from = from.saturating_sub(close.len() - 1);
+        let new = &self.buffer.as_bytes()[from..];
+        new.windows(close.len()).any(|w| w == close.as_bytes())
+      }
+      None => self.buffer[from..].contains(char::is_whitespace),
+    };
+    if !complete {
+      return;
+    }
+    if let Some(end) = self.buffer.rfind(char::is_whitespace) {
+      self.lex_until(end);
+    }
+  }
+}
+
+/// The text that closes a token that can contain whitespace, at the start of `source`
+fn closing(source: &str) -> Option<&'static str> {
+  let source = source.trim_start();
+  if source.starts_with("#(") {
+    Some(")#")
+  } else if source.starts_with('#') {
+    Some("\n")
+  } else if source.starts_with('"') || source.starts_with("f\"") {
+    Some("\"")
+  } else if source.starts_with('\'') {
+    Some("'")
+  } else {
+    None
+  }
+}
+
+impl<C: Iterator<Item = io::Result<String>>> Iterator for TokenStream<C> {
+  type Item = Result<StreamToken, StreamError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some(lexed) = self.lexed.pop_front() {
+        return Some(lexed);
+      }
+      if self.done {
+        return None;
+      }
+      match self.chunks.next() {
+        Some(Ok(chunk)) => {
+          let from = self.buffer.len();
+          self.buffer.push_str(&chunk);
+          self.flush(from);
+        }
+        Some(Err(err)) => {
+          // the source read before the error can still be lexed
+          self.done = true;
+          self.lex_until(self.buffer.len());
+          self.lexed.push_back(Err(StreamError::Io(err)));
+        }
+        None => {
+          self.done = true;
+          self.lex_until(self.buffer.len());
+        }
+      }
+    }
+  }
+}
+
+/// Lexes tokens from a reader, as they're needed
+///
+/// ```
+/// # use diom_lexer::stream::lex_reader;
+/// # use diom_tokens::Token;
+/// let file: &[u8] = b"let greeting = \"hello\nworld\";";
+/// let tokens: Vec<_> = lex_reader(file).map(|t| t.unwrap()).collect();
+/// assert_eq!(tokens[3].token, Token::String("hello\nworld".into()));
+/// assert_eq!(tokens[3].span, 15..28);
+/// ```
+pub fn lex_reader<R: Read>(reader: R) -> TokenStream<ReadChunks<R>> {
+  TokenStream::new(ReadChunks::new(reader, CHUNK_SIZE))
+}
+
+/// Lexes tokens from a sequence of chunks of source
+///
+/// ```
+/// # use diom_lexer::stream::lex_chunks;
+/// # use diom_tokens::Token;
+/// let tokens: Vec<_> = lex_chunks(["let x = #( split\n", "comment )# 1"])
+///   .map(|t| t.unwrap().token)
+///   .collect();
+/// assert_eq!(tokens[3], Token::Comment(" split\ncomment ".into()));
+/// ```
+pub fn lex_chunks<S: Into<String>>(
+  chunks: impl IntoIterator<Item = S>,
+) -> TokenStream<impl Iterator<Item = io::Result<String>>> {
+  TokenStream::new(chunks.into_iter().map(|chunk| Ok(chunk.into())))
+}
+
+#[cfg(test)]
+mod test {
+  use super::{lex_chunks, ReadChunks, StreamError, StreamToken, TokenStream};
+  use crate::{errors::LexErrorKind, parse_tokens, tests::LexError};
+  use diom_tokens::Token;
+  use nom::{Offset, Parser};
+
+  /// Lexes `code` read `size` bytes at a time, checking it matches `parse_tokens`
+  fn stream(code: &str, size: usize) -> Vec<StreamToken> {
+    let chunks = ReadChunks::new(code.as_bytes(), size);
+    let tokens: Vec<_> = TokenStream::new(chunks).map(|t| t.unwrap()).collect();
+
+    let (_, expected) = parse_tokens::<LexError>().parse(code).unwrap();
+    let expected: Vec<_> = expected
+      .into_iter()
+      .map(|t| StreamToken {
+        token: t.token,
+        span: code.offset(t.origin)..code.offset(t.origin) + t.origin.len(),
+      })
+      .collect();
+    assert_eq!(tokens, expected);
+    tokens
+  }
+
+  #[test]
+  fn split_tokens() {
+    let code = "let value = 1.5;\nlet name = value_name + 0x1f;\n";
+    for size in 1..8 {
+      stream(code, size);
+    }
+  }
+
+  #[test]
+  fn split_characters() {
+    // `ÿ` and `変` are split between reads
+    for size in 1..4 {
+      stream("let größe = 'ÿ';\n変数 + größe", size);
+    }
+  }
+
+  #[test]
+  fn multi_line_tokens() {
+    let code = "let s = \"a\nb\";\n#( block\n#( nested )#\n)#\nf\"{\n1\n} {s}\"";
+    for size in 1..6 {
+      let tokens = stream(code, size);
+      assert_eq!(tokens.len(), 11);
+    }
+  }
+
+  #[test]
+  fn whitespace_boundaries() {
+    // tokens are yielded as soon as the whitespace after them is read
+    let chunks = ["let x = 1", " + y"].into_iter().map(|c| Ok(c.into()));
+    let mut tokens = TokenStream::new(chunks.chain(std::iter::from_fn(|| panic!("read too far"))));
+    let lexed: Vec<_> = tokens.by_ref().take(5).map(|t| t.unwrap().token).collect();
+    assert_eq!(lexed[3], Token::Int(1));
+    assert_eq!(lexed[4], Token::Plus);
+  }
+
+  #[test]
+  fn held_tokens() {
+    // tokens after a held back string are lexed once it's closed
+    let chunks = ["x = \"a", " b", " c", "\" y", " z"].into_iter();
+    let mut tokens = TokenStream::new(
+      chunks
+        .map(|c| Ok(c.into()))
+        .chain(std::iter::from_fn(|| panic!("read too far"))),
+    );
+    let lexed: Vec<_> = tokens.by_ref().take(4).map(|t| t.unwrap().token).collect();
+    assert_eq!(lexed[2], Token::String("a b c".into()));
+    assert_eq!(lexed[3], Token::StringIdent("y".into()));
+  }
+
+  #[test]
+  fn errors() {
+    let lexed: Vec<_> = lex_chunks(["x $ y\n\"unter", "minated\n"])
+      .map(|t| match t {
+        Ok(token) => Ok(token.span),
+        Err(StreamError::Lex { kind, span, .. }) => Err((kind, span)),
+        Err(err) => panic!("{err}"),
+      })
+      .collect();
+    assert_eq!(
+      lexed,
+      vec![
+        Ok(0..1),
+        Err((LexErrorKind::Unrecognised, 2..3)),
+        Ok(4..5),
+        Err((LexErrorKind::UnterminatedString, 6..20)),
+      ]
+    );
+  }
+
+  #[test]
+  fn invalid_utf8() {
+    let bytes: &[u8] = b"x\n\xff";
+    let mut chunks = ReadChunks::new(bytes, 1);
+    assert_eq!(chunks.next().unwrap().unwrap(), "x");
+    assert_eq!(chunks.next().unwrap().unwrap(), "\n");
+    assert!(chunks.next().unwrap().is_err());
+
+    let truncated: &[u8] = "x ÿ".as_bytes();
+    let mut tokens = TokenStream::new(ReadChunks::new(&truncated[..3], 2));
+    assert_eq!(
+      tokens.next().unwrap().unwrap().token,
+      Token::StringIdent("x".into())
+    );
+    assert!(matches!(tokens.next(), Some(Err(StreamError::Io(_)))));
+    assert!(tokens.next().is_none());
+  }
+}
@@ -0,0 +1,147 @@
+//! Template literals, i.e. `f"hello {name}!"`.
+//!
+//! Templates lex to several tokens, so that their interpolated values lex as usual:
+//! ```_
+//! f"hello {name}!"
+//! # lexes to
+//! TemplateStart("hello ") StringIdent("name") TemplateEnd("!")
+//! ```
+use crate::{
+  chars::{escaped_char, escaped_eol},
+  errors::TokensError,
+  lex_into, In,
+};
+use diom_tokens::{SpanToken, Token};
+use nom::{
+  branch::alt,
+  bytes::complete::tag,
+  character::complete::{char, none_of},
+  combinator::{consumed, cut, value},
+  multi::many0,
+  sequence::{preceded, terminated},
+  IResult, Parser,
+};
+
+/// Parses the text of a template between interpolated values, handling escapes:
+/// ```_
+/// f"\{ braces \}";
+/// f"\"quotes\"";
+/// f"tabs\t";
+/// ```
+fn template_text<'a, E: TokensError<'a>>() -> impl Parser<In<'a>, Output = Box<str>, Error = E> {
+  let parse_single = alt((
+    escaped_char(),
+    value('"', tag("\\\"")),
+    value('{', tag("\\{")),
+    value('}', tag("\\}")),
+    none_of("\"{}"),
+  ));
+
+  terminated(
+    many0(preceded(many0(escaped_eol()), parse_single)),
+    many0(escaped_eol()),
+  )
+  .map(|chars| chars.into_iter().collect())
+}
+
+/// Parses a template, along with the tokens of the values interpolated within it
+pub fn parse_template<'a, E: TokensError<'a>>(
+  input: In<'a>,
+) -> IResult<In<'a>, Vec<SpanToken<'a>>, E> {
+  // once `f"` is found, this can't fall back to an identifier followed by a string
+  let (mut input, (origin, (text, end))) = consumed((
+    preceded(tag("f\""), template_text()),
+    cut(alt((char('"'), char('{')))),
+  ))
+  .parse(input)?;
+  if end == '"' {
+    let token = Token::Template(text);
+    return Ok((input, vec![SpanToken::new(token, origin)]));
+  }
+
+  let mut tokens = vec![SpanToken::new(Token::TemplateStart(text), origin)];
+  loop {
+    (input, _) = lex_into(input, true, &mut tokens)?;
+    let (rest, (origin, (text, end))) = cut(consumed((
+      preceded(char('}'), template_text()),
+      alt((char('"'), char('{'))),
+    )))
+    .parse(input)?;
+    input = rest;
+
+    if end == '"' {
+      let token = Token::TemplateEnd(text);
+      tokens.push(SpanToken::new(token, origin));
+      return Ok((input, tokens));
+    }
+    let token = Token::TemplateMiddle(text);
+    tokens.push(SpanToken::new(token, origin));
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::parse_template;
+  use crate::tests::TestResult;
+  use diom_tokens::Token::{self, *};
+  use nom::{combinator::all_consuming, Parser};
+
+  fn lex(input: &str) -> TestResult<'_, Vec<Token>> {
+    let (_, tokens) = all_consuming(parse_template).parse(input)?;
+    Ok(tokens.into_iter().map(|t| t.token).collect())
+  }
+
+  #[test]
+  fn plain() -> TestResult<'static, ()> {
+    assert_eq!(lex(r#"f"hello""#)?, vec![Template("hello".into())]);
+    Ok(())
+  }
+
+  #[test]
+  fn escaped_braces() -> TestResult<'static, ()> {
+    assert_eq!(lex(r#"f"\{x\}""#)?, vec![Template("{x}".into())]);
+    Ok(())
+  }
+
+  #[test]
+  fn unterminated() {
+    let result = all_consuming(parse_template::<nom::error::Error<_>>).parse(r#"f"{x"#);
+    assert!(matches!(result, Err(nom::Err::Failure(_))));
+  }
+
+  #[test]
+  fn interpolated() -> TestResult<'static, ()> {
+    assert_eq!(
+      lex(r#"f"a{x}b{ y + 1 }c""#)?,
+      vec![
+        TemplateStart("a".into()),
+        StringIdent("x".into()),
+        TemplateMiddle("b".into()),
+        StringIdent("y".into()),
+        Plus,
+        Int(1),
+        TemplateEnd("c".into()),
+      ]
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn nested() -> TestResult<'static, ()> {
+    assert_eq!(
+      lex(r#"f"{ {a: f"{b}"} }""#)?,
+      vec![
+        TemplateStart("".into()),
+        LCurly,
+        StringIdent("a".into()),
+        Colon,
+        TemplateStart("".into()),
+        StringIdent("b".into()),
+        TemplateEnd("".into()),
+        RCurly,
+        TemplateEnd("".into()),
+      ]
+    );
+    Ok(())
+  }
+}
@@ -1,9 +1,17 @@
-use crate::parse_tokens;
-use diom_syntax::expressions::Expression;
-use diom_tokens::SpanToken;
+use crate::{
+  fast::parse_tokens_fast,
+  incremental::{relex, TextEdit},
+  keywords::KEYWORDS,
+  parse_tokens,
+  stream::lex_chunks,
+  trivia::parse_lossless,
+};
+use diom_syntax::{expressions::Expression, idents::Ident};
+use diom_tokens::{keywords::is_keyword, strings::StringLiteral, SpanToken, Token};
 use nom::{combinator::all_consuming, error::Error, Err, Offset, Parser};
-use proptest::prelude::*;
+use proptest::{num::f64, prelude::*};
 use std::fmt::{from_fn, Debug};
+use unicode_normalization::UnicodeNormalization;
 
 proptest! {
   /// Tests that we can lex code produced from a syntax tree
@@ -12,6 +20,242 @@ proptest! {
     let code = format!("{expr}");
     quick_lex(&code);
   }
+
+  /// Tests that identifiers lex to a single identifier token
+  #[test]
+  fn lex_ident(ident in Ident::any()) {
+    let tokens = quick_lex(&ident.name);
+    prop_assert_eq!(tokens, vec![SpanToken::new(
+      Token::StringIdent(ident.name.as_ref().into()),
+      &ident.name,
+    )]);
+  }
+
+  /// Tests that identifiers starting with a keyword aren't split at the keyword
+  #[test]
+  fn lex_keyword_prefix(
+    idx in 0..KEYWORDS.len(),
+    rest in "[_a-zA-Z0-9]+",
+  ) {
+    let name = format!("{}{rest}", KEYWORDS[idx].0);
+    prop_assume!(!is_keyword(&name));
+    let tokens: Vec<_> = quick_lex(&name).into_iter().map(|t| t.token).collect();
+    prop_assert_eq!(tokens, vec![Token::StringIdent(name.into())]);
+  }
+
+  /// Tests that integers lex exactly in any radix, with any digit separators
+  #[test]
+  fn lex_int(
+    value: u64,
+    radix in prop::sample::select(vec![2, 8, 10, 16]),
+    separators in prop::collection::vec(any::<bool>(), 64),
+  ) {
+    let digits = match radix {
+      2 => format!("{value:b}"),
+      8 => format!("{value:o}"),
+      16 => format!("{value:x}"),
+      _ => value.to_string(),
+    };
+    let digits: String = digits
+      .chars()
+      .zip(&separators)
+      .flat_map(|(c, &sep)| [Some(c), sep.then_some('_')])
+      .flatten()
+      .collect();
+    let prefix = match radix {
+      2 => "0b",
+      8 => "0o",
+      16 => "0x",
+      _ => "",
+    };
+    let code = format!("{prefix}{digits}");
+    let tokens: Vec<_> = quick_lex(&code).into_iter().map(|t| t.token).collect();
+    prop_assert_eq!(tokens, vec![Token::Int(value)]);
+  }
+
+  /// Tests that integers too large for a `u64` are rejected
+  #[test]
+  fn lex_int_overflow(value in (u64::MAX as u128 + 1)..=u128::MAX) {
+    let code = value.to_string();
+    let result = all_consuming(parse_tokens::<Error<_>>()).parse(code.as_str());
+    prop_assert!(matches!(result, Err(Err::Failure(_))));
+  }
+
+  /// Tests that floats lex to the same float they were formatted from
+  #[test]
+  fn lex_float(value in f64::POSITIVE | f64::NORMAL | f64::SUBNORMAL | f64::ZERO) {
+    let code = format!("{value:?}");
+    let tokens: Vec<_> = quick_lex(&code).into_iter().map(|t| t.token).collect();
+    prop_assert_eq!(tokens, vec![Token::Float(value)]);
+  }
+
+  /// Tests that lossless lexing reproduces code exactly, with any trivia between tokens
+  #[test]
+  fn lex_lossless(
+    expr: Expression<()>,
+    trivia in prop::collection::vec(r"[ \t\r\n]+(#[a-z ]+\n|#\([a-z \n]*\)#)?[ \t\n]*", 1..100)
+  ) {
+    let code = format!("{expr}");
+    let tokens = quick_lex(&code);
+    let mut spaced = String::new();
+    for (token, trivia) in tokens.iter().zip(trivia.iter().cycle()) {
+      spaced += token.origin;
+      spaced += trivia;
+    }
+
+    let (rest, lossless) = all_consuming(parse_lossless::<Error<_>>())
+      .parse(&spaced)
+      .map_err(|err| format_error(&spaced, err))
+      .unwrap();
+    prop_assert_eq!(rest, "");
+    prop_assert_eq!(lossless.iter().map(SpanToken::lossless).collect::<String>(), spaced.as_str());
+    let lossless: Vec<_> = lossless.into_iter().map(|t| t.token).collect();
+    let tokens: Vec<_> = tokens.into_iter().map(|t| t.token).collect();
+    prop_assert_eq!(lossless, tokens);
+  }
+
+  /// Tests that input of only whitespace and comments is kept when lexing losslessly
+  #[test]
+  fn lossless_only_trivia(
+    trivia in prop::collection::vec(r"[ \t\r\n]*(#[a-z ]+\n|#\([a-z \n]*\)#)?", 0..8)
+  ) {
+    let trivia = trivia.concat();
+    let (rest, lossless) = all_consuming(parse_lossless::<Error<_>>())
+      .parse(&trivia)
+      .map_err(|err| format_error(&trivia, err))
+      .unwrap();
+    prop_assert_eq!(rest, "");
+    prop_assert_eq!(lossless.iter().map(SpanToken::lossless).collect::<String>(), trivia.as_str());
+    prop_assert!(lossless.iter().all(|t| t.origin.is_empty()));
+  }
+
+  /// Tests that relexing after an edit gives the same tokens as lexing from scratch,\
+  /// or fails when the edited source can't all be lexed
+  #[test]
+  fn relex_edits(
+    expr: Expression<()>,
+    start: prop::sample::Index,
+    len in 0..8usize,
+    text in r#"[a-z0-9 ._#"'(){}\n=+f]{0,4}"#,
+  ) {
+    let old = format!("{expr}");
+    let tokens = quick_lex(&old);
+    let bounds: Vec<_> = (0..=old.len()).filter(|&i| old.is_char_boundary(i)).collect();
+    let start = start.index(bounds.len());
+    let end = bounds[(start + len).min(bounds.len() - 1)];
+    let edit = TextEdit { range: bounds[start]..end, text: &text };
+    let new = edit.apply(&old);
+
+    let expected = parse_tokens::<Error<_>>()
+      .parse(&new)
+      .map(|(rest, tokens)| (rest.trim_start_matches([' ', '\t', '\r', '\n']).is_empty(), tokens));
+    let relexed = relex::<Error<_>>(&tokens, &old, &new, &edit);
+    match (expected, relexed) {
+      (Ok((true, expected)), Ok(relexed)) => {
+        let offsets = |tokens: &[SpanToken]| -> Vec<_> {
+          tokens.iter().map(|t| new.offset(t.origin)).collect()
+        };
+        prop_assert_eq!(offsets(&relexed.tokens), offsets(&expected));
+        prop_assert_eq!(relexed.tokens, expected);
+      }
+      (Ok((false, _)) | Err(_), Err(_)) => {}
+      (expected, relexed) => prop_assert!(false, "expected {expected:?}, relexed {relexed:?}"),
+    }
+  }
+
+  /// Tests that the fast lexer lexes code the same as `parse_tokens`
+  #[test]
+  fn lex_fast_code(expr: Expression<()>) {
+    let code = format!("{expr}");
+    prop_assert_eq!(
+      parse_tokens_fast::<Error<_>>().parse(&code),
+      parse_tokens().parse(&code)
+    );
+  }
+
+  /// Tests that the fast lexer lexes, or fails to lex, any input the same as `parse_tokens`
+  #[test]
+  fn lex_fast_any(code in r#"([ -~\n]|f"|##|#\(|\)#|let|0x|1e|é|μ|а|\u{301}|変|r#"|"""\n)*"#) {
+    prop_assert_eq!(
+      parse_tokens_fast::<Error<_>>().parse(&code),
+      parse_tokens().parse(&code)
+    );
+  }
+
+  /// Tests that lexing code in chunks gives the same tokens as lexing it all at once
+  #[test]
+  fn lex_stream(expr: Expression<()>, sizes in prop::collection::vec(1..16usize, 1..8)) {
+    let code = format!("{expr}");
+    let (_, expected) = all_consuming(parse_tokens::<Error<_>>()).parse(&code).unwrap();
+    let expected: Vec<_> = expected
+      .into_iter()
+      .map(|t| (t.token, code.offset(t.origin)..code.offset(t.origin) + t.origin.len()))
+      .collect();
+
+    let mut chunks = vec![];
+    let mut rest = code.as_str();
+    for size in sizes.iter().cycle() {
+      if rest.is_empty() {
+        break;
+      }
+      let mut end = (*size).min(rest.len());
+      while !rest.is_char_boundary(end) {
+        end += 1;
+      }
+      let (chunk, after) = rest.split_at(end);
+      chunks.push(chunk);
+      rest = after;
+    }
+    let tokens: Vec<_> = lex_chunks(chunks)
+      .map(|t| t.map(|t| (t.token, t.span)).map_err(|e| e.to_string()))
+      .collect();
+    prop_assert_eq!(tokens, expected.into_iter().map(Ok).collect::<Vec<_>>());
+  }
+
+  /// Tests that identifiers lex to the same name in any normal form
+  #[test]
+  fn lex_ident_normalised(ident in Ident::any()) {
+    let decomposed: String = ident.name.nfd().collect();
+    let tokens = quick_lex(&decomposed);
+    prop_assert_eq!(tokens, vec![SpanToken::new(
+      Token::StringIdent(ident.name.as_ref().into()),
+      &decomposed,
+    )]);
+  }
+
+  /// Tests that string literals lex to a single string token
+  #[test]
+  fn lex_string(value: String) {
+    let code = format!("{value:?}");
+    let tokens = quick_lex(&code);
+    prop_assert_eq!(tokens, vec![SpanToken::new(Token::String(value.into()), &code)]);
+  }
+
+  /// Tests that strings written as raw strings or text blocks lex to the same string
+  #[test]
+  fn lex_string_literal(value in r#"([ -~]|\n|"|\\|#|"""|é)*"#) {
+    let code = StringLiteral(&value).to_string();
+    let tokens = quick_lex(&code);
+    prop_assert_eq!(tokens, vec![SpanToken::new(Token::String(value.into()), &code)]);
+  }
+}
+
+#[test]
+fn lex_keywords() {
+  let tokens: Vec<_> = quick_lex("let type return use if")
+    .into_iter()
+    .map(|t| t.token)
+    .collect();
+  assert_eq!(
+    tokens,
+    vec![
+      Token::Let,
+      Token::Type,
+      Token::Return,
+      Token::Use,
+      Token::Reserved("if".into())
+    ]
+  );
 }
 
 fn quick_lex(code: &str) -> Vec<SpanToken<'_>> {
@@ -0,0 +1,167 @@
+//! Lossless lexing, where whitespace and comments are kept as trivia on tokens.
+//!
+//! Every byte of the lexed input belongs to some token, either as its `origin`,\
+//! or as its `leading` or `trailing` trivia, so that formatting tools can preserve layout:
+//! - a token's trailing trivia runs up to, and including, the end of its line
+//! - all other trivia is leading trivia of the next token
+//! - trivia at the end of the input is trailing trivia of the last token
+use crate::{comments::parse_comment, errors::TokensError, parse_tokens, In};
+use diom_tokens::{SpanToken, Token};
+use nom::{character::complete::multispace0, Offset, Parser};
+
+/// Parses tokens losslessly, attaching whitespace and comments to them as trivia.\
+/// Concatenating the trivia and origins of all tokens reproduces the parsed input exactly.
+///
+/// ```
+/// # use diom_lexer::trivia::parse_lossless;
+/// # use nom::Parser;
+/// let code = "let x = 1 # one\n  + 2";
+/// let (_, tokens) = parse_lossless::<nom::error::Error<_>>().parse(code).unwrap();
+/// assert_eq!(tokens[3].trailing, " # one\n");
+/// assert_eq!(tokens[4].leading, "  ");
+/// assert_eq!(tokens.iter().map(|t| t.lossless()).collect::<String>(), code);
+/// ```
+///
+/// Input that's only trivia has no tokens to attach it to,
+/// so it's attached to an empty comment at the end of the input.
+pub fn parse_lossless<'a, E: TokensError<'a>>(
+) -> impl Parser<In<'a>, Output = Vec<SpanToken<'a>>, Error = E> {
+  move |input: In<'a>| {
+    let (rest, tokens) = parse_tokens().parse(input)?;
+    let (rest, _) = multispace0(rest)?;
+    let source = &input[..input.len() - rest.len()];
+    if source.is_empty() {
+      return Ok((rest, vec![]));
+    }
+    if tokens.iter().all(|t| matches!(t.token, Token::Comment(_))) {
+      let end = SpanToken::new(Token::Comment("".into()), &source[source.len()..]);
+      return Ok((
+        rest,
+        vec![SpanToken {
+          leading: source,
+          ..end
+        }],
+      ));
+    }
+    Ok((rest, attach_trivia(source, tokens)))
+  }
+}
+
+/// Moves comments, and the gaps between tokens, into the trivia of the tokens around them.\
+/// `source` should span from the start of the first token to the end of the trivia after the last.
+fn attach_trivia<'a>(source: In<'a>, tokens: Vec<SpanToken<'a>>) -> Vec<SpanToken<'a>> {
+  let mut attached: Vec<SpanToken<'a>> = vec![];
+  let mut start = 0;
+  for mut token in tokens {
+    if let Token::Comment(_) = token.token {
+      continue;
+    }
+    let gap = &source[start..source.offset(token.origin)];
+    if let Some(prev) = attached.last_mut() {
+      let (trailing, leading) = gap.split_at(trailing_len(gap));
+      prev.trailing = trailing;
+      token.leading = leading;
+    } else {
+      token.leading = gap;
+    }
+    start = source.offset(token.origin) + token.origin.len();
+    attached.push(token);
+  }
+  if let Some(last) = attached.last_mut() {
+    last.trailing = &source[start..];
+  }
+  attached
+}
+
+/// The length of the trivia at the start of `gap` that's on the same line as the token before it
+fn trailing_len(gap: In<'_>) -> usize {
+  let mut rest = gap;
+  while let Some(c) = rest.chars().next() {
+    if c == '\n' {
+      return gap.offset(rest) + 1;
+    }
+    if c.is_whitespace() {
+      rest = &rest[c.len_utf8()..];
+      continue;
+    }
+    let Ok((after, _)) = parse_comment::<nom::error::Error<_>>().parse(rest) else {
+      break;
+    };
+    let comment = &rest[..rest.offset(after)];
+    rest = after;
+    // line comments include the end of their line
+    if comment.ends_with('\n') {
+      return gap.offset(rest);
+    }
+  }
+  gap.len()
+}
+
+#[cfg(test)]
+mod test {
+  use super::parse_lossless;
+  use crate::tests::TestResult;
+  use diom_tokens::Token;
+  use nom::{combinator::all_consuming, Parser};
+
+  fn trivia(input: &str) -> TestResult<'_, Vec<(&str, Token, &str)>> {
+    let (_, tokens) = all_consuming(parse_lossless()).parse(input)?;
+    Ok(
+      tokens
+        .into_iter()
+        .map(|t| (t.leading, t.token, t.trailing))
+        .collect(),
+    )
+  }
+
+  #[test]
+  fn whitespace() -> TestResult<'static, ()> {
+    assert_eq!(
+      trivia(" a\n  b ")?,
+      vec![
+        (" ", Token::StringIdent("a".into()), "\n"),
+        ("  ", Token::StringIdent("b".into()), " "),
+      ]
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn comments() -> TestResult<'static, ()> {
+    assert_eq!(
+      trivia("a # line\n#( block )# b #( multi\nline )#")?,
+      vec![
+        ("", Token::StringIdent("a".into()), " # line\n"),
+        (
+          "#( block )# ",
+          Token::StringIdent("b".into()),
+          " #( multi\nline )#"
+        ),
+      ]
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn doc_comments() -> TestResult<'static, ()> {
+    // doc comments are tokens, as the parser attaches them to declarations
+    assert_eq!(
+      trivia("## docs\nlet")?,
+      vec![
+        ("", Token::DocComment(" docs".into()), ""),
+        ("", Token::Let, ""),
+      ]
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn only_trivia() -> TestResult<'static, ()> {
+    assert_eq!(
+      trivia("  # comment\n")?,
+      vec![("  # comment\n", Token::Comment("".into()), "")]
+    );
+    assert_eq!(trivia("")?, vec![]);
+    Ok(())
+  }
+}
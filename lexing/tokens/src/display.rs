@@ -1,9 +1,10 @@
-use crate::{SpanToken, SpanTokens, Token};
+use crate::{strings::StringLiteral, templates::Escaped, SpanToken, SpanTokens, Token};
 use std::fmt::{Display, Write};
 
 #[cfg(feature = "pretty")]
-mod pretty {
+pub(crate) mod pretty {
   use super::Token;
+  use crate::{strings::StringLiteral, templates::Escaped};
   use colorz::{ansi, Colorize, Style};
   use std::fmt::Display;
 
@@ -14,6 +15,12 @@ mod pretty {
   pub const LITERAL_STYLE: Style = Style::new().const_into_runtime_style();
   pub const IDENTIFIER_STYLE: Style = Style::new().const_into_runtime_style();
   pub const COMMENT_STYLE: Style = Style::new().fg(ansi::White).const_into_runtime_style();
+  pub const ERROR_STYLE: Style = Style::new().fg(ansi::Red).const_into_runtime_style();
+
+  // identifiers are only given a role when highlighting, see `Class::style`
+  pub const FUNCTION_STYLE: Style = Style::new().fg(ansi::Blue).const_into_runtime_style();
+  pub const FIELD_STYLE: Style = Style::new().fg(ansi::BrightBlue).const_into_runtime_style();
+  pub const TYPE_STYLE: Style = Style::new().fg(ansi::Yellow).const_into_runtime_style();
 
   pub fn display(tok: &Token, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match tok {
@@ -38,6 +45,8 @@ mod pretty {
       Token::Let => "let".style_with(KEYWORD_STYLE).fmt(f),
       Token::Type => "type".style_with(KEYWORD_STYLE).fmt(f),
       Token::Return => "return".style_with(KEYWORD_STYLE).fmt(f),
+      Token::Use => "use".style_with(KEYWORD_STYLE).fmt(f),
+      Token::Reserved(word) => word.style_with(KEYWORD_STYLE).fmt(f),
 
       /* Operators */
       Token::Not => '!'.style_with(OPERATOR_STYLE).fmt(f),
@@ -56,12 +65,33 @@ mod pretty {
       Token::Monad => '?'.style_with(OPERATOR_STYLE).fmt(f),
 
       /* Literals */
-      Token::Float(value) => value.style_with(LITERAL_STYLE).fmt(f),
+      Token::Int(value) => value.style_with(LITERAL_STYLE).fmt(f),
+      Token::Float(value) => format!("{value:?}").style_with(LITERAL_STYLE).fmt(f),
       Token::Char(chr) => chr.style_with(LITERAL_STYLE).fmt(f),
+      Token::String(string) => StringLiteral(string)
+        .to_string()
+        .style_with(LITERAL_STYLE)
+        .fmt(f),
+      Token::Template(text) => format!("f\"{}\"", Escaped(text))
+        .style_with(LITERAL_STYLE)
+        .fmt(f),
+      Token::TemplateStart(text) => format!("f\"{}{{", Escaped(text))
+        .style_with(LITERAL_STYLE)
+        .fmt(f),
+      Token::TemplateMiddle(text) => format!("}}{}{{", Escaped(text))
+        .style_with(LITERAL_STYLE)
+        .fmt(f),
+      Token::TemplateEnd(text) => format!("}}{}\"", Escaped(text))
+        .style_with(LITERAL_STYLE)
+        .fmt(f),
 
       /* String-like */
       Token::StringIdent(ident) => ident.style_with(IDENTIFIER_STYLE).fmt(f),
       Token::Comment(com) => com.style_with(COMMENT_STYLE).fmt(f),
+      Token::DocComment(doc) => format!("##{doc}").style_with(COMMENT_STYLE).fmt(f),
+
+      /* Recovery */
+      Token::Invalid => "<error>".style_with(ERROR_STYLE).fmt(f),
     }
   }
 }
@@ -89,6 +119,8 @@ fn display(tok: &Token, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     Token::Let => f.write_str("let"),
     Token::Type => f.write_str("type"),
     Token::Return => f.write_str("return"),
+    Token::Use => f.write_str("use"),
+    Token::Reserved(word) => f.write_str(word),
 
     /* Operators */
     Token::Not => f.write_char('!'),
@@ -107,12 +139,23 @@ fn display(tok: &Token, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     Token::Monad => f.write_char('?'),
 
     /* Literals */
-    Token::Float(value) => value.fmt(f),
+    Token::Int(value) => value.fmt(f),
+    // floats always have a `.` or exponent, so they don't lex as integers
+    Token::Float(value) => write!(f, "{value:?}"),
     Token::Char(chr) => chr.fmt(f),
+    Token::String(string) => StringLiteral(string).fmt(f),
+    Token::Template(text) => write!(f, "f\"{}\"", Escaped(text)),
+    Token::TemplateStart(text) => write!(f, "f\"{}{{", Escaped(text)),
+    Token::TemplateMiddle(text) => write!(f, "}}{}{{", Escaped(text)),
+    Token::TemplateEnd(text) => write!(f, "}}{}\"", Escaped(text)),
 
     /* String-like */
     Token::StringIdent(ident) => ident.fmt(f),
     Token::Comment(com) => com.fmt(f),
+    Token::DocComment(doc) => write!(f, "##{doc}"),
+
+    /* Recovery */
+    Token::Invalid => f.write_str("<error>"),
   }
 }
 
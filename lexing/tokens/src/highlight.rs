@@ -0,0 +1,158 @@
+//! Classification of tokens for syntax highlighting.
+//!
+//! Each token is given a `Class`, with identifiers also given a `Role` from the tokens around them,\
+//! so that documentation, the playground and terminal output all highlight code the same way.
+use crate::{SpanToken, Token};
+
+/// How an identifier is used, guessed from the tokens around it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+  /// A variable, i.e. `x` in `x + 1`
+  Variable,
+  /// A function being called, i.e. `f` in `f(x)`
+  Function,
+  /// A field being accessed, i.e. `y` in `x.y`
+  Field,
+  /// A type, which by convention starts with a capital, i.e. `Int`
+  Type,
+}
+
+/// The highlighting class of a token
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Class {
+  Bracket,
+  Punctuation,
+  Keyword,
+  Operator,
+  /// Numbers, characters, strings and templates
+  Literal,
+  Comment,
+  DocComment,
+  Ident(Role),
+  /// Source that couldn't be lexed
+  Error,
+}
+
+impl Class {
+  /// The class of `token` on its own, where all identifiers are variables
+  pub fn of(token: &Token) -> Self {
+    match token {
+      Token::LParen
+      | Token::RParen
+      | Token::LCurly
+      | Token::RCurly
+      | Token::LBrace
+      | Token::RBrace => Class::Bracket,
+
+      Token::Dot
+      | Token::Semi
+      | Token::Colon
+      | Token::Comma
+      | Token::Assign
+      | Token::Ellipses
+      | Token::Function => Class::Punctuation,
+
+      Token::Let | Token::Type | Token::Return | Token::Use | Token::Reserved(_) => Class::Keyword,
+
+      Token::Not
+      | Token::And
+      | Token::Or
+      | Token::Plus
+      | Token::Minus
+      | Token::Times
+      | Token::Divide
+      | Token::Eq
+      | Token::Ne
+      | Token::Lt
+      | Token::Gt
+      | Token::LtEq
+      | Token::GtEq
+      | Token::Monad => Class::Operator,
+
+      Token::Int(_)
+      | Token::Float(_)
+      | Token::Char(_)
+      | Token::String(_)
+      | Token::Template(_)
+      | Token::TemplateStart(_)
+      | Token::TemplateMiddle(_)
+      | Token::TemplateEnd(_) => Class::Literal,
+
+      Token::StringIdent(_) => Class::Ident(Role::Variable),
+      Token::Comment(_) => Class::Comment,
+      Token::DocComment(_) => Class::DocComment,
+      Token::Invalid => Class::Error,
+    }
+  }
+
+  /// The CSS class used for this class when highlighting to HTML
+  pub fn css_class(self) -> &'static str {
+    match self {
+      Class::Bracket => "dm-bracket",
+      Class::Punctuation => "dm-punctuation",
+      Class::Keyword => "dm-keyword",
+      Class::Operator => "dm-operator",
+      Class::Literal => "dm-literal",
+      Class::Comment => "dm-comment",
+      Class::DocComment => "dm-doc-comment",
+      Class::Ident(Role::Variable) => "dm-variable",
+      Class::Ident(Role::Function) => "dm-function",
+      Class::Ident(Role::Field) => "dm-field",
+      Class::Ident(Role::Type) => "dm-type",
+      Class::Error => "dm-error",
+    }
+  }
+
+  /// The terminal style used for this class when highlighting to ANSI,\
+  /// from the same palette as the alternate `Display` of tokens
+  #[cfg(feature = "pretty")]
+  pub fn style(self) -> colorz::Style {
+    use crate::display::pretty::*;
+    match self {
+      Class::Bracket => BRACKET_STYLE,
+      Class::Punctuation => PUNCTUATION_STYLE,
+      Class::Keyword => KEYWORD_STYLE,
+      Class::Operator => OPERATOR_STYLE,
+      Class::Literal => LITERAL_STYLE,
+      Class::Comment | Class::DocComment => COMMENT_STYLE,
+      Class::Ident(Role::Variable) => IDENTIFIER_STYLE,
+      Class::Ident(Role::Function) => FUNCTION_STYLE,
+      Class::Ident(Role::Field) => FIELD_STYLE,
+      Class::Ident(Role::Type) => TYPE_STYLE,
+      Class::Error => ERROR_STYLE,
+    }
+  }
+}
+
+/// Classifies each of `tokens`, using the tokens around identifiers to find their role
+///
+/// ```
+/// # use diom_tokens::{highlight::{classify, Class, Role}, SpanToken, Token::*};
+/// let tokens = [StringIdent("f".into()), LParen, StringIdent("x".into()), Dot, StringIdent("y".into()), RParen];
+/// let classes = classify(&tokens.map(SpanToken::from));
+/// assert_eq!(classes[0], Class::Ident(Role::Function));
+/// assert_eq!(classes[2], Class::Ident(Role::Variable));
+/// assert_eq!(classes[4], Class::Ident(Role::Field));
+/// ```
+pub fn classify(tokens: &[SpanToken<'_>]) -> Vec<Class> {
+  let is_comment = |t: &&SpanToken| matches!(t.token, Token::Comment(_) | Token::DocComment(_));
+  (0..tokens.len())
+    .map(|i| {
+      let Token::StringIdent(name) = &tokens[i].token else {
+        return Class::of(&tokens[i].token);
+      };
+      let prev = tokens[..i].iter().rev().find(|t| !is_comment(t));
+      let next = tokens[i + 1..].iter().find(|t| !is_comment(t));
+      let role = if next.is_some_and(|t| t.token == Token::LParen) {
+        Role::Function
+      } else if prev.is_some_and(|t| t.token == Token::Dot) {
+        Role::Field
+      } else if name.starts_with(char::is_uppercase) {
+        Role::Type
+      } else {
+        Role::Variable
+      };
+      Class::Ident(role)
+    })
+    .collect()
+}
@@ -0,0 +1,47 @@
+//! The keywords of the Diom language, shared by the lexer and parser.
+use crate::Token;
+
+/// Every keyword, along with the token it lexes to.\
+/// Keywords without a token are reserved for future syntax,\
+/// they lex to `Token::Reserved` so can't be used as identifiers.
+pub const KEYWORDS: &[(&str, Option<Token>)] = &[
+  ("let", Some(Token::Let)),
+  ("type", Some(Token::Type)),
+  ("return", Some(Token::Return)),
+  ("use", Some(Token::Use)),
+  // reserved for future syntax
+  ("if", None),
+  ("else", None),
+  ("match", None),
+  ("for", None),
+  ("in", None),
+  ("while", None),
+  ("break", None),
+  ("continue", None),
+  ("import", None),
+  ("module", None),
+  ("pub", None),
+  ("as", None),
+];
+
+/// The token that `word` lexes to, if it's a keyword
+///
+/// ```
+/// # use diom_tokens::{keywords::keyword, Token};
+/// assert_eq!(keyword("let"), Some(Token::Let));
+/// assert_eq!(keyword("if"), Some(Token::Reserved("if".into())));
+/// assert_eq!(keyword("letter"), None);
+/// ```
+pub fn keyword(word: &str) -> Option<Token> {
+  let (_, token) = KEYWORDS.iter().find(|(name, _)| *name == word)?;
+  Some(
+    token
+      .clone()
+      .unwrap_or_else(|| Token::Reserved(word.into())),
+  )
+}
+
+/// Whether `word` is a keyword, so can't be used as an identifier
+pub fn is_keyword(word: &str) -> bool {
+  KEYWORDS.iter().any(|(name, _)| *name == word)
+}
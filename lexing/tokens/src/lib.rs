@@ -1,4 +1,10 @@
 mod display;
+pub mod highlight;
+pub mod keywords;
+pub mod owned;
+pub mod source_map;
+pub mod strings;
+pub mod templates;
 mod token;
 pub use token::Token;
 mod span_token;
@@ -0,0 +1,146 @@
+//! Tokens that own their source, so that they aren't tied to the lifetime of a buffer.
+//!
+//! `SpanToken`s borrow the source they were lexed from, which stops them being cached,
+//! or sent between threads.\
+//! Instead, `OwnedTokens` share their source in an `Arc<str>`, with each token holding a byte `Span`.
+use crate::{SpanToken, SpanTokens, Token};
+use std::{ops::Range, sync::Arc};
+
+/// A byte range within a source
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+  pub start: usize,
+  pub end: usize,
+}
+
+impl Span {
+  /// Finds the span of `part` within `source`.\
+  /// This is `None` when `part` isn't a slice of `source`.
+  ///
+  /// ```
+  /// # use diom_tokens::owned::Span;
+  /// let source = "let x = 1";
+  /// assert_eq!(Span::of(source, &source[4..5]), Some(Span { start: 4, end: 5 }));
+  /// assert_eq!(Span::of(source, "x"), None);
+  /// ```
+  pub fn of(source: &str, part: &str) -> Option<Self> {
+    let start = (part.as_ptr() as usize).checked_sub(source.as_ptr() as usize)?;
+    let end = start + part.len();
+    (end <= source.len()).then_some(Span { start, end })
+  }
+
+  /// The byte range of the span
+  pub fn range(self) -> Range<usize> {
+    self.start..self.end
+  }
+
+  pub fn len(self) -> usize {
+    self.end - self.start
+  }
+
+  pub fn is_empty(self) -> bool {
+    self.start == self.end
+  }
+}
+
+impl std::fmt::Display for Span {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}..{}", self.start, self.end)
+  }
+}
+
+impl From<Range<usize>> for Span {
+  fn from(range: Range<usize>) -> Self {
+    Span {
+      start: range.start,
+      end: range.end,
+    }
+  }
+}
+
+impl From<Span> for Range<usize> {
+  fn from(span: Span) -> Self {
+    span.range()
+  }
+}
+
+/// A token, along with the span of source it was lexed from
+#[derive(Clone, Debug, PartialEq)]
+pub struct OwnedToken {
+  pub token: Token,
+  pub span: Span,
+}
+
+/// Tokens that share ownership of the source they were lexed from.\
+/// Cloning is cheap, as both the source and tokens are reference counted.
+///
+/// ```
+/// # use diom_tokens::{owned::{OwnedTokens, Span}, SpanToken, Token};
+/// let source = String::from("x + 1");
+/// let tokens = [
+///   SpanToken::new(Token::StringIdent("x".into()), &source[0..1]),
+///   SpanToken::new(Token::Plus, &source[2..3]),
+///   SpanToken::new(Token::Int(1), &source[4..5]),
+/// ];
+/// let owned = OwnedTokens::new(&source, &tokens);
+/// assert_eq!(owned.span_tokens(), tokens);
+///
+/// drop(source);
+/// assert_eq!(owned.tokens()[1].span, Span { start: 2, end: 3 });
+/// assert_eq!(owned.text(owned.tokens()[2].span), "1");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct OwnedTokens {
+  source: Arc<str>,
+  tokens: Arc<[OwnedToken]>,
+}
+
+impl OwnedTokens {
+  /// Copies `tokens` lexed from `source`, dropping any trivia
+  ///
+  /// # Panics
+  ///
+  /// When a token's origin isn't a slice of `source`.
+  pub fn new(source: &str, tokens: &[SpanToken<'_>]) -> Self {
+    let tokens = tokens
+      .iter()
+      .map(|t| OwnedToken {
+        token: t.token.clone(),
+        span: Span::of(source, t.origin).expect("tokens should be lexed from source"),
+      })
+      .collect();
+    OwnedTokens {
+      source: source.into(),
+      tokens,
+    }
+  }
+
+  /// The source the tokens were lexed from
+  pub fn source(&self) -> &str {
+    &self.source
+  }
+
+  pub fn tokens(&self) -> &[OwnedToken] {
+    &self.tokens
+  }
+
+  /// The source text of `span`
+  pub fn text(&self, span: Span) -> &str {
+    &self.source[span.range()]
+  }
+
+  /// Copies the tokens into `SpanToken`s that borrow the source, to be wrapped in `SpanTokens` for parsing
+  pub fn span_tokens(&self) -> Vec<SpanToken<'_>> {
+    self
+      .tokens
+      .iter()
+      .map(|t| SpanToken::new(t.token.clone(), self.text(t.span)))
+      .collect()
+  }
+
+  /// Finds the span of borrowed `tokens` within the source.\
+  /// This is `None` when `tokens` weren't borrowed from these owned tokens.
+  pub fn span_of(&self, tokens: &SpanTokens<'_>) -> Option<Span> {
+    Span::of(&self.source, tokens.origin)
+  }
+}
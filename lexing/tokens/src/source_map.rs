@@ -0,0 +1,305 @@
+//! Conversions between byte offsets in source files and line / column positions.
+//!
+//! Spans are byte offsets into the source they were lexed from,\
+//! but editors and error messages count columns in different units:
+//! - `Unit::Utf8` counts bytes, as Rust's `str` does
+//! - `Unit::Utf16` counts UTF-16 code units, as the language server / debug adapter protocols do
+//! - `Unit::Char` counts unicode scalar values, as `SpanTokens::str_range` does
+use std::ops::Range;
+
+/// The unit that offsets and columns are counted in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Unit {
+  Utf8,
+  Utf16,
+  Char,
+}
+
+impl Unit {
+  /// The number of units that `text` takes up
+  pub fn len(self, text: &str) -> usize {
+    match self {
+      Unit::Utf8 => text.len(),
+      Unit::Utf16 => text.encode_utf16().count(),
+      Unit::Char => text.chars().count(),
+    }
+  }
+
+  /// The number of units that `c` takes up
+  fn len_char(self, c: char) -> usize {
+    match self {
+      Unit::Utf8 => c.len_utf8(),
+      Unit::Utf16 => c.len_utf16(),
+      Unit::Char => 1,
+    }
+  }
+
+  /// The byte offset of `offset` units into `text`,\
+  /// or `None` if that's past the end of `text`, or within a character.
+  fn byte_offset(self, text: &str, offset: usize) -> Option<usize> {
+    let mut units = 0;
+    for (i, c) in text.char_indices() {
+      if units == offset {
+        return Some(i);
+      }
+      if units > offset {
+        return None;
+      }
+      units += self.len_char(c);
+    }
+    (units == offset).then_some(text.len())
+  }
+}
+
+/// Converts offsets in a source file into zero-indexed `(line, column)` positions, and back.
+///
+/// ```
+/// # use diom_tokens::source_map::{LineIndex, Unit};
+/// let index = LineIndex::new("let ÿ = 1;\nÿ + 2");
+/// assert_eq!(index.len(), 2);
+/// // `+` is at byte 15, but char 13
+/// assert_eq!(index.position(15, Unit::Utf8), (1, 3));
+/// assert_eq!(index.position(15, Unit::Char), (1, 2));
+/// assert_eq!(index.offset((1, 2), Unit::Char), Some(15));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+  source: Box<str>,
+  /// The byte offset that each line starts at
+  starts: Vec<usize>,
+  /// The char offset that each line starts at
+  char_starts: Vec<usize>,
+}
+
+impl LineIndex {
+  pub fn new(source: &str) -> Self {
+    let newlines = source.match_indices('\n').map(|(i, _)| i + 1);
+    let starts: Vec<_> = std::iter::once(0).chain(newlines).collect();
+    let char_starts = starts.windows(2).scan(0, |chars, line| {
+      *chars += source[line[0]..line[1]].chars().count();
+      Some(*chars)
+    });
+    Self {
+      source: source.into(),
+      char_starts: std::iter::once(0).chain(char_starts).collect(),
+      starts,
+    }
+  }
+
+  /// The source this index was built from
+  pub fn source(&self) -> &str {
+    &self.source
+  }
+
+  /// The number of lines in the source
+  pub fn len(&self) -> usize {
+    self.starts.len()
+  }
+
+  /// Whether the source has no lines, which is never the case
+  pub fn is_empty(&self) -> bool {
+    false
+  }
+
+  /// The byte range of `line`, excluding its line ending
+  pub fn line(&self, line: usize) -> Range<usize> {
+    let start = self.starts[line];
+    let end = self
+      .starts
+      .get(line + 1)
+      .map_or(self.source.len(), |next| next - 1);
+    let end = if self.source[..end].ends_with('\r') {
+      end - 1
+    } else {
+      end
+    };
+    start..end.max(start)
+  }
+
+  /// The `(line, column)` position of the byte `offset`, with the column counted in `unit`
+  pub fn position(&self, offset: usize, unit: Unit) -> (usize, usize) {
+    let line = self.starts.partition_point(|start| *start <= offset) - 1;
+    let start = self.starts[line];
+    (line, unit.len(&self.source[start..offset]))
+  }
+
+  /// The `(line, column)` position of the char `offset`, like those in `SpanTokens::str_range` spans,\
+  /// with the column counted in `unit`.
+  ///
+  /// # Panics
+  ///
+  /// When `offset` is past the end of the source.
+  pub fn char_position(&self, offset: usize, unit: Unit) -> (usize, usize) {
+    let line = self.char_starts.partition_point(|start| *start <= offset) - 1;
+    let start = self.starts[line];
+    let column = Unit::Char
+      .byte_offset(&self.source[start..], offset - self.char_starts[line])
+      .expect("offsets should be within the source");
+    (line, unit.len(&self.source[start..start + column]))
+  }
+
+  /// The byte offset of a `(line, column)` position, with the column counted in `unit`.\
+  /// This is `None` when the position is outside the source, or within a character.
+  pub fn offset(&self, (line, column): (usize, usize), unit: Unit) -> Option<usize> {
+    let start = *self.starts.get(line)?;
+    let end = self
+      .starts
+      .get(line + 1)
+      .copied()
+      .unwrap_or(self.source.len());
+    Some(start + unit.byte_offset(&self.source[start..end], column)?)
+  }
+
+  /// Converts a byte offset into an offset from the start of the source counted in `unit`
+  pub fn to_unit(&self, offset: usize, unit: Unit) -> usize {
+    unit.len(&self.source[..offset])
+  }
+
+  /// Converts an offset from the start of the source counted in `unit` into a byte offset
+  pub fn from_unit(&self, offset: usize, unit: Unit) -> Option<usize> {
+    unit.byte_offset(&self.source, offset)
+  }
+
+  /// The `(line, column)` positions of the start and end of the byte range `span`
+  pub fn span(&self, span: &Range<usize>, unit: Unit) -> ((usize, usize), (usize, usize)) {
+    (
+      self.position(span.start, unit),
+      self.position(span.end, unit),
+    )
+  }
+}
+
+/// Identifies a file within a `SourceMap`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId(pub usize);
+
+/// A source file that's been added to a `SourceMap`
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+  /// The name of the file, usually its path
+  pub name: String,
+  pub index: LineIndex,
+}
+
+impl SourceFile {
+  /// The contents of the file
+  pub fn source(&self) -> &str {
+    self.index.source()
+  }
+}
+
+/// A collection of source files, used to convert spans in any of them to positions.
+///
+/// ```
+/// # use diom_tokens::source_map::{SourceMap, Unit};
+/// let mut map = SourceMap::default();
+/// let main = map.add("main.dm", "let x = 1;\nx");
+/// let lib = map.add("lib.dm", "2");
+///
+/// let x = &map.file(main).source()[11..];
+/// assert_eq!(map.find(x), Some((main, 11)));
+/// assert_eq!(map.position(main, 11, Unit::Char), (1, 0));
+/// assert_eq!(map.file(lib).name, "lib.dm");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+  files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+  /// Adds a file to the map, returning the id to refer to it with
+  pub fn add(&mut self, name: impl Into<String>, source: &str) -> FileId {
+    self.files.push(SourceFile {
+      name: name.into(),
+      index: LineIndex::new(source),
+    });
+    FileId(self.files.len() - 1)
+  }
+
+  /// The file with a given id
+  ///
+  /// # Panics
+  ///
+  /// When `id` wasn't returned by `add` on this map.
+  pub fn file(&self, id: FileId) -> &SourceFile {
+    &self.files[id.0]
+  }
+
+  /// All files in the map, along with their ids
+  pub fn files(&self) -> impl Iterator<Item = (FileId, &SourceFile)> {
+    self.files.iter().enumerate().map(|(i, f)| (FileId(i), f))
+  }
+
+  /// The `(line, column)` position of the byte `offset` in a file
+  pub fn position(&self, id: FileId, offset: usize, unit: Unit) -> (usize, usize) {
+    self.file(id).index.position(offset, unit)
+  }
+
+  /// Finds the file that `part` is a slice of, along with its byte offset in that file.\
+  /// This is `None` when `part` isn't a slice of any file's source in this map.
+  pub fn find(&self, part: &str) -> Option<(FileId, usize)> {
+    let ptr = part.as_ptr() as usize;
+    self.files().find_map(|(id, file)| {
+      let source = file.source();
+      let start = source.as_ptr() as usize;
+      (start..=start + source.len())
+        .contains(&ptr)
+        .then(|| (id, ptr - start))
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{LineIndex, Unit};
+
+  #[test]
+  fn single_line() {
+    let index = LineIndex::new("let x = 1");
+    assert_eq!(index.len(), 1);
+    assert_eq!(index.position(0, Unit::Utf8), (0, 0));
+    assert_eq!(index.position(4, Unit::Utf8), (0, 4));
+    assert_eq!(index.position(9, Unit::Utf8), (0, 9));
+  }
+
+  #[test]
+  fn multi_line() {
+    let index = LineIndex::new("(\n  x;\n  ÿ\n)");
+    assert_eq!(index.len(), 4);
+    assert_eq!(index.position(1, Unit::Utf8), (0, 1));
+    assert_eq!(index.position(2, Unit::Utf8), (1, 0));
+    assert_eq!(index.position(4, Unit::Utf8), (1, 2));
+    assert_eq!(index.position(11, Unit::Utf8), (2, 4));
+    assert_eq!(index.position(11, Unit::Char), (2, 3));
+    assert_eq!(index.span(&(9..13), Unit::Char), ((2, 2), (3, 1)));
+    assert_eq!(index.line(1), 2..6);
+    assert_eq!(index.line(3), 12..13);
+  }
+
+  #[test]
+  fn units() {
+    // `𝄞` is 4 bytes, 2 UTF-16 code units and 1 char
+    let index = LineIndex::new("a𝄞b\r\nc");
+    assert_eq!(index.position(5, Unit::Utf8), (0, 5));
+    assert_eq!(index.position(5, Unit::Utf16), (0, 3));
+    assert_eq!(index.position(5, Unit::Char), (0, 2));
+    assert_eq!(index.offset((0, 3), Unit::Utf16), Some(5));
+    assert_eq!(index.offset((0, 2), Unit::Utf16), None);
+    assert_eq!(index.offset((1, 1), Unit::Char), Some(9));
+    assert_eq!(index.offset((2, 0), Unit::Char), None);
+    assert_eq!(index.to_unit(8, Unit::Char), 5);
+    assert_eq!(index.from_unit(5, Unit::Char), Some(8));
+    assert_eq!(index.line(0), 0..6);
+  }
+
+  #[test]
+  fn char_positions() {
+    let index = LineIndex::new("(\n  ÿ;\n  z\n)");
+    assert_eq!(index.char_position(4, Unit::Char), (1, 2));
+    assert_eq!(index.char_position(9, Unit::Char), (2, 2));
+    assert_eq!(index.char_position(9, Unit::Utf8), (2, 2));
+    assert_eq!(index.char_position(5, Unit::Utf8), (1, 4));
+    assert_eq!(index.char_position(11, Unit::Char), (3, 0));
+    assert_eq!(index.char_position(12, Unit::Char), (3, 1));
+  }
+}
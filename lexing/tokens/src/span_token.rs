@@ -5,34 +5,55 @@ use std::ops::Deref;
 pub struct SpanToken<'a> {
   pub token: Token,
   pub origin: &'a str,
+  /// The whitespace and comments before this token,\
+  /// this is only filled in when lexing losslessly.
+  pub leading: &'a str,
+  /// The whitespace and comments after this token, up to the end of its line,\
+  /// this is only filled in when lexing losslessly.
+  pub trailing: &'a str,
 }
 
 impl<'a> SpanToken<'a> {
+  /// Creates a spanned token without any trivia
+  pub fn new(token: Token, origin: &'a str) -> Self {
+    SpanToken {
+      token,
+      origin,
+      leading: "",
+      trailing: "",
+    }
+  }
+
+  /// Returns the source this token was lexed from, including its trivia.
+  ///
+  /// ```
+  /// # use diom_tokens::{SpanToken, Token::*};
+  /// let token = SpanToken { leading: "  ", trailing: " # one\n", ..SpanToken::new(Float(1.0), "1") };
+  /// assert_eq!(token.lossless(), "  1 # one\n");
+  /// ```
+  pub fn lossless(&self) -> String {
+    [self.leading, self.origin, self.trailing].concat()
+  }
+
   /// Returns a spanned token with the same span, but a different token.
   ///
   /// ```
   /// # use diom_tokens::{SpanToken, Token::*};
-  /// let lbrac = SpanToken { token: LParen, origin: "(" };
-  /// assert_eq!(
-  ///   lbrac.with_token(LCurly),
-  ///   SpanToken { token: LCurly, origin: "(" }
-  /// );
-  /// assert_eq!(
-  ///   lbrac.with_token(Comma),
-  ///   SpanToken { token: Comma, origin: "(" }
-  /// );
+  /// let lbrac = SpanToken::new(LParen, "(");
+  /// assert_eq!(lbrac.with_token(LCurly), SpanToken::new(LCurly, "("));
+  /// assert_eq!(lbrac.with_token(Comma), SpanToken::new(Comma, "("));
   /// ```
   pub fn with_token(&self, token: Token) -> Self {
     Self {
       token,
-      origin: self.origin,
+      ..self.clone()
     }
   }
 }
 
 impl<'a> From<Token> for SpanToken<'a> {
   fn from(token: Token) -> Self {
-    SpanToken { token, origin: "" }
+    SpanToken::new(token, "")
   }
 }
 
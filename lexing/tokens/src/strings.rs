@@ -0,0 +1,57 @@
+//! Formatting of string literals, shared by tokens and the syntax tree.
+//!
+//! Strings can be written in 3 forms, and the most readable one for the string is picked:
+//! - text blocks, for strings over multiple lines
+//! - raw strings, for strings with quotes or backslashes that would otherwise need escaping
+//! - escaped strings, for everything else
+use std::fmt::{Display, Write};
+
+/// Writes `value` as a string literal, in whichever form needs the fewest escapes
+///
+/// ```
+/// # use diom_tokens::strings::StringLiteral;
+/// assert_eq!(StringLiteral("hello").to_string(), r#""hello""#);
+/// assert_eq!(StringLiteral(r"\d+").to_string(), r#"r"\d+""#);
+/// assert_eq!(StringLiteral(r#"say "hi""#).to_string(), r##"r#"say "hi""#"##);
+/// assert_eq!(StringLiteral("tab\t").to_string(), r#""tab\t""#);
+/// assert_eq!(StringLiteral("a\nb").to_string(), "\"\"\"\na\nb\n\"\"\"");
+/// ```
+pub struct StringLiteral<'a>(pub &'a str);
+
+impl Display for StringLiteral<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let value = self.0;
+    let printable = |c: char| !c.is_control();
+    if value.trim_end_matches('\n').contains('\n')
+      && value
+        .chars()
+        .all(|c| printable(c) || c == '\n' || c == '\t')
+    {
+      write_text_block(value, f)
+    } else if value.contains(['"', '\\']) && value.chars().all(printable) {
+      write_raw(value, f)
+    } else {
+      write!(f, "{value:?}")
+    }
+  }
+}
+
+/// Writes `value` as a raw string, with enough `#`s that it doesn't end early
+fn write_raw(value: &str, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+  let hashes = value
+    .match_indices('"')
+    .map(|(i, _)| value[i + 1..].len() - value[i + 1..].trim_start_matches('#').len() + 1)
+    .max()
+    .unwrap_or(0);
+  let hashes = "#".repeat(hashes);
+  write!(f, "r{hashes}\"{value}\"{hashes}")
+}
+
+/// Writes `value` as an unindented text block
+fn write_text_block(value: &str, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+  f.write_str("\"\"\"\n")?;
+  // a line starting with `"""` would close the block
+  f.write_str(&value.replace('\\', "\\\\").replace("\"\"\"", "\\\"\"\""))?;
+  f.write_char('\n')?;
+  f.write_str("\"\"\"")
+}
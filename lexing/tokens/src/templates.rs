@@ -0,0 +1,33 @@
+//! Formatting of the text within templates, shared by tokens and the syntax tree.
+use std::fmt::{Display, Write};
+
+/// Escapes `text` so that it can be written within a template, i.e. `f"..."`.\
+/// This is the same as escaping a string, except braces are escaped too.
+///
+/// ```
+/// # use diom_tokens::templates::Escaped;
+/// assert_eq!(Escaped("say \"hi\"").to_string(), r#"say \"hi\""#);
+/// assert_eq!(Escaped("{x}\n").to_string(), r"\{x\}\n");
+/// ```
+pub struct Escaped<'a>(pub &'a str);
+
+impl Display for Escaped<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    // strings only escape braces within `\u{...}`, so we escape the text between them
+    let mut rest = self.0;
+    while let Some(i) = rest.find(['{', '}']) {
+      let (text, brace) = rest.split_at(i);
+      write_unquoted(text, f)?;
+      f.write_char('\\')?;
+      f.write_str(&brace[..1])?;
+      rest = &brace[1..];
+    }
+    write_unquoted(rest, f)
+  }
+}
+
+/// Writes `text` escaped as a string, without the surrounding quotes
+fn write_unquoted(text: &str, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+  let quoted = format!("{text:?}");
+  f.write_str(&quoted[1..quoted.len() - 1])
+}
@@ -47,6 +47,10 @@ pub enum Token {
   Type,
   /// A `return` keyword (for early returns)
   Return,
+  /// A `use` keyword (for importing modules)
+  Use,
+  /// A keyword reserved for future syntax, i.e. `if`, `match`
+  Reserved(Box<str>),
 
   /* Operators */
   /// A not `!` operator
@@ -79,16 +83,35 @@ pub enum Token {
   Monad,
 
   /* Literals */
-  /// A floating point value, i.e. `2.34`, `-0.02`
+  /// An integer, i.e. `42`, `0xff`, `1_000`
+  Int(u64),
+  /// A floating point value, i.e. `2.34`, `0.2e-1`
   Float(f64),
   /// A single character, i.e. `'x'`, `'\u+26c4'`
   Char(char),
+  /// A string of characters, with escapes already handled, i.e. `"hi\n"`
+  String(Box<str>),
+  /// A template without any interpolated values, i.e. `f"hi"`
+  Template(Box<str>),
+  /// The start of a template, up to its first interpolated value, i.e. `f"hi {`
+  TemplateStart(Box<str>),
+  /// Text between two interpolated values of a template, i.e. `} and {`
+  TemplateMiddle(Box<str>),
+  /// The end of a template, after its last interpolated value, i.e. `}!"`
+  TemplateEnd(Box<str>),
 
   /* String-like */
   /// An identifier, i.e. `x`, `_`, `string_ident_0`
   StringIdent(Box<str>),
   /// A single / multiline comment
   Comment(Box<str>),
+  /// A doc comment, for the declaration or type definition after it, i.e. `## The answer`
+  DocComment(Box<str>),
+
+  /* Recovery */
+  /// A part of the input that failed to parse, left by error recovery.\
+  /// The lexer never produces this, so it can't be confused with valid input.
+  Invalid,
 }
 
 impl Token {
@@ -124,6 +147,8 @@ impl Token {
       | (Let, Let)
       | (Type, Type)
       | (Return, Return)
+      | (Use, Use)
+      | (Reserved(_), Reserved(_))
       | (Not, Not)
       | (And, And)
       | (Or, Or)
@@ -141,7 +166,15 @@ impl Token {
       | (Function, Function)
       | (StringIdent(_), StringIdent(_))
       | (Char(_), Char(_))
+      | (String(_), String(_))
+      | (Template(_), Template(_))
+      | (TemplateStart(_), TemplateStart(_))
+      | (TemplateMiddle(_), TemplateMiddle(_))
+      | (TemplateEnd(_), TemplateEnd(_))
       | (Comment(_), Comment(_))
+      | (DocComment(_), DocComment(_))
+      | (Invalid, Invalid)
+      | (Int(_), Int(_))
       | (Float(_), Float(_)) => true,
       (LParen, _)
       | (RParen, _)
@@ -158,6 +191,8 @@ impl Token {
       | (Let, _)
       | (Type, _)
       | (Return, _)
+      | (Use, _)
+      | (Reserved(_), _)
       | (Not, _)
       | (And, _)
       | (Or, _)
@@ -175,7 +210,15 @@ impl Token {
       | (Function, _)
       | (StringIdent(_), _)
       | (Char(_), _)
+      | (String(_), _)
+      | (Template(_), _)
+      | (TemplateStart(_), _)
+      | (TemplateMiddle(_), _)
+      | (TemplateEnd(_), _)
       | (Comment(_), _)
+      | (DocComment(_), _)
+      | (Invalid, _)
+      | (Int(_), _)
       | (Float(_), _) => false,
     }
   }
@@ -0,0 +1,267 @@
+//! Structured syntax errors, for reporting to users and tools.
+//!
+//! Parsers try many alternatives at once, so a failed parse produces an error for each of them.\
+//! A `Diagnostic` only keeps the errors that got furthest through the input,
+//! merging the tokens that each of them expected.
+use crate::{
+  expressions::BuildError,
+  parsers::{IsApprox, IsExact},
+  In,
+};
+use diom_tokens::Token;
+use nom::error::{ContextError, ErrorKind, FromExternalError, ParseError};
+use std::fmt::{self, Display};
+
+/// A token that would have been accepted where parsing failed
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expected {
+  /// Exactly this token, i.e. `)`
+  Exact(Token),
+  /// Any token of the same kind, i.e. any name for `Token::StringIdent`
+  Like(Token),
+}
+
+impl Display for Expected {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let token = match self {
+      Self::Exact(token) => token,
+      Self::Like(token) => match token {
+        Token::StringIdent(_) => return f.write_str("a name"),
+        Token::Int(_) | Token::Float(_) => return f.write_str("a number"),
+        Token::Char(_) => return f.write_str("a character"),
+        Token::String(_) => return f.write_str("a string"),
+        Token::Template(_) | Token::TemplateStart(_) => return f.write_str("a template"),
+        Token::TemplateMiddle(_) | Token::TemplateEnd(_) => {
+          return f.write_str("the rest of a template")
+        }
+        Token::DocComment(_) => return f.write_str("a doc comment"),
+        token => token,
+      },
+    };
+    write!(f, "`{token}`")
+  }
+}
+
+/// Secondary information about a diagnostic, i.e. the syntax that was being parsed
+#[derive(Clone, Debug, PartialEq)]
+pub struct Note<'a> {
+  pub span: In<'a>,
+  pub message: &'static str,
+}
+
+/// A syntax error at the furthest point that parsing reached
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic<'a> {
+  /// The input from where parsing failed
+  pub span: In<'a>,
+  /// The tokens that would have been accepted at `span`
+  pub expected: Vec<Expected>,
+  /// The syntax being parsed when the error occured, innermost first
+  pub notes: Vec<Note<'a>>,
+  /// Whether the input ended before a bracket was closed
+  pub incomplete: bool,
+  /// Whether a number literal was too large to be held exactly
+  pub too_large: bool,
+}
+
+impl<'a> Diagnostic<'a> {
+  fn new(span: In<'a>) -> Self {
+    Self {
+      span,
+      expected: vec![],
+      notes: vec![],
+      incomplete: false,
+      too_large: false,
+    }
+  }
+
+  /// Whether `self` failed further through the input than `other`.\
+  /// Spans are compared by their position in the source, as groups are parsed from a slice of the input.
+  fn is_after(&self, other: &Self) -> bool {
+    self.span.origin.as_ptr() > other.span.origin.as_ptr()
+  }
+
+  fn expect(mut self, expected: impl IntoIterator<Item = Expected>) -> Self {
+    for expected in expected {
+      if !self.expected.contains(&expected) {
+        self.expected.push(expected);
+      }
+    }
+    self
+  }
+
+  fn note(mut self, note: Note<'a>) -> Self {
+    let start = |note: &Note| note.span.origin.as_ptr();
+    if !self.notes.iter().any(|n| start(n) == start(&note)) {
+      self.notes.push(note);
+    }
+    self
+  }
+
+  /// The token found where parsing failed, or `None` at the end of the input
+  pub fn found(&self) -> Option<&Token> {
+    self.span.first().map(|item| &item.token)
+  }
+}
+
+impl Display for Diagnostic<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.incomplete {
+      return f.write_str("incomplete expression");
+    }
+    if let (true, Some(token)) = (self.too_large, self.found()) {
+      return write!(f, "`{token}` is too large to be held exactly");
+    }
+    // different tokens of the same kind are described the same way
+    let mut expected: Vec<String> = vec![];
+    for description in self.expected.iter().map(ToString::to_string) {
+      if !expected.contains(&description) {
+        expected.push(description);
+      }
+    }
+
+    let Some((last, rest)) = expected.split_last() else {
+      return match self.found() {
+        Some(token) => write!(f, "unexpected `{token}`"),
+        None => f.write_str("unexpected end of input"),
+      };
+    };
+    write!(f, "expected {}", rest.join(", "))?;
+    if !rest.is_empty() {
+      f.write_str(" or ")?;
+    }
+    f.write_str(last)?;
+    // groups are parsed from a slice of the input, so the end of a span isn't always the end of the input
+    match self.found() {
+      Some(token) => write!(f, ", found `{token}`"),
+      None => Ok(()),
+    }
+  }
+}
+
+impl<'a> ParseError<In<'a>> for Diagnostic<'a> {
+  fn from_error_kind(input: In<'a>, kind: ErrorKind) -> Self {
+    Self {
+      incomplete: kind == ErrorKind::Complete,
+      too_large: kind == ErrorKind::TooLarge,
+      ..Self::new(input)
+    }
+  }
+
+  fn append(_: In<'a>, _: ErrorKind, other: Self) -> Self {
+    other
+  }
+
+  /// Keeps the error that got furthest, merging errors that got equally far
+  fn or(self, other: Self) -> Self {
+    if self.is_after(&other) {
+      return self;
+    }
+    if other.is_after(&self) {
+      return other;
+    }
+    let merged = self.expect(other.expected);
+    let merged = other.notes.into_iter().fold(merged, Self::note);
+    Self {
+      incomplete: merged.incomplete || other.incomplete,
+      too_large: merged.too_large || other.too_large,
+      ..merged
+    }
+  }
+}
+
+impl<'a> ContextError<In<'a>> for Diagnostic<'a> {
+  /// Notes the syntax being parsed, when it started before the error
+  fn add_context(input: In<'a>, ctx: &'static str, other: Self) -> Self {
+    if input.origin.as_ptr() >= other.span.origin.as_ptr() {
+      return other;
+    }
+    other.note(Note {
+      span: input,
+      message: ctx,
+    })
+  }
+}
+
+impl<'a> FromExternalError<In<'a>, IsExact> for Diagnostic<'a> {
+  fn from_external_error(input: In<'a>, _: ErrorKind, e: IsExact) -> Self {
+    Self::new(input).expect(e.0.into_iter().map(Expected::Exact))
+  }
+}
+
+impl<'a> FromExternalError<In<'a>, IsApprox> for Diagnostic<'a> {
+  fn from_external_error(input: In<'a>, _: ErrorKind, e: IsApprox) -> Self {
+    Self::new(input).expect(e.0.into_iter().map(Expected::Like))
+  }
+}
+
+impl<'a> FromExternalError<In<'a>, BuildError> for Diagnostic<'a> {
+  fn from_external_error(_: In<'a>, _: ErrorKind, e: BuildError) -> Self {
+    match e {}
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::Diagnostic;
+  use crate::{recovery::recover_program, tests::utils::quick_lex};
+  use diom_tokens::SpanTokens;
+
+  /// The messages and notes for each error in `code`
+  fn diagnose(code: &str) -> Vec<(String, Vec<&'static str>)> {
+    let tokens = quick_lex(code);
+    let (_, errors) = recover_program::<Diagnostic>(SpanTokens::new(&tokens, code));
+    errors
+      .iter()
+      .map(|error| {
+        let notes = error.notes.iter().map(|note| note.message).collect();
+        (error.to_string(), notes)
+      })
+      .collect()
+  }
+
+  #[test]
+  fn merges_expected() {
+    let errors = diagnose("{x 1}");
+    assert_eq!(
+      errors,
+      [("expected `,` or `}`, found `1`".into(), vec!["struct"])]
+    );
+  }
+
+  #[test]
+  fn kinds() {
+    let [(message, _)] = &diagnose("let = 2")[..] else {
+      panic!("expected a single error");
+    };
+    assert_eq!(message, "expected `[`, `_`, `{` or a name, found `=`");
+  }
+
+  #[test]
+  fn separate_errors() {
+    let errors = diagnose("[1, 2 +]; {a 1}");
+    let messages: Vec<_> = errors.iter().map(|(message, _)| &**message).collect();
+    assert_eq!(messages.len(), 2, "{messages:?}");
+    assert!(messages[0].starts_with("expected `let`"), "{messages:?}");
+    assert_eq!(messages[1], "expected `,` or `}`, found `1`");
+  }
+
+  #[test]
+  fn too_large() {
+    assert_eq!(
+      diagnose("1 + 9007199254740993"),
+      [(
+        "`9007199254740993` is too large to be held exactly".into(),
+        vec!["expression"]
+      )]
+    );
+  }
+
+  #[test]
+  fn incomplete() {
+    assert_eq!(
+      diagnose("(let x = 1; y"),
+      [("incomplete expression".into(), vec![])]
+    );
+  }
+}
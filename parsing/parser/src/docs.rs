@@ -0,0 +1,73 @@
+//! Doc comments, attached to the declaration or type definition after them.
+use crate::{
+  errors::{PResult, SyntaxError},
+  parsers::matches,
+  In,
+};
+use diom_syntax::Ptr;
+use diom_tokens::Token;
+use nom::{multi::many0, Parser};
+
+/// Parses any doc comments, joining them into a single doc with a line for each comment
+///
+/// ```_
+/// ## The answer to life,
+/// ## the universe and everything
+/// let answer = 42;
+/// ```
+pub fn parse_docs<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Option<Ptr<str>>, E> {
+  let (input, docs) = many0(matches(Token::DocComment("".into()))).parse(input)?;
+  let lines: Vec<_> = docs
+    .into_iter()
+    .map(|doc| match doc.token {
+      Token::DocComment(line) => line,
+      _ => unreachable!("we've parsed a `DocComment` token"),
+    })
+    .collect();
+  Ok((input, (!lines.is_empty()).then(|| lines.join("\n").into())))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::tests::utils::{quick_lex, quick_parse};
+  use diom_syntax::{
+    expressions::{Expression, Statement},
+    types::TypeDef,
+  };
+  use diom_tokens::SpanTokens;
+
+  #[test]
+  fn declare() {
+    let code = "## The answer\n## to everything\nlet x = 42";
+    let tokens = quick_lex(code);
+    let expr = quick_parse(code, SpanTokens::new(&tokens, code));
+    let Expression::Declare(declare) = expr else {
+      panic!("expected a declaration, found {expr:?}")
+    };
+    assert_eq!(declare.doc.as_deref(), Some(" The answer\n to everything"));
+    // the docs aren't part of the declaration's span
+    assert_eq!(declare.info.origin, "let x = 42");
+  }
+
+  #[test]
+  fn typedef() {
+    let code = "(## A number\ntype Number = Float; 2)";
+    let tokens = quick_lex(code);
+    let expr = quick_parse(code, SpanTokens::new(&tokens, code));
+    let Expression::Block(block) = expr else {
+      panic!("expected a block, found {expr:?}")
+    };
+    let Statement::TypeDef(TypeDef::Alias(alias)) = &block.statements[0] else {
+      panic!("expected a type alias, found {:?}", block.statements[0])
+    };
+    assert_eq!(alias.doc.as_deref(), Some(" A number"));
+  }
+
+  #[test]
+  fn roundtrip() {
+    let code = "## The answer\nlet x = 42";
+    let tokens = quick_lex(code);
+    let expr = quick_parse(code, SpanTokens::new(&tokens, code));
+    assert_eq!(expr.to_string(), "## The answer\nlet x=42");
+  }
+}
@@ -1,20 +1,16 @@
 use super::super::parse_expression;
 use crate::{
   errors::{PResult, SyntaxError},
-  parsers::{group, token_separated_list},
+  parsers::{end, group, token_separated_list},
   types::parse_typedef,
   In,
 };
 use diom_syntax::expressions::{Block, Statement};
 use diom_tokens::Token;
-use nom::{
-  branch::alt,
-  combinator::{consumed, eof},
-  error::context,
-  sequence::terminated,
-  Parser,
-};
+use nom::{branch::alt, combinator::consumed, error::context, sequence::terminated, Parser};
 
+/// Parses a statement within a block.\
+/// Modules can only be imported at the top level, see `parse_top_level`.
 pub fn parse_statement<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Statement<In<'a>>, E> {
   alt((
     context("type def", parse_typedef).map(Statement::TypeDef),
@@ -26,7 +22,10 @@ pub fn parse_statement<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Sta
 pub fn parse_block<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Block<In<'a>>, E> {
   let parse_inner = context(
     "block contents",
-    terminated(token_separated_list(Token::Semi, parse_statement), eof),
+    terminated(
+      token_separated_list(Token::Semi, parse_statement),
+      end([Token::Semi, Token::RParen]),
+    ),
   );
   let parser = group(Token::LParen, Token::RParen).and_then(parse_inner);
 
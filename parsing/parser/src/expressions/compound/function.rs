@@ -1,7 +1,7 @@
 use crate::{
   errors::{PResult, SyntaxError},
   expressions::parse_expression,
-  parsers::{group, matches},
+  parsers::{end, group, matches},
   patterns::parse_pattern,
   types::parse_type,
   In,
@@ -72,7 +72,10 @@ pub fn parse_arm<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, FunctionA
 pub fn parse_arms<'a, E: SyntaxError<'a>>(
   input: In<'a>,
 ) -> PResult<'a, (In<'a>, Vec<FunctionArm<In<'a>>>), E> {
-  let parse_inner = terminated(separated_list0(matches(Token::Comma), parse_arm), eof);
+  let parse_inner = terminated(
+    separated_list0(matches(Token::Comma), parse_arm),
+    end([Token::Comma, Token::RCurly]),
+  );
   let (input, (info, arms)) =
     consumed(group(Token::LCurly, Token::RCurly).and_then(parse_inner)).parse(input)?;
 
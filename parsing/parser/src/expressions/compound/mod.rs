@@ -5,11 +5,13 @@ use nom::{branch::alt, error::context, Parser};
 mod arrays;
 pub use arrays::parse_array;
 mod structs;
-pub use structs::parse_struct;
+pub use structs::{parse_struct, parse_struct_field};
 pub mod function;
 pub use function::parse_function;
 mod block;
-pub use block::parse_block;
+pub use block::{parse_block, parse_statement};
+mod templates;
+pub use templates::parse_template;
 
 pub fn parse_compound_value<'a, E: SyntaxError<'a>>(
   input: In<'a>,
@@ -21,6 +23,7 @@ pub fn parse_compound_value<'a, E: SyntaxError<'a>>(
       context("struct", parse_struct.map(Expression::Struct)),
       context("function", parse_function.map(Expression::Function)),
       context("block", parse_block.map(Expression::Block)),
+      context("template", parse_template.map(Expression::Template)),
     )),
   )
   .parse(input)
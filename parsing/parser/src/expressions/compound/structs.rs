@@ -2,16 +2,18 @@ use crate::{
   errors::{PResult, SyntaxError},
   expressions::parse_expression,
   idents::parse_method,
-  parsers::{group, matches},
+  parsers::{end, group, matches},
+  recovery::parse_invalid,
   In,
 };
 use diom_syntax::{
   expressions::{Expression, Struct},
-  idents::Method,
+  idents::{Method, Name},
 };
 use diom_tokens::Token;
 use nom::{
-  combinator::{consumed, eof, opt},
+  branch::alt,
+  combinator::{consumed, opt},
   error::context,
   multi::separated_list0,
   sequence::{preceded, terminated},
@@ -34,14 +36,23 @@ pub fn parse_struct_field<'a, E: SyntaxError<'a>>(
       ))
     });
 
-  let (input, (field, value)) = context("struct field", parser).parse(input)?;
+  // fields replaced during error recovery keep their place in the struct
+  let parse_error = parse_invalid.map(|error| {
+    let field = Method {
+      name: Name::Literal("".into()),
+      info: error.info,
+    };
+    (field, Expression::Error(error))
+  });
+
+  let (input, (field, value)) = context("struct field", alt((parse_error, parser))).parse(input)?;
   Ok((input, (field, value)))
 }
 
 pub fn parse_struct<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Struct<In<'a>>, E> {
   let parse_inner = terminated(
     separated_list0(matches(Token::Comma), parse_struct_field),
-    eof,
+    end([Token::Comma, Token::RCurly]),
   );
   let parser = group(Token::LCurly, Token::RCurly).and_then(parse_inner);
 
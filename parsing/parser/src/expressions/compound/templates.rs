@@ -0,0 +1,83 @@
+use crate::{
+  errors::{PResult, SyntaxError},
+  expressions::parse_expression,
+  parsers::{matches, single_item},
+  In,
+};
+use diom_syntax::{
+  expressions::{Expression, Template},
+  Ptr,
+};
+use diom_tokens::Token;
+use nom::{
+  combinator::{consumed, eof},
+  error::context,
+  sequence::terminated,
+  Input, Parser,
+};
+use std::num::NonZero;
+
+pub fn parse_template<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Template<In<'a>>, E> {
+  let (input, (info, (strings, values))) = consumed(parse_parts).parse(input)?;
+  Ok((
+    input,
+    Template {
+      strings,
+      values,
+      info,
+    },
+  ))
+}
+
+type Parts<'a> = (Vec<Ptr<str>>, Vec<Expression<In<'a>>>);
+
+/// Parses the text and interpolated values of a template
+fn parse_parts<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Parts<'a>, E> {
+  let start = [Token::Template("".into()), Token::TemplateStart("".into())];
+  let (mut input, first) = context("template start", matches(start)).parse(input)?;
+  let text = match first.token {
+    Token::Template(text) => return Ok((input, (vec![text.into()], vec![]))),
+    Token::TemplateStart(text) => text,
+    _ => unreachable!("we've parsed a template start"),
+  };
+
+  let mut strings = vec![text.into()];
+  let mut values = vec![];
+  loop {
+    // find the end of the interpolated value, skipping over nested templates
+    let mut depth = 0usize;
+    let idx = input.iter().position(|tok| match tok.token {
+      Token::TemplateStart(_) => {
+        depth += 1;
+        false
+      }
+      Token::TemplateMiddle(_) => depth == 0,
+      Token::TemplateEnd(_) if depth > 0 => {
+        depth -= 1;
+        false
+      }
+      Token::TemplateEnd(_) => true,
+      _ => false,
+    });
+    let Some(i) = idx else {
+      return Err(nom::Err::Incomplete(nom::Needed::Size(
+        NonZero::new(depth + 1).unwrap(),
+      )));
+    };
+
+    let (rest, inner) = input.take_split(i);
+    let (_, value) = context("template value", terminated(parse_expression(), eof)).parse(inner)?;
+    values.push(value);
+
+    let (rest, end) = single_item().parse(rest)?;
+    input = rest;
+    match end.token {
+      Token::TemplateMiddle(text) => strings.push(text.into()),
+      Token::TemplateEnd(text) => {
+        strings.push(text.into());
+        return Ok((input, (strings, values)));
+      }
+      _ => unreachable!("we've found the end of an interpolated value"),
+    }
+  }
+}
@@ -5,16 +5,54 @@ use crate::{
 };
 use diom_syntax::expressions::Float;
 use diom_tokens::Token;
-use nom::{combinator::consumed, Parser};
+use nom::{combinator::consumed, error::ErrorKind, Err, Parser};
 
+/// Parses a number literal.\
+/// Diom only has floats for now, so integer literals are converted to floats,
+/// failing on integers that a float can't hold exactly, i.e. `9007199254740993`.
 pub fn parse_float<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Float<In<'a>>, E> {
-  let parser = matches(Token::Float(0.0));
+  let parser = matches([Token::Float(0.0), Token::Int(0)]);
   let (input, (info, v)) = consumed(parser).parse(input)?;
-  Ok((
-    input,
-    Float {
-      info,
-      value: v.token.try_into().unwrap(),
-    },
-  ))
+  let value = match v.token {
+    Token::Int(value) => {
+      exact(value).ok_or_else(|| Err::Failure(E::from_error_kind(info, ErrorKind::TooLarge)))?
+    }
+    token => token.try_into().unwrap(),
+  };
+  Ok((input, Float { info, value }))
+}
+
+/// Converts `value` to a float, when that doesn't round it
+fn exact(value: u64) -> Option<f64> {
+  let float = value as f64;
+  // `u64::MAX` rounds up to 2^64, which is then saturated back to `u64::MAX`
+  (float < u64::MAX as f64 && float as u64 == value).then_some(float)
+}
+
+#[cfg(test)]
+mod test {
+  use super::parse_float;
+  use crate::tests::utils::quick_lex;
+  use diom_tokens::SpanTokens;
+  use nom::error::Error;
+
+  fn parse(code: &str) -> Option<f64> {
+    let tokens = quick_lex(code);
+    let (_, float) = parse_float::<Error<_>>(SpanTokens::new(&tokens, code)).ok()?;
+    Some(float.value)
+  }
+
+  #[test]
+  fn integers() {
+    assert_eq!(parse("42"), Some(42.0));
+    assert_eq!(parse("9007199254740992"), Some(9007199254740992.0));
+    // powers of two are exact, however large
+    assert_eq!(parse("0x8000000000000000"), Some(9223372036854775808.0));
+  }
+
+  #[test]
+  fn inexact_integers() {
+    assert_eq!(parse("9007199254740993"), None);
+    assert_eq!(parse("0xffffffffffffffff"), None);
+  }
 }
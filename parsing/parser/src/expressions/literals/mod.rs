@@ -6,6 +6,8 @@ mod chars;
 use chars::parse_char;
 mod floats;
 use floats::parse_float;
+mod strings;
+pub(crate) use strings::parse_string;
 
 pub fn parse_literal_value<'a, E: SyntaxError<'a>>(
   input: In<'a>,
@@ -15,6 +17,7 @@ pub fn parse_literal_value<'a, E: SyntaxError<'a>>(
     alt((
       parse_char.map(Expression::Char),
       parse_float.map(Expression::Float),
+      parse_string.map(Expression::String),
       parse_ident.map(Expression::Var),
     )),
   )
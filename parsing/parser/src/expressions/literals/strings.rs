@@ -0,0 +1,23 @@
+use crate::{
+  errors::{PResult, SyntaxError},
+  parsers::matches,
+  In,
+};
+use diom_syntax::expressions::String;
+use diom_tokens::Token;
+use nom::{combinator::consumed, Parser};
+
+pub fn parse_string<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, String<In<'a>>, E> {
+  let parser = matches(Token::String("".into()));
+  let (input, (info, s)) = consumed(parser).parse(input)?;
+  let Token::String(value) = s.token else {
+    unreachable!("we've parsed a `String` token")
+  };
+  Ok((
+    input,
+    String {
+      info,
+      value: value.into(),
+    },
+  ))
+}
@@ -6,6 +6,7 @@
 use crate::{
   errors::SyntaxError,
   parsers::{matches, token},
+  recovery::parse_invalid,
   In,
 };
 use diom_syntax::expressions::Expression;
@@ -15,6 +16,7 @@ use nom_language::precedence::{binary_op, precedence, unary_op, Assoc, Operation
 
 mod compound;
 use compound::parse_compound_value;
+pub(crate) use compound::{function::parse_parameter, parse_statement, parse_struct_field};
 mod infix;
 use infix::*;
 mod postfix;
@@ -23,6 +25,7 @@ mod prefix;
 use prefix::*;
 mod literals;
 use literals::parse_literal_value;
+pub(crate) use literals::parse_string;
 mod scopes;
 use scopes::parse_scope_value;
 
@@ -51,14 +54,13 @@ use scopes::parse_scope_value;
 /// 1. `return` statements
 /// 1. field accesses
 /// 1. indexing
-/// 1. explicit function calls
+/// 1. explicit and implicit function calls
 /// 1. *"method-like"* operators
 /// 1. `*` and `/`
 /// 1. `+` and `-`
 /// 1. `&` and `|`
 /// 1. `<`, `>`, `<=`, `>=`, `==` and `!=`
 /// 1. `=` assignment
-/// 1. implicit function calls
 pub fn parse_expression<'a, E: SyntaxError<'a>>(
 ) -> impl Parser<In<'a>, Output = Expression<In<'a>>, Error = E> {
   let parser = precedence(
@@ -74,7 +76,7 @@ pub fn parse_expression<'a, E: SyntaxError<'a>>(
       unary_op(2, parse_field.map(PartialPostFix::Field)),
       unary_op(2, parse_index.map(PartialPostFix::Index)),
       unary_op(2, parse_explicit_call.map(PartialPostFix::Call)),
-      // unary_op(9, parse_implicit_call.map(PartialPostFix::Call)),
+      unary_op(2, parse_implicit_call.map(PartialPostFix::Call)),
     )),
     alt((
       binary_op(
@@ -114,7 +116,12 @@ pub fn parse_expression<'a, E: SyntaxError<'a>>(
     )),
     context(
       "value",
-      alt((parse_scope_value, parse_literal_value, parse_compound_value)),
+      alt((
+        parse_invalid.map(Expression::Error),
+        parse_scope_value,
+        parse_literal_value,
+        parse_compound_value,
+      )),
     ),
     apply_operation,
   );
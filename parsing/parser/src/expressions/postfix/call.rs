@@ -1,10 +1,11 @@
 use crate::{
   common::PResult,
   errors::SyntaxError,
-  expressions::parse_expression,
-  parsers::{group, token_separated_list},
+  expressions::{parse_compound_value, parse_expression, parse_literal_value},
+  idents::parse_ident,
+  parsers::{group, matches, token_separated_list},
   utils::merge_spans,
-  In,
+  In, Item,
 };
 use diom_info_traits::InfoRef as _;
 use diom_syntax::{
@@ -12,7 +13,13 @@ use diom_syntax::{
   Ptr,
 };
 use diom_tokens::Token;
-use nom::{combinator::consumed, error::context, Parser};
+use nom::{
+  branch::alt,
+  combinator::{consumed, not},
+  error::context,
+  sequence::{preceded, terminated},
+  Parser,
+};
 
 pub struct PostFixCall<I> {
   pub(crate) args: Vec<Expression<I>>,
@@ -32,16 +39,49 @@ impl<'a> PostFixCall<In<'a>> {
   }
 }
 
+/// Parses a single argument written directly after a function, i.e. `f 1`, `f "name"` or `f x`.\
+/// Implicit calls bind as tightly as explicit calls, so `f 1 2` is `f(1)(2)`.
+///
+/// Names written between two values are still infix methods,\
+/// so `a max b` is `max(a, b)` and only the name in `f x` is an argument.
 pub fn parse_implicit_call<'a, E: SyntaxError<'a>>(
   input: In<'a>,
 ) -> PResult<'a, PostFixCall<In<'a>>, E> {
-  let parser = consumed(parse_expression()).map(|(info, arg)| PostFixCall {
+  let name = terminated(parse_ident, not(starts_expression())).map(Expression::Var);
+  // names are only arguments when `name` accepts them
+  let value = preceded(
+    not(matches(Token::StringIdent("".into()))),
+    alt((parse_literal_value, parse_compound_value)),
+  );
+  let parser = consumed(alt((name, value))).map(|(info, arg)| PostFixCall {
     args: vec![arg],
     info,
   });
   context("implicit call", parser).parse(input)
 }
 
+/// Matches the tokens that an expression can start with
+fn starts_expression<'a, E: SyntaxError<'a>>() -> impl Parser<In<'a>, Output = Item<'a>, Error = E>
+{
+  matches([
+    Token::LParen,
+    Token::LCurly,
+    Token::LBrace,
+    Token::Let,
+    Token::Return,
+    Token::Plus,
+    Token::Minus,
+    Token::Int(0),
+    Token::Float(0.0),
+    Token::Char(' '),
+    Token::String("".into()),
+    Token::Template("".into()),
+    Token::TemplateStart("".into()),
+    Token::StringIdent("".into()),
+    Token::DocComment("".into()),
+  ])
+}
+
 pub fn parse_explicit_call<'a, E: SyntaxError<'a>>(
   input: In<'a>,
 ) -> PResult<'a, PostFixCall<In<'a>>, E> {
@@ -50,3 +90,53 @@ pub fn parse_explicit_call<'a, E: SyntaxError<'a>>(
   let parser = consumed(parser).map(|(info, args)| PostFixCall { args, info });
   context("explicit call", parser).parse(input)
 }
+
+#[cfg(test)]
+mod test {
+  use crate::tests::utils::{quick_lex, quick_parse};
+  use diom_syntax::expressions::Expression;
+  use diom_tokens::SpanTokens;
+
+  fn displayed(code: &str) -> String {
+    let tokens = quick_lex(code);
+    quick_parse(code, SpanTokens::new(&tokens, code)).to_string()
+  }
+
+  #[test]
+  fn implicit() {
+    assert_eq!(displayed("f 1"), "f(1)");
+    assert_eq!(displayed("f x"), "f(x)");
+    assert_eq!(displayed(r#"print "hi""#), r#"print("hi")"#);
+    assert_eq!(displayed("f {x: 1}"), "f({x:1})");
+  }
+
+  #[test]
+  fn left_associative() {
+    assert_eq!(displayed("f 1 2"), "f(1)(2)");
+    assert_eq!(displayed("f 1 x"), "f(1)(x)");
+  }
+
+  #[test]
+  fn precedence() {
+    assert_eq!(displayed("f 1 + g 2"), "f(1) + g(2)");
+    assert_eq!(displayed("- f 1"), "- f(1)");
+    assert_eq!(displayed("f 1 .x"), "f(1).x");
+    assert_eq!(displayed("f x = 1"), "f(x)=1");
+  }
+
+  #[test]
+  fn infix_methods() {
+    // names between two values are still infix methods
+    let code = "a max b";
+    let tokens = quick_lex(code);
+    let expr = quick_parse(code, SpanTokens::new(&tokens, code));
+    assert!(matches!(expr, Expression::Infix(_)));
+    assert_eq!(displayed("f x y"), "f x y");
+    assert_eq!(displayed("f x - 1"), "f x - 1");
+    assert_eq!(displayed("f 1 max g 2"), "f(1) max g(2)");
+    assert_eq!(displayed("x contains 3"), "x contains 3");
+    assert_eq!(displayed("xs.len max 3"), "xs.len max 3");
+    assert_eq!(displayed("a max b max c"), "a max b max c");
+    assert_eq!(displayed("a.b x"), "a.b(x)");
+  }
+}
@@ -1,6 +1,6 @@
 use crate::{
-  errors::SyntaxError, parsers::matches, patterns::parse_pattern, types::parse_type,
-  utils::merge_spans, In,
+  docs::parse_docs, errors::SyntaxError, parsers::matches, patterns::parse_pattern,
+  types::parse_type, utils::merge_spans, In,
 };
 use diom_info_traits::InfoRef;
 use diom_syntax::{
@@ -17,6 +17,7 @@ use nom::{
 };
 
 pub struct PartialDeclare<I> {
+  doc: Option<Ptr<str>>,
   pattern: Pattern<I>,
   annotation: Option<Type<I>>,
   info: I,
@@ -28,6 +29,7 @@ impl<'a> PartialDeclare<In<'a>> {
   pub unsafe fn apply(self, value: Expression<In<'a>>) -> Declare<In<'a>> {
     let info = *value.info();
     Declare {
+      doc: self.doc,
       pattern: self.pattern,
       annotation: self.annotation,
       value: Ptr::new(value),
@@ -43,8 +45,9 @@ pub fn parse_let<'a, E: SyntaxError<'a>>(
   let parse_rest = terminated(parse_pattern.and(opt(parse_annot)), matches(Token::Assign));
   let parser = consumed(preceded(matches(Token::Let), cut(parse_rest)));
 
-  parser
-    .map(|(info, (pattern, annotation))| PartialDeclare {
+  (parse_docs, parser)
+    .map(|(doc, (info, (pattern, annotation)))| PartialDeclare {
+      doc,
       pattern,
       annotation,
       info,
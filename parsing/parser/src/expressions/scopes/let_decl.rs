@@ -1,4 +1,5 @@
 use crate::{
+  docs::parse_docs,
   errors::{PResult, SyntaxError},
   expressions::parse_expression,
   parsers::matches,
@@ -53,10 +54,12 @@ pub fn parse_let<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Declare<I
     ),
   );
 
+  let (input, doc) = parse_docs(input)?;
   let (input, (info, ((pattern, annotation), value))) = consumed(parser).parse(input)?;
   Ok((
     input,
     Declare {
+      doc,
       info,
       pattern,
       annotation,
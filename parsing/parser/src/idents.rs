@@ -1,44 +1,64 @@
 use crate::common::PResult;
 use crate::errors::SyntaxError;
-use crate::parsers::{single_item, IsExact};
+use crate::parsers::{matches, token};
 use crate::In;
 use diom_syntax::{
   from_box,
   idents::{Ident, Method, Op},
 };
 use diom_tokens::Token;
+use nom::branch::alt;
 use nom::combinator::consumed;
 use nom::Parser;
 
+/// The tokens of each operator symbol
+const SYMBOLS: [Token; 13] = [
+  Token::Not,
+  Token::And,
+  Token::Or,
+  Token::Plus,
+  Token::Minus,
+  Token::Times,
+  Token::Divide,
+  Token::Eq,
+  Token::Ne,
+  Token::Lt,
+  Token::Gt,
+  Token::LtEq,
+  Token::GtEq,
+];
+
 pub fn parse_ident<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Ident<In<'a>>, E> {
-  let mut parser = consumed(single_item()).map_res(|(info, tok)| match tok.token {
-    Token::StringIdent(name) => Ok(Ident {
+  let mut parser = consumed(matches(Token::StringIdent("".into()))).map(|(info, tok)| {
+    let Token::StringIdent(name) = tok.token else {
+      unreachable!("only names are matched")
+    };
+    Ident {
       name: from_box(name),
       info,
-    }),
-    _ => Err(IsExact("Ident".into())),
+    }
   });
 
   parser.parse(input)
 }
 
 pub fn parse_op<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Op<In<'a>>, E> {
-  let mut parser = consumed(single_item()).map_res(|(info, tok)| {
-    Ok::<_, IsExact>(Op {
-      sym: tok.token.try_into().map_err(|_| IsExact("Op".into()))?,
-      info,
-    })
+  let mut parser = consumed(token(SYMBOLS)).map(|(info, tok)| Op {
+    sym: tok.token.try_into().expect("only symbols are matched"),
+    info,
   });
 
   parser.parse(input)
 }
 
 pub fn parse_method<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Method<In<'a>>, E> {
-  let mut parser = consumed(single_item()).map_res(|(info, tok)| {
-    Ok::<_, IsExact>(Method {
-      name: tok.token.try_into().map_err(|_| IsExact("Ident".into()))?,
-      info,
-    })
+  let name = alt((token(SYMBOLS), matches(Token::StringIdent("".into()))));
+  let mut parser = consumed(name).map(|(info, tok)| Method {
+    name: tok
+      .token
+      .try_into()
+      .expect("only names and symbols are matched"),
+    info,
   });
 
   parser.parse(input)
@@ -4,19 +4,30 @@
 //! at once, i.e. they are parsing a slice and not an iterator.
 use common::PResult;
 use diom_syntax::expressions::Expression;
+use diom_syntax::program::Program;
 use diom_tokens::{SpanToken, SpanTokens};
 use expressions::parse_expression;
-use nom::Parser;
+use nom::{branch::alt, combinator::eof, sequence::terminated, Parser};
+use program::parse_program;
 
 use crate::errors::SyntaxError;
 
 mod common;
+pub mod diagnostics;
+pub mod docs;
 pub mod errors;
 pub mod expressions;
 pub mod idents;
+pub mod loader;
+pub mod modules;
+pub mod owned;
 pub mod parsers;
 pub mod path;
 pub mod patterns;
+pub mod program;
+pub mod recovery;
+#[cfg(feature = "testing")]
+pub mod testing;
 #[cfg(test)]
 mod tests;
 pub mod types;
@@ -26,10 +37,16 @@ mod utils;
 #[derive(PartialEq)]
 pub enum SyntaxNode<I> {
   Expression(Expression<I>),
+  Program(Program<I>),
 }
 
+/// Parses a single expression when that's all of the input, otherwise parses a program
 pub fn parse_node<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, SyntaxNode<In<'a>>, E> {
-  parse_expression().map(SyntaxNode::Expression).parse(input)
+  alt((
+    terminated(parse_expression(), eof).map(SyntaxNode::Expression),
+    parse_program.map(SyntaxNode::Program),
+  ))
+  .parse(input)
 }
 
 type In<'a> = SpanTokens<'a>;
@@ -0,0 +1,390 @@
+//! Loading of the modules imported by `use` statements.
+//!
+//! Modules are parsed once each and cached by their canonical path,\
+//! so that modules imported from several places share the same exports.
+use crate::{diagnostics::Diagnostic, program::parse_program};
+use diom_info_traits::InfoMap;
+use diom_lexer::{idents::is_name, recovery::recover_tokens};
+use diom_syntax::{
+  expressions::Statement,
+  modules::{ModulePath, Use},
+  program::Program,
+};
+use diom_tokens::{
+  owned::Span,
+  source_map::{LineIndex, Unit},
+  SpanTokens,
+};
+use nom::{combinator::all_consuming, Parser};
+use std::{
+  collections::HashMap,
+  fmt, fs, io,
+  ops::Range,
+  path::{Path, PathBuf},
+};
+
+/// The extension of Diom files
+pub const EXTENSION: &str = "diom";
+
+/// The index of a loaded module
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ModuleId(usize);
+
+impl ModuleId {
+  /// The index of the module within `Loader::modules`
+  pub fn index(self) -> usize {
+    self.0
+  }
+}
+
+/// A parsed module, along with the modules it imports
+#[derive(Debug)]
+pub struct Module {
+  /// The canonical path of the module's file
+  pub path: PathBuf,
+  pub source: String,
+  /// The parsed module, with char ranges into `source` as info
+  pub program: Program<Range<usize>>,
+  /// The modules imported by `program`, keyed by how they're referred to
+  pub imports: HashMap<Box<str>, ModuleId>,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+  /// A module's file couldn't be read
+  Io(PathBuf, io::Error),
+  /// No file was found for a module, from the given directory
+  NotFound(Box<str>, PathBuf),
+  /// Modules import each other, in the order given
+  Cycle(Vec<PathBuf>),
+  /// A module's exports can't be bound to a name, i.e. for `"./my-lib.diom"`
+  Unbound(Box<str>),
+  /// Two modules would be bound to the same name, i.e. `use a.util; use b.util`
+  Rebound {
+    binding: Box<str>,
+    first: Box<str>,
+    second: Box<str>,
+  },
+  /// A module's source couldn't be lexed or parsed
+  Syntax {
+    path: PathBuf,
+    line: usize,
+    column: usize,
+    message: String,
+  },
+}
+
+impl fmt::Display for LoadError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Io(path, err) => write!(f, "{}: {err}", path.display()),
+      Self::NotFound(module, dir) => {
+        write!(f, "module {module} not found from {}", dir.display())
+      }
+      Self::Cycle(paths) => {
+        f.write_str("modules import each other: ")?;
+        for (i, path) in paths.iter().enumerate() {
+          if i > 0 {
+            f.write_str(" -> ")?;
+          }
+          write!(f, "{}", path.display())?;
+        }
+        Ok(())
+      }
+      Self::Unbound(module) => write!(f, "module {module} can't be bound to a name"),
+      Self::Rebound {
+        binding,
+        first,
+        second,
+      } => write!(
+        f,
+        "modules {first} and {second} are both bound to {binding}"
+      ),
+      Self::Syntax {
+        path,
+        line,
+        column,
+        message,
+      } => write!(f, "{}:{line}:{column}: {message}", path.display()),
+    }
+  }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Resolves, parses and caches modules
+#[derive(Debug, Default)]
+pub struct Loader {
+  /// Directories to look for named modules in, after the importing module's directory
+  search: Vec<PathBuf>,
+  /// Loaded modules, with each module after the modules it imports
+  modules: Vec<Module>,
+  ids: HashMap<PathBuf, ModuleId>,
+  /// The modules currently being loaded, to detect cycles
+  loading: Vec<PathBuf>,
+}
+
+impl Loader {
+  pub fn new(search: Vec<PathBuf>) -> Self {
+    Self {
+      search,
+      ..Self::default()
+    }
+  }
+
+  /// The loaded modules, with each module after the modules it imports
+  pub fn modules(&self) -> &[Module] {
+    &self.modules
+  }
+
+  pub fn module(&self, id: ModuleId) -> &Module {
+    &self.modules[id.0]
+  }
+
+  /// Loads the module at `path`, along with everything it imports
+  pub fn load(&mut self, path: &Path) -> Result<ModuleId, LoadError> {
+    let path = fs::canonicalize(path).map_err(|err| LoadError::Io(path.to_owned(), err))?;
+    if let Some(id) = self.ids.get(&path) {
+      return Ok(*id);
+    }
+    if let Some(start) = self.loading.iter().position(|p| *p == path) {
+      let mut cycle = self.loading[start..].to_vec();
+      cycle.push(path);
+      return Err(LoadError::Cycle(cycle));
+    }
+
+    let source = fs::read_to_string(&path).map_err(|err| LoadError::Io(path.clone(), err))?;
+    let program = parse_module(&path, &source)?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+
+    self.loading.push(path.clone());
+    let imports = self.load_imports(dir, &program);
+    self.loading.pop();
+
+    let id = ModuleId(self.modules.len());
+    self.modules.push(Module {
+      path: path.clone(),
+      source,
+      program,
+      imports: imports?,
+    });
+    self.ids.insert(path, id);
+    Ok(id)
+  }
+
+  /// Loads the modules imported by the top level `use` statements of `program`,\
+  /// resolving relative imports from `dir`.
+  ///
+  /// Each module must be bound to a name that no other imported module is bound to.
+  pub fn load_imports<I>(
+    &mut self,
+    dir: &Path,
+    program: &Program<I>,
+  ) -> Result<HashMap<Box<str>, ModuleId>, LoadError> {
+    let mut imports = HashMap::new();
+    let mut bindings = HashMap::new();
+    for stmt in &program.statements {
+      let Statement::Use(Use { module, .. }) = stmt else {
+        continue;
+      };
+      let name: Box<str> = module.to_string().into();
+      let binding = match module.binding() {
+        Some(binding) if is_name(binding) => binding,
+        _ => return Err(LoadError::Unbound(name)),
+      };
+      match bindings.insert(binding, name.clone()) {
+        Some(first) if first != name => {
+          return Err(LoadError::Rebound {
+            binding: binding.into(),
+            first,
+            second: name,
+          })
+        }
+        _ => {}
+      }
+      let path = self.resolve(dir, module)?;
+      imports.insert(name, self.load(&path)?);
+    }
+    Ok(imports)
+  }
+
+  /// Finds the file for `module`, when imported from a module in `dir`.
+  ///
+  /// Files are relative to `dir`,\
+  /// whilst names are looked for in `dir` and then each search directory.
+  pub fn resolve<I>(&self, dir: &Path, module: &ModulePath<I>) -> Result<PathBuf, LoadError> {
+    let not_found = || LoadError::NotFound(module.to_string().into(), dir.to_owned());
+    match module {
+      ModulePath::File(file) => {
+        let path = dir.join(&*file.value);
+        path.is_file().then_some(path).ok_or_else(not_found)
+      }
+      ModulePath::Name(name) => {
+        let mut relative: PathBuf = name.segments.iter().map(|s| &*s.name).collect();
+        relative.set_extension(EXTENSION);
+        std::iter::once(dir)
+          .chain(self.search.iter().map(PathBuf::as_path))
+          .map(|dir| dir.join(&relative))
+          .find(|path| path.is_file())
+          .ok_or_else(not_found)
+      }
+    }
+  }
+}
+
+/// Lexes and parses the module `source`, read from `path`
+fn parse_module(path: &Path, source: &str) -> Result<Program<Range<usize>>, LoadError> {
+  let index = LineIndex::new(source);
+  let syntax_error = |origin: &str, message: String| {
+    let offset = Span::of(source, origin).map_or(source.len(), |span| span.start);
+    let (line, column) = index.position(offset, Unit::Char);
+    LoadError::Syntax {
+      path: path.to_owned(),
+      line: line + 1,
+      column: column + 1,
+      message,
+    }
+  };
+
+  let (tokens, errors) = recover_tokens(source);
+  if let Some(error) = errors.first() {
+    return Err(syntax_error(error.origin, error.to_string()));
+  }
+  let input = SpanTokens::new(&tokens, source);
+  let (_, program) = all_consuming(parse_program::<Diagnostic>)
+    .parse(input)
+    .map_err(|err| match err {
+      nom::Err::Error(e) | nom::Err::Failure(e) => syntax_error(e.span.origin, e.to_string()),
+      nom::Err::Incomplete(_) => syntax_error("", "incomplete expression".to_owned()),
+    })?;
+  // Safety: the tokens were all lexed from `source`
+  Ok(program.map(|src| unsafe { src.str_range(source) }.unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod test {
+  use super::{LoadError, Loader};
+  use std::{fs, path::PathBuf};
+
+  /// Creates an empty directory for a test's modules
+  fn modules_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("diom-loader-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  #[test]
+  fn dependency_order() {
+    let dir = modules_dir("order");
+    fs::create_dir(dir.join("math")).unwrap();
+    fs::write(
+      dir.join("main.diom"),
+      r#"use math.vector; use "./shapes.diom""#,
+    )
+    .unwrap();
+    fs::write(dir.join("shapes.diom"), "use math.vector; let origin = 0").unwrap();
+    fs::write(dir.join("math/vector.diom"), "let zero = 0").unwrap();
+
+    let mut loader = Loader::default();
+    let main = loader.load(&dir.join("main.diom")).unwrap();
+    let names: Vec<_> = loader
+      .modules()
+      .iter()
+      .map(|m| m.path.file_stem().unwrap().to_str().unwrap())
+      .collect();
+    // `vector` is shared between `main` and `shapes`
+    assert_eq!(names, ["vector", "shapes", "main"]);
+    let imports = &loader.module(main).imports;
+    assert_eq!(imports.len(), 2);
+    assert!(imports.contains_key("math.vector"));
+    assert!(imports.contains_key(r#""./shapes.diom""#));
+  }
+
+  #[test]
+  fn search_path() {
+    let dir = modules_dir("search");
+    let lib = modules_dir("search-lib");
+    fs::write(dir.join("main.diom"), "use util").unwrap();
+    fs::write(lib.join("util.diom"), "let one = 1").unwrap();
+
+    assert!(matches!(
+      Loader::default().load(&dir.join("main.diom")),
+      Err(LoadError::NotFound(..))
+    ));
+    let mut loader = Loader::new(vec![lib]);
+    loader.load(&dir.join("main.diom")).unwrap();
+    assert_eq!(loader.modules().len(), 2);
+  }
+
+  #[test]
+  fn cycle() {
+    let dir = modules_dir("cycle");
+    fs::write(dir.join("a.diom"), r#"use "./b.diom""#).unwrap();
+    fs::write(dir.join("b.diom"), r#"use "./a.diom""#).unwrap();
+
+    let Err(LoadError::Cycle(paths)) = Loader::default().load(&dir.join("a.diom")) else {
+      panic!("expected a cycle");
+    };
+    let names: Vec<_> = paths.iter().map(|p| p.file_name().unwrap()).collect();
+    assert_eq!(names, ["a.diom", "b.diom", "a.diom"]);
+  }
+
+  #[test]
+  fn bindings() {
+    let dir = modules_dir("bindings");
+    fs::create_dir(dir.join("a")).unwrap();
+    fs::create_dir(dir.join("b")).unwrap();
+    fs::write(dir.join("a/util.diom"), "let one = 1").unwrap();
+    fs::write(dir.join("b/util.diom"), "let two = 2").unwrap();
+    fs::write(dir.join("my-lib.diom"), "let three = 3").unwrap();
+
+    // importing the same module twice binds it once
+    fs::write(dir.join("twice.diom"), "use a.util; use a.util").unwrap();
+    Loader::default().load(&dir.join("twice.diom")).unwrap();
+
+    fs::write(dir.join("rebound.diom"), "use a.util; use b.util").unwrap();
+    let Err(LoadError::Rebound {
+      binding,
+      first,
+      second,
+    }) = Loader::default().load(&dir.join("rebound.diom"))
+    else {
+      panic!("expected both modules to be bound to `util`");
+    };
+    assert_eq!((&*binding, &*first, &*second), ("util", "a.util", "b.util"));
+
+    fs::write(dir.join("unbound.diom"), r#"use "./my-lib.diom""#).unwrap();
+    let Err(LoadError::Unbound(module)) = Loader::default().load(&dir.join("unbound.diom")) else {
+      panic!("expected `my-lib` not to be a name");
+    };
+    assert_eq!(&*module, r#""./my-lib.diom""#);
+  }
+
+  #[test]
+  fn syntax_error() {
+    let dir = modules_dir("syntax");
+    fs::write(dir.join("main.diom"), "let x = 1;\nlet = 2").unwrap();
+
+    let Err(LoadError::Syntax {
+      line,
+      column,
+      message,
+      ..
+    }) = Loader::default().load(&dir.join("main.diom"))
+    else {
+      panic!("expected a syntax error");
+    };
+    assert_eq!((line, column), (2, 5));
+    assert!(message.ends_with("found `=`"), "{message}");
+
+    // positions agree with the source map, including after `\r\n`
+    fs::write(dir.join("crlf.diom"), "let x = 1;\r\nlet = 2").unwrap();
+    let Err(LoadError::Syntax { line, column, .. }) =
+      Loader::default().load(&dir.join("crlf.diom"))
+    else {
+      panic!("expected a syntax error");
+    };
+    assert_eq!((line, column), (2, 5));
+  }
+}
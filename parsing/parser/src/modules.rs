@@ -0,0 +1,74 @@
+use crate::{
+  errors::{PResult, SyntaxError},
+  expressions::parse_string,
+  parsers::matches,
+  path::parse_path,
+  In,
+};
+use diom_syntax::modules::{ModulePath, Use};
+use diom_tokens::Token;
+use nom::{
+  branch::alt,
+  combinator::{consumed, cut},
+  error::context,
+  sequence::preceded,
+  Parser,
+};
+
+/// Parses an import of another module, i.e.
+///
+/// ```_
+/// use math.vector;
+/// use "./shapes.diom";
+/// ```
+pub fn parse_use<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Use<In<'a>>, E> {
+  let parse_module = alt((
+    parse_path.map(ModulePath::Name),
+    parse_string.map(ModulePath::File),
+  ));
+  let parser = preceded(matches(Token::Use), cut(parse_module));
+  let (input, (info, module)) = context("use", consumed(parser)).parse(input)?;
+  Ok((input, Use { module, info }))
+}
+
+#[cfg(test)]
+mod test {
+  use super::parse_use;
+  use crate::tests::utils::quick_lex;
+  use diom_syntax::modules::ModulePath;
+  use diom_tokens::SpanTokens;
+  use nom::{combinator::all_consuming, error::Error, Parser};
+
+  #[test]
+  fn name() {
+    let code = "use math.vector";
+    let tokens = quick_lex(code);
+    let (_, import) = all_consuming(parse_use::<Error<_>>)
+      .parse(SpanTokens::new(&tokens, code))
+      .unwrap();
+    let ModulePath::Name(path) = &import.module else {
+      panic!("expected a module name");
+    };
+    assert_eq!(path.segments.len(), 2);
+    assert_eq!(import.module.binding(), Some("vector"));
+  }
+
+  #[test]
+  fn file() {
+    let code = r#"use "./shapes.diom""#;
+    let tokens = quick_lex(code);
+    let (_, import) = all_consuming(parse_use::<Error<_>>)
+      .parse(SpanTokens::new(&tokens, code))
+      .unwrap();
+    assert!(matches!(import.module, ModulePath::File(_)));
+    assert_eq!(import.module.binding(), Some("shapes"));
+  }
+
+  #[test]
+  fn missing_module() {
+    let code = "use 1";
+    let tokens = quick_lex(code);
+    let result = parse_use::<Error<_>>(SpanTokens::new(&tokens, code));
+    assert!(matches!(result, Err(nom::Err::Failure(_))));
+  }
+}
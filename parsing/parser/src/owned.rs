@@ -0,0 +1,96 @@
+//! Syntax trees of owned tokens, that don't borrow their source.
+//!
+//! Syntax trees parsed from `SpanTokens` hold them as info, so can't outlive the lexed source.\
+//! These replace that info with byte `Span`s, so that parsed files can be cached,
+//! and shared between threads with the `sync` feature.
+//!
+//! This is a conversion layer rather than a parser over owned tokens:\
+//! the parsers are only written over `SpanTokens`, so owned tokens are borrowed as `SpanTokens`
+//! for the length of the parse, and the spans of the resulting tree are found from the borrowed tokens.
+use crate::expressions::parse_expression;
+use diom_info_traits::InfoMap;
+use diom_syntax::expressions::Expression;
+use diom_tokens::{
+  owned::{OwnedTokens, Span},
+  SpanTokens,
+};
+use nom::{
+  combinator::all_consuming,
+  error::{Error, ParseError},
+  Err, Parser,
+};
+
+/// Parses all of `tokens` as an expression, with byte spans into their source as info.\
+/// This converts the tree parsed from a borrowed copy of `tokens`, see the module docs.
+///
+/// ```
+/// # use diom_lexer::parse_tokens;
+/// # use diom_parser::owned::parse_owned;
+/// # use diom_tokens::owned::{OwnedTokens, Span};
+/// # use diom_info_traits::InfoRef;
+/// # use nom::Parser;
+/// let source = "f(x) + 1";
+/// let (_, tokens) = parse_tokens::<nom::error::Error<_>>().parse(source).unwrap();
+/// let tokens = OwnedTokens::new(source, &tokens);
+///
+/// let expr = std::thread::spawn(move || parse_owned(&tokens)).join().unwrap().unwrap();
+/// assert_eq!(*expr.info(), Span { start: 0, end: 8 });
+/// ```
+pub fn parse_owned(tokens: &OwnedTokens) -> Result<Expression<Span>, Err<Error<Span>>> {
+  let borrowed = tokens.span_tokens();
+  let span = |input: SpanTokens<'_>| {
+    tokens
+      .span_of(&input)
+      .expect("parsed spans should be borrowed from tokens")
+  };
+
+  let (_, expr) = all_consuming(parse_expression::<Error<_>>())
+    .parse(SpanTokens::new(&borrowed, tokens.source()))
+    .map_err(|err| err.map(|e| Error::from_error_kind(span(e.input), e.code)))?;
+  Ok(expr.map(span))
+}
+
+#[cfg(test)]
+mod test {
+  use super::parse_owned;
+  use diom_info_traits::InfoRef;
+  use diom_lexer::parse_tokens;
+  use diom_syntax::expressions::Expression;
+  use diom_tokens::owned::{OwnedTokens, Span};
+  use nom::{error::Error, Err, Parser};
+
+  fn owned(source: &str) -> OwnedTokens {
+    let (_, tokens) = parse_tokens::<Error<_>>().parse(source).unwrap();
+    OwnedTokens::new(source, &tokens)
+  }
+
+  #[test]
+  fn send_sync() {
+    fn is_send_sync<T: Send + Sync>() {}
+    is_send_sync::<OwnedTokens>();
+    is_send_sync::<Expression<Span>>();
+  }
+
+  #[test]
+  fn spans() {
+    let tokens = owned("(let xs = [1, 2]; xs)");
+    let Expression::Block(block) = parse_owned(&tokens).unwrap() else {
+      panic!("expected a block");
+    };
+    let spans: Vec<_> = block
+      .statements
+      .iter()
+      .map(|s| tokens.text(*s.info()))
+      .collect();
+    assert_eq!(spans, ["let xs = [1, 2]", "xs"]);
+  }
+
+  #[test]
+  fn error() {
+    let tokens = owned("1 + ) 2");
+    let Err(Err::Error(err)) = parse_owned(&tokens) else {
+      panic!("expected a parse error");
+    };
+    assert_eq!(tokens.text(err.input), ") 2");
+  }
+}
@@ -19,66 +19,86 @@ pub fn single_item<'a, E: SyntaxError<'a>>() -> impl Parser<In<'a>, Output = Ite
   }
 }
 
+/// The tokens that were expected, when a token didn't exactly match them
 #[derive(Debug)]
-pub struct IsExact(pub String);
+pub struct IsExact(pub Vec<Token>);
 
 pub fn token<'a, E: SyntaxError<'a>>(
   tok: impl ExactMatch<Token> + 'static,
 ) -> impl Parser<In<'a>, Output = Item<'a>, Error = E> {
-  single_item().map_res(move |item| {
-    if tok.exact(&item) {
-      Ok(item)
-    } else {
-      Err(IsExact(format!("{tok:?}")))
-    }
-  })
+  move |input: In<'a>| match input.first() {
+    Some(item) if tok.exact(item) => Ok((input.take_from(1usize), item.clone())),
+    _ => Err(nom::Err::Error(E::from_external_error(
+      input,
+      ErrorKind::MapRes,
+      IsExact(tok.tokens()),
+    ))),
+  }
 }
 
 pub trait ExactMatch<T>: Debug {
   fn exact(&self, other: &T) -> bool;
+  /// The tokens that are matched
+  fn tokens(&self) -> Vec<T>;
 }
 impl ExactMatch<Token> for Token {
   #[inline]
   fn exact(&self, other: &Token) -> bool {
     self == other
   }
+  fn tokens(&self) -> Vec<Token> {
+    vec![self.clone()]
+  }
 }
 impl<const N: usize> ExactMatch<Token> for [Token; N] {
   #[inline]
   fn exact(&self, other: &Token) -> bool {
     self.into_iter().any(|tok| tok == other)
   }
+  fn tokens(&self) -> Vec<Token> {
+    self.to_vec()
+  }
 }
 
+/// The kinds of tokens that were expected, when a token didn't match any of them
 #[derive(Debug)]
-pub struct IsApprox(pub String);
+pub struct IsApprox(pub Vec<Token>);
 
 pub fn matches<'a, E: SyntaxError<'a>>(
   tok: impl ApproxMatch<Token> + 'static,
 ) -> impl Parser<In<'a>, Output = Item<'a>, Error = E> {
-  single_item().map_res(move |item| {
-    if tok.approx(&item) {
-      Ok(item)
-    } else {
-      Err(IsApprox(format!("{tok:?}")))
-    }
-  })
+  move |input: In<'a>| match input.first() {
+    Some(item) if tok.approx(item) => Ok((input.take_from(1usize), item.clone())),
+    _ => Err(nom::Err::Error(E::from_external_error(
+      input,
+      ErrorKind::MapRes,
+      IsApprox(tok.tokens()),
+    ))),
+  }
 }
 
 pub trait ApproxMatch<T>: Debug {
   fn approx(&self, other: &T) -> bool;
+  /// The kinds of tokens that are matched
+  fn tokens(&self) -> Vec<T>;
 }
 impl ApproxMatch<Token> for Token {
   #[inline]
   fn approx(&self, other: &Token) -> bool {
     self.matches(other)
   }
+  fn tokens(&self) -> Vec<Token> {
+    vec![self.clone()]
+  }
 }
 impl<const N: usize> ApproxMatch<Token> for [Token; N] {
   #[inline]
   fn approx(&self, other: &Token) -> bool {
     self.into_iter().any(|tok| tok.matches(other))
   }
+  fn tokens(&self) -> Vec<Token> {
+    self.to_vec()
+  }
 }
 
 pub fn group<'a, E: SyntaxError<'a>>(
@@ -115,6 +135,21 @@ pub fn group<'a, E: SyntaxError<'a>>(
   }
 }
 
+/// Matches the end of the input,\
+/// otherwise expecting one of `tok` to continue it, i.e. a separator or the closing bracket of a group.
+pub fn end<'a, E: SyntaxError<'a>>(
+  tok: impl ExactMatch<Token> + 'static,
+) -> impl Parser<In<'a>, Output = (), Error = E> {
+  move |input: In<'a>| match input.first() {
+    None => Ok((input, ())),
+    Some(_) => Err(nom::Err::Error(E::from_external_error(
+      input,
+      ErrorKind::Eof,
+      IsExact(tok.tokens()),
+    ))),
+  }
+}
+
 pub fn token_separated_list<'a, I, R, E: ParseError<I>>(
   tok: Token,
   mut parser: impl Parser<I, Output = R, Error = E>,
@@ -1,17 +1,13 @@
 use super::{parse_pattern, parse_rest};
 use crate::{
   errors::{PResult, SyntaxError},
-  parsers::{group, matches},
+  parsers::{end, group, matches},
   In,
 };
 use diom_syntax::patterns::arrays::{Array, ArrayItem};
 use diom_tokens::Token;
 use nom::{
-  branch::alt,
-  combinator::{consumed, eof},
-  multi::separated_list0,
-  sequence::terminated,
-  Parser,
+  branch::alt, combinator::consumed, multi::separated_list0, sequence::terminated, Parser,
 };
 
 pub fn parse_array_item<'a, E: SyntaxError<'a>>(
@@ -27,7 +23,7 @@ pub fn parse_array_item<'a, E: SyntaxError<'a>>(
 pub fn parse_array<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Array<In<'a>>, E> {
   let parse_inner = terminated(
     separated_list0(matches(Token::Comma), parse_array_item),
-    eof,
+    end([Token::Comma, Token::RBrace]),
   );
   let parser = group(Token::LBrace, Token::RBrace).and_then(parse_inner);
 
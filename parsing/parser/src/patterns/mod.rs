@@ -1,6 +1,7 @@
 use crate::{
   errors::{PResult, SyntaxError},
   idents::parse_ident,
+  recovery::parse_invalid,
   In,
 };
 use diom_syntax::patterns::Pattern;
@@ -21,6 +22,7 @@ use tuples::parse_tuple;
 
 pub fn parse_pattern<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Pattern<In<'a>>, E> {
   alt((
+    parse_invalid.map(Pattern::Error),
     parse_array.map(Pattern::Array),
     parse_ignored.map(Pattern::Ignored),
     parse_struct.map(Pattern::Struct),
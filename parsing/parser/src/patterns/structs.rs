@@ -2,7 +2,7 @@ use super::{parse_pattern, parse_rest};
 use crate::{
   errors::{PResult, SyntaxError},
   idents::parse_method,
-  parsers::{group, matches},
+  parsers::{end, group, matches},
   In,
 };
 use diom_syntax::patterns::{
@@ -12,7 +12,7 @@ use diom_syntax::patterns::{
 use diom_tokens::Token;
 use nom::{
   branch::alt,
-  combinator::{consumed, eof, opt},
+  combinator::{consumed, opt},
   multi::separated_list1,
   sequence::{preceded, terminated},
   Parser,
@@ -51,7 +51,7 @@ pub fn parse_struct_item<'a, E: SyntaxError<'a>>(
 pub fn parse_struct<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Struct<In<'a>>, E> {
   let parse_inner = terminated(
     separated_list1(matches(Token::Comma), parse_struct_item),
-    eof,
+    end([Token::Comma, Token::RCurly]),
   );
   let parser = group(Token::LCurly, Token::RCurly).and_then(parse_inner);
 
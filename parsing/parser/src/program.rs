@@ -0,0 +1,96 @@
+use crate::{
+  errors::{PResult, SyntaxError},
+  expressions::parse_statement,
+  modules::parse_use,
+  parsers::{end, matches},
+  In,
+};
+use diom_syntax::{expressions::Statement, program::Program};
+use diom_tokens::Token;
+use nom::{
+  branch::alt,
+  combinator::{consumed, opt},
+  error::context,
+  multi::separated_list0,
+  sequence::terminated,
+  Parser,
+};
+
+/// Parses a statement at the top level of a file.\
+/// Imports are only allowed here, so that every module a file uses is known before it's evaluated.
+pub fn parse_top_level<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Statement<In<'a>>, E> {
+  alt((parse_use.map(Statement::Use), parse_statement)).parse(input)
+}
+
+/// Parses a whole file of `;` separated statements, with an optional trailing `;`
+pub fn parse_program<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Program<In<'a>>, E> {
+  let parse_statements = separated_list0(matches(Token::Semi), parse_top_level);
+  let parser = terminated(
+    parse_statements,
+    opt(matches(Token::Semi)).and(end(Token::Semi)),
+  );
+
+  let (input, (info, statements)) = context("program", consumed(parser)).parse(input)?;
+  Ok((input, Program { statements, info }))
+}
+
+#[cfg(test)]
+mod test {
+  use super::parse_program;
+  use crate::tests::utils::quick_lex;
+  use diom_syntax::expressions::{Expression, Statement};
+  use diom_tokens::SpanTokens;
+  use nom::{combinator::all_consuming, error::Error, Parser};
+
+  fn statements(code: &str) -> Vec<String> {
+    let tokens = quick_lex(code);
+    let (_, program) = all_consuming(parse_program::<Error<_>>)
+      .parse(SpanTokens::new(&tokens, code))
+      .unwrap();
+    program.statements.iter().map(|s| s.to_string()).collect()
+  }
+
+  #[test]
+  fn declarations() {
+    let code = "type Point = {x: Float, y: Float};\nlet p = {x: 1, y: 2};\np.x";
+    assert_eq!(statements(code).len(), 3);
+  }
+
+  #[test]
+  fn trailing_semi() {
+    assert_eq!(statements("let x = 1; x;"), ["let x=1", "x"]);
+  }
+
+  #[test]
+  fn nested_blocks() {
+    let code = "let f = (x) => (let y = x; y); f(1)";
+    assert_eq!(statements(code).len(), 2);
+  }
+
+  #[test]
+  fn imports() {
+    assert_eq!(statements("use util; util.two"), ["use util", "util.two"]);
+    // imports within blocks wouldn't be loaded before the program is evaluated
+    let code = "let f = () => (use util; util.two); f()";
+    let tokens = quick_lex(code);
+    assert!(all_consuming(parse_program::<Error<_>>)
+      .parse(SpanTokens::new(&tokens, code))
+      .is_err());
+  }
+
+  #[test]
+  fn empty() {
+    assert!(statements("").is_empty());
+  }
+
+  #[test]
+  fn spans() {
+    let code = "let x = 1;\nx + 2";
+    let tokens = quick_lex(code);
+    let (_, program) = parse_program::<Error<_>>(SpanTokens::new(&tokens, code)).unwrap();
+    let Statement::Expression(Expression::Infix(infix)) = &program.statements[1] else {
+      panic!("expected an infix");
+    };
+    assert_eq!(infix.info.origin, "x + 2");
+  }
+}
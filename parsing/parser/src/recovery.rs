@@ -0,0 +1,398 @@
+//! Parsing that continues past syntax errors.
+//!
+//! Editors need a syntax tree even whilst code is broken, so instead of failing on the first error,\
+//! each part of a bracketed group that fails to parse is replaced with an error node and parsing continues.
+//!
+//! Parts are synchronised on `,` and `;` separators and closing brackets, i.e. in
+//! ```_
+//! (let x = 1 +; f(x, ]); x)
+//! ```
+//! both `let x = 1 +` and `]` are replaced, giving a block with 3 statements.
+use crate::{
+  errors::{PResult, SyntaxError},
+  expressions::{parse_expression, parse_parameter, parse_statement, parse_struct_field},
+  parsers::group,
+  program::{parse_program, parse_top_level},
+  types::parse_type,
+  In, Item,
+};
+use diom_info_traits::InfoMap;
+use diom_syntax::{
+  expressions::{Expression, Statement},
+  invalid::Invalid,
+  program::Program,
+};
+use diom_tokens::{SpanToken, SpanTokens, Token};
+use nom::{
+  combinator::all_consuming,
+  error::{Error, ErrorKind},
+  multi::many1,
+  Err, Input, Offset, Parser,
+};
+
+/// Parses a placeholder left by error recovery.\
+/// Only `recover_program` and `recover_expression` insert `Token::Invalid`,
+/// so this never matches lexed input, nor is it suggested in syntax errors.
+pub(crate) fn parse_invalid<'a, E: SyntaxError<'a>>(
+  input: In<'a>,
+) -> PResult<'a, Invalid<In<'a>>, E> {
+  match input.first() {
+    Some(item) if item.token == Token::Invalid => {
+      let (rest, info) = input.take_split(1);
+      Ok((rest, Invalid { info }))
+    }
+    _ => Err(Err::Error(E::from_error_kind(input, ErrorKind::Tag))),
+  }
+}
+
+/// Parses all of `input` as an expression, recovering from syntax errors.\
+/// This returns the syntax tree, along with the error for each section replaced with an error node.
+///
+/// ```
+/// # use diom_lexer::parse_tokens;
+/// # use diom_parser::recovery::recover_expression;
+/// # use diom_syntax::expressions::Expression;
+/// # use diom_tokens::SpanTokens;
+/// # use nom::{error::Error, Parser};
+/// let source = "[1, 2 +, 3]";
+/// let (_, tokens) = parse_tokens::<Error<_>>().parse(source).unwrap();
+/// let (expr, errors) = recover_expression::<Error<_>>(SpanTokens::new(&tokens, source));
+///
+/// let Expression::Array(array) = expr else { panic!("expected an array") };
+/// assert!(matches!(array.contents[1], Expression::Error(_)));
+/// assert_eq!(errors.len(), 1);
+/// ```
+pub fn recover_expression<'a, E: SyntaxError<'a>>(input: In<'a>) -> (Expression<In<'a>>, Vec<E>) {
+  let error = match all_consuming(parse_expression::<E>()).parse(input) {
+    Ok((_, expr)) => return (expr, vec![]),
+    Err(err) => into_error(input, err),
+  };
+
+  let mut errors = vec![];
+  let patched = patch_groups(input, &mut errors);
+  let result =
+    all_consuming(parse_expression::<Error<_>>()).parse(SpanTokens::new(&patched, input.origin));
+  match result {
+    Ok((_, expr)) => (expr.map(|span| rebase(input, span)), errors),
+    Err(_) => (Expression::Error(Invalid { info: input }), vec![error]),
+  }
+}
+
+/// Parses all of `input` as a program, recovering from syntax errors.\
+/// Each `;` separated statement that fails to parse is replaced with an error node.
+///
+/// ```
+/// # use diom_lexer::parse_tokens;
+/// # use diom_parser::recovery::recover_program;
+/// # use diom_syntax::expressions::{Expression, Statement};
+/// # use diom_tokens::SpanTokens;
+/// # use nom::{error::Error, Parser};
+/// let source = "let x = 1; let = 2; x";
+/// let (_, tokens) = parse_tokens::<Error<_>>().parse(source).unwrap();
+/// let (program, errors) = recover_program::<Error<_>>(SpanTokens::new(&tokens, source));
+///
+/// assert_eq!(program.statements.len(), 3);
+/// assert!(matches!(program.statements[1], Statement::Expression(Expression::Error(_))));
+/// assert_eq!(errors.len(), 1);
+/// ```
+pub fn recover_program<'a, E: SyntaxError<'a>>(input: In<'a>) -> (Program<In<'a>>, Vec<E>) {
+  let error = match all_consuming(parse_program::<E>).parse(input) {
+    Ok((_, program)) => return (program, vec![]),
+    Err(err) => into_error(input, err),
+  };
+
+  let mut errors = vec![];
+  let patched = patch_parts(input, is_valid_top_level, &mut errors);
+  let result =
+    all_consuming(parse_program::<Error<_>>).parse(SpanTokens::new(&patched, input.origin));
+  match result {
+    Ok((_, program)) => (program.map(|span| rebase(input, span)), errors),
+    Err(_) => {
+      let statement = Statement::Expression(Expression::Error(Invalid { info: input }));
+      let program = Program {
+        statements: vec![statement],
+        info: input,
+      };
+      (program, vec![error])
+    }
+  }
+}
+
+/// Converts a failed parse into the error for `input`
+fn into_error<'a, E: SyntaxError<'a>>(input: In<'a>, err: Err<E>) -> E {
+  match err {
+    Err::Error(e) | Err::Failure(e) => e,
+    // unclosed brackets are the only source of incomplete input
+    Err::Incomplete(_) => E::from_error_kind(input, ErrorKind::Complete),
+  }
+}
+
+/// The closing bracket for `token`, when it opens a group
+fn closing(token: &Token) -> Option<Token> {
+  match token {
+    Token::LParen => Some(Token::RParen),
+    Token::LBrace => Some(Token::RBrace),
+    Token::LCurly => Some(Token::RCurly),
+    _ => None,
+  }
+}
+
+/// Skips over the bracketed group at the start of `input`, if it's closed
+fn skip_group(input: In<'_>) -> Option<(In<'_>, In<'_>)> {
+  let open = input.first()?;
+  let close = closing(&open.token)?;
+  group::<Error<_>>(open.token.clone(), close)(input).ok()
+}
+
+/// Checks whether tokens can be parsed as a part of their enclosing syntax
+type Validity = fn(&[Item<'_>], &str) -> bool;
+
+/// Whether `tokens` can be parsed as any part of a group
+fn is_valid(tokens: &[Item<'_>], origin: &str) -> bool {
+  let input = SpanTokens::new(tokens, origin);
+  all_consuming(parse_statement::<Error<_>>)
+    .parse(input)
+    .is_ok()
+    || all_consuming(parse_struct_field::<Error<_>>)
+      .parse(input)
+      .is_ok()
+    || all_consuming(many1(parse_parameter::<Error<_>>))
+      .parse(input)
+      .is_ok()
+    || all_consuming(parse_type::<Error<_>>).parse(input).is_ok()
+}
+
+/// Whether `tokens` can be parsed as a top level statement, or any part of a group
+fn is_valid_top_level(tokens: &[Item<'_>], origin: &str) -> bool {
+  let input = SpanTokens::new(tokens, origin);
+  all_consuming(parse_top_level::<Error<_>>)
+    .parse(input)
+    .is_ok()
+    || is_valid(tokens, origin)
+}
+
+/// Whether `name` and `colon` start a struct field or an annotated parameter
+fn is_field(name: &Item<'_>, colon: &Item<'_>) -> bool {
+  matches!(name.token, Token::StringIdent(_)) && colon.token == Token::Colon
+}
+
+/// Patches the contents of each group in `input`, leaving the tokens between groups as is
+fn patch_groups<'a, E: SyntaxError<'a>>(input: In<'a>, errors: &mut Vec<E>) -> Vec<SpanToken<'a>> {
+  let mut patched = vec![];
+  let mut rest = input;
+  while let Some(first) = rest.first() {
+    let Some((after, inner)) = skip_group(rest) else {
+      // unclosed brackets are left for the enclosing part to fail on
+      patched.push(first.clone());
+      rest = rest.take_from(1);
+      continue;
+    };
+    patched.push(first.clone());
+    patched.extend(patch_parts(inner, is_valid, errors));
+    patched.push(rest[rest.len() - after.len() - 1].clone());
+    rest = after;
+  }
+  patched
+}
+
+/// Patches each of the `,` or `;` separated parts of `input`, keeping the parts that are `valid`
+fn patch_parts<'a, E: SyntaxError<'a>>(
+  input: In<'a>,
+  valid: Validity,
+  errors: &mut Vec<E>,
+) -> Vec<SpanToken<'a>> {
+  let mut patched = vec![];
+  let (mut start, mut i) = (0, 0);
+  while i < input.len() {
+    let tok = &input[i];
+    if matches!(tok.token, Token::Comma | Token::Semi) {
+      patched.extend(patch_part(
+        input.take_from(start).take(i - start),
+        valid,
+        errors,
+      ));
+      patched.push(tok.clone());
+      start = i + 1;
+      i += 1;
+    } else if let Some((after, _)) = skip_group(input.take_from(i)) {
+      i = input.len() - after.len();
+    } else {
+      i += 1;
+    }
+  }
+  patched.extend(patch_part(input.take_from(start), valid, errors));
+  patched
+}
+
+/// Patches a single part of a group, replacing it with a placeholder when it can't be fixed
+fn patch_part<'a, E: SyntaxError<'a>>(
+  part: In<'a>,
+  valid: Validity,
+  errors: &mut Vec<E>,
+) -> Vec<SpanToken<'a>> {
+  if part.is_empty() || valid(&part, part.origin) {
+    return part.to_vec();
+  }
+  let before = errors.len();
+  let patched = match &part[..] {
+    // only the value of fields and annotated parameters is replaced, i.e. `x: <error>`
+    [name, colon, _, ..] if is_field(name, colon) => {
+      let mut patched = part[..2].to_vec();
+      patched.extend(patch_part(part.take_from(2), valid, errors));
+      patched
+    }
+    _ => patch_groups(part, errors),
+  };
+  if valid(&patched, part.origin) {
+    return patched;
+  }
+
+  // errors within the part are replaced by the part's own error
+  errors.truncate(before);
+  let error = match all_consuming(parse_expression::<E>()).parse(part) {
+    Ok(_) => E::from_error_kind(part, ErrorKind::Verify),
+    Err(err) => into_error(part, err),
+  };
+  errors.push(error);
+
+  let (first, last) = (&part[0], &part[part.len() - 1]);
+  let start = part.origin.offset(first.origin);
+  let end = part.origin.offset(last.origin) + last.origin.len();
+  vec![SpanToken::new(Token::Invalid, &part.origin[start..end])]
+}
+
+/// Finds the span of `input` that the patched `span` was parsed from
+fn rebase<'a>(input: In<'a>, span: SpanTokens<'_>) -> In<'a> {
+  let offset = |origin: &str| input.origin.offset(origin);
+  let (Some(first), Some(last)) = (span.first(), span.last()) else {
+    let start = span.origin.as_ptr() as usize - input.origin.as_ptr() as usize;
+    let idx = input
+      .iter()
+      .position(|t| offset(t.origin) >= start)
+      .unwrap_or(input.len());
+    return SpanTokens::new(&input.tokens[idx..idx], &input.origin[start..start]);
+  };
+
+  let start = offset(first.origin);
+  let end = offset(last.origin) + last.origin.len();
+  let i = input
+    .iter()
+    .position(|t| offset(t.origin) == start)
+    .unwrap();
+  let j = input
+    .iter()
+    .rposition(|t| offset(t.origin) + t.origin.len() == end)
+    .unwrap();
+  SpanTokens::new(&input.tokens[i..=j], &input.origin[start..end])
+}
+
+#[cfg(test)]
+mod test {
+  use super::{recover_expression, recover_program};
+  use crate::tests::utils::quick_lex;
+  use diom_syntax::{
+    expressions::{Expression, Statement},
+    patterns::Pattern,
+  };
+  use diom_tokens::SpanTokens;
+  use nom::error::Error;
+
+  /// Recovers `code`, returning the displayed tree and the source of each error
+  fn recover(code: &str) -> (String, Vec<String>) {
+    let tokens = quick_lex(code);
+    let (expr, errors) = recover_expression::<Error<_>>(SpanTokens::new(&tokens, code));
+    let errors = errors.into_iter().map(|e| e.input.to_string()).collect();
+    (expr.to_string(), errors)
+  }
+
+  #[test]
+  fn valid() {
+    let (expr, errors) = recover("(let x = 1; x + 2)");
+    assert_eq!(expr, "(let x=1;x + 2)");
+    assert!(errors.is_empty());
+  }
+
+  #[test]
+  fn statements() {
+    let (expr, errors) = recover("(let x = 1 +; f(x, ]); x)");
+    assert_eq!(expr, "(<error>;f(x,<error>);x)");
+    assert_eq!(errors.len(), 2);
+  }
+
+  #[test]
+  fn nested() {
+    let (expr, errors) = recover("{a: 1 * * 2, b: (x) => [!]}");
+    assert_eq!(expr, "{a:<error>,b:{(x)=>[<error>]}}");
+    assert_eq!(errors.len(), 2);
+  }
+
+  #[test]
+  fn fields() {
+    // fields that are replaced entirely keep their place in the struct
+    let code = "{a: 1, +, b: 2}";
+    let tokens = quick_lex(code);
+    let (expr, errors) = recover_expression::<Error<_>>(SpanTokens::new(&tokens, code));
+    let Expression::Struct(structure) = expr else {
+      panic!("expected a struct");
+    };
+    assert_eq!(structure.fields.len(), 3);
+    let (_, Expression::Error(error)) = &structure.fields[1] else {
+      panic!("expected an error node");
+    };
+    assert_eq!(error.info.origin, "+");
+    assert_eq!(errors.len(), 1);
+  }
+
+  #[test]
+  fn program() {
+    let code = "type T = Int; let x = [1 +]; let y = ); x";
+    let tokens = quick_lex(code);
+    let (program, errors) = recover_program::<Error<_>>(SpanTokens::new(&tokens, code));
+    assert_eq!(
+      program.to_string(),
+      "type T = Int;\nlet x=[<error>];\n<error>;\nx;"
+    );
+    assert_eq!(errors.len(), 2);
+  }
+
+  #[test]
+  fn unrecoverable() {
+    let (expr, errors) = recover("1 + ) 2");
+    assert_eq!(expr, "<error>");
+    assert_eq!(errors.len(), 1);
+  }
+
+  #[test]
+  fn spans() {
+    let code = "(f(1, +); let y = 2)";
+    let tokens = quick_lex(code);
+    let (expr, _) = recover_expression::<Error<_>>(SpanTokens::new(&tokens, code));
+    let Expression::Block(block) = expr else {
+      panic!("expected a block");
+    };
+    let Statement::Expression(Expression::Call(call)) = &block.statements[0] else {
+      panic!("expected a call");
+    };
+    let Expression::Error(error) = &call.args[1] else {
+      panic!("expected an error node");
+    };
+    assert_eq!(error.info.origin, "+");
+    let Statement::Expression(Expression::Declare(declare)) = &block.statements[1] else {
+      panic!("expected a declaration");
+    };
+    assert_eq!(declare.info.origin, "let y = 2");
+  }
+
+  #[test]
+  fn patterns() {
+    let code = "([) => x";
+    let tokens = quick_lex(code);
+    let (expr, errors) = recover_expression::<Error<_>>(SpanTokens::new(&tokens, code));
+    let Expression::Function(func) = expr else {
+      panic!("expected a function");
+    };
+    let params = &func.arms[0].parameters.parameters;
+    assert!(matches!(params[0].pattern, Pattern::Error(_)));
+    assert_eq!(errors.len(), 1);
+  }
+}
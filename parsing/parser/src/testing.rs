@@ -0,0 +1,44 @@
+//! Parsing of test code, for the tests of crates that evaluate syntax trees.
+//!
+//! Code is expected to lex and parse, so any errors panic,\
+//! and trees have char ranges into the code as info.
+use crate::{expressions::parse_expression, program::parse_program};
+use diom_info_traits::InfoMap;
+use diom_lexer::parse_tokens;
+use diom_syntax::{expressions::Expression, program::Program};
+use diom_tokens::{SpanToken, SpanTokens};
+use nom::{combinator::all_consuming, Parser};
+use nom_language::error::VerboseError;
+use std::ops::Range;
+
+/// Lexes all of `code`, panicking on any errors
+fn lex(code: &str) -> Vec<SpanToken<'_>> {
+  let (_, tokens) = all_consuming(parse_tokens::<VerboseError<_>>())
+    .parse(code)
+    .unwrap();
+  tokens
+}
+
+/// Converts `tokens` parsed from `code` into char ranges
+fn ranges(code: &str) -> impl FnMut(SpanTokens<'_>) -> Range<usize> + '_ {
+  // Safety: the tokens were all lexed from `code`
+  move |src| unsafe { src.str_range(code) }.unwrap_or_default()
+}
+
+/// Parses all of `code` as a single expression
+pub fn parse_expr(code: &str) -> Expression<Range<usize>> {
+  let tokens = lex(code);
+  let (_, expr) = all_consuming(parse_expression::<VerboseError<_>>())
+    .parse(SpanTokens::new(&tokens, code))
+    .unwrap();
+  expr.map(ranges(code))
+}
+
+/// Parses all of `code` as a program
+pub fn parse_prog(code: &str) -> Program<Range<usize>> {
+  let tokens = lex(code);
+  let (_, program) = all_consuming(parse_program::<VerboseError<_>>)
+    .parse(SpanTokens::new(&tokens, code))
+    .unwrap();
+  program.map(ranges(code))
+}
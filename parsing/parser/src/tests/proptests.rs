@@ -1,8 +1,20 @@
 use super::utils::{quick_lex, quick_parse};
+use crate::{expressions::parse_expression, program::parse_program};
 use diom_info_traits::InfoMap;
-use diom_syntax::expressions::Expression;
-use diom_tokens::SpanTokens;
-use proptest::prelude::*;
+use diom_syntax::{
+  expressions::{
+    Assign, Call, Char, Declare, Expression, ExpressionConfig, Field, Float, Index, Infix, Prefix,
+    Return, Statement, String, Template,
+  },
+  idents::{Ident, Method, Name, Op, Symbol},
+  patterns::Pattern,
+  program::Program,
+  types::TypeConfig,
+  Ptr,
+};
+use diom_tokens::{SpanToken, SpanTokens};
+use nom::{combinator::all_consuming, error::Error, Parser};
+use proptest::{collection::vec, prelude::*};
 
 proptest! {
   /// Tests that we can format an Expression to code and then parse it back to an expression
@@ -15,4 +27,372 @@ proptest! {
     let _expr = expr_.map(|_| ());
     // @todo implement `PartialEq` and test expression equality
   }
+
+  /// Tests that templates parse back to the same template they were formatted from
+  #[test]
+  fn template_roundtrip(template in Template::any(literal(), Default::default())) {
+    let code = format!("{template}");
+    let tokens = quick_lex(&code);
+
+    let expr = quick_parse(&code, SpanTokens::new(&tokens, &code)).map(|_| ());
+    prop_assert_eq!(expr, Expression::Template(template));
+  }
+
+  /// Tests that programs parse back to the same statements they were formatted from
+  #[test]
+  fn program_roundtrip(program in programs()) {
+    let code = format!("{program}");
+    let tokens = quick_lex(&code);
+
+    let (_, parsed) = all_consuming(parse_program::<Error<_>>)
+      .parse(SpanTokens::new(&tokens, &code))
+      .unwrap();
+    prop_assert_eq!(parsed.map(|_| ()), program);
+  }
+
+  /// Tests that operators parse back to the same tree they were formatted from,\
+  /// and that removing any of the parentheses added when formatting changes how they parse
+  #[test]
+  fn operator_roundtrip(expr in operators()) {
+    let code = format!("{expr}");
+    let tokens = quick_lex(&code);
+
+    let parsed = parse(&tokens, &code).unwrap();
+    let mut parens = vec![];
+    added_parens(&parsed, &mut parens);
+    prop_assert_eq!(without_parens(&parsed.map(|_| ())), expr.clone());
+
+    for parens in parens {
+      let start = parens.tokens.as_ptr() as usize - tokens.as_ptr() as usize;
+      let start = start / std::mem::size_of::<SpanToken>();
+      let end = start + parens.tokens.len() - 1;
+      let tokens: Vec<_> = tokens
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != start && *i != end)
+        .map(|(_, token)| token.clone())
+        .collect();
+      let reparsed = parse(&tokens, &code).map(|e| without_parens(&e.map(|_| ())));
+      prop_assert_ne!(reparsed, Some(expr.clone()), "parentheses at {} weren't needed", start);
+    }
+  }
+
+  /// Tests that implicit calls with names as arguments parse alongside named infix methods,\
+  /// and that they're displayed as code that parses back to the same tree
+  #[test]
+  fn implicit_call_roundtrip((code, expr) in implicit_calls()) {
+    let tokens = quick_lex(&code);
+    let parsed = parse(&tokens, &code).map(|e| without_parens(&e.map(|_| ())));
+    prop_assert_eq!(parsed, Some(expr.clone()), "parsing `{}`", code);
+
+    let displayed = format!("{expr}");
+    let tokens = quick_lex(&displayed);
+    let reparsed = parse(&tokens, &displayed).map(|e| without_parens(&e.map(|_| ())));
+    prop_assert_eq!(reparsed, Some(expr), "parsing `{}`", displayed);
+  }
+}
+
+fn parse<'a>(tokens: &'a [SpanToken<'a>], code: &'a str) -> Option<Expression<SpanTokens<'a>>> {
+  let (_, expr) = all_consuming(parse_expression::<Error<_>>())
+    .parse(SpanTokens::new(tokens, code))
+    .ok()?;
+  Some(expr)
+}
+
+/// Generates trees of operators, which only parse back to themselves when parenthesised correctly
+fn operators() -> impl Strategy<Value = Expression<()>> {
+  let leaf = prop_oneof![
+    Ident::any().prop_map(Expression::Var),
+    Float::any()
+      .prop_filter("positive", |f| f.value.is_sign_positive()
+        && f.value.is_finite())
+      .prop_map(Expression::Float),
+  ];
+  let method = prop_oneof![
+    Ident::any().prop_map(|i| Name::Literal(i.name)),
+    Symbol::any()
+      .prop_filter("infix", |sym| *sym != Symbol::Not)
+      .prop_map(Name::Symbol),
+  ]
+  .prop_map(|name| Method { name, info: () });
+  let sign =
+    prop_oneof![Just(Symbol::Plus), Just(Symbol::Minus)].prop_map(|sym| Op { sym, info: () });
+
+  leaf.prop_recursive(4, 24, 2, move |inner| {
+    let item = inner.clone().prop_map(Ptr::new);
+    // lists of more than one item are split at nested commas, so calls and indexes only use one
+    prop_oneof![
+      (item.clone(), method.clone(), item.clone())
+        .prop_map(|(value, name, other)| Infix {
+          value,
+          name,
+          other,
+          info: ()
+        })
+        .prop_map(Expression::Infix),
+      (sign.clone(), item.clone())
+        .prop_map(|(name, value)| Prefix {
+          name,
+          value,
+          info: ()
+        })
+        .prop_map(Expression::Prefix),
+      (item.clone(), vec(inner.clone(), 0..2))
+        .prop_map(|(value, args)| Call {
+          value,
+          args: args.into_iter().collect(),
+          info: ()
+        })
+        .prop_map(Expression::Call),
+      (item.clone(), Ident::any())
+        .prop_map(|(value, i)| Field {
+          value,
+          name: Method {
+            name: Name::Literal(i.name),
+            info: ()
+          },
+          info: ()
+        })
+        .prop_map(Expression::Field),
+      (item.clone(), inner.clone())
+        .prop_map(|(value, key)| Index {
+          value,
+          keys: [key].into_iter().collect(),
+          info: ()
+        })
+        .prop_map(Expression::Index),
+      (item.clone(), item.clone())
+        .prop_map(|(reference, value)| Assign {
+          reference,
+          value,
+          info: ()
+        })
+        .prop_map(Expression::Assign),
+      // `_` is parsed as an ignored pattern
+      (
+        Ident::any().prop_filter("named", |i| &*i.name != "_"),
+        item.clone()
+      )
+        .prop_map(|(name, value)| Declare {
+          doc: None,
+          pattern: Pattern::Var(name),
+          annotation: None,
+          value,
+          info: ()
+        })
+        .prop_map(Expression::Declare),
+      item
+        .prop_map(|value| Return { value, info: () })
+        .prop_map(Expression::Return),
+    ]
+  })
+}
+
+/// Generates implicit calls, i.e. `f 1 x`, and named infix methods around them, i.e. `a max f x`,\
+/// along with the code they're written as
+fn implicit_calls() -> impl Strategy<Value = (std::string::String, Expression<()>)> {
+  let float = || {
+    Float::any()
+      .prop_filter("positive", |f| {
+        f.value.is_sign_positive() && f.value.is_finite()
+      })
+      .prop_map(|f| (f.to_string(), Expression::Float(f)))
+  };
+  let var = || Ident::any().prop_map(|i| (i.name.to_string(), Expression::Var(i)));
+  let call = |callee: Ident<()>, args: Vec<(std::string::String, Expression<()>)>| {
+    args.into_iter().fold(
+      (callee.name.to_string(), Expression::Var(callee)),
+      |(code, value), (arg, expr)| {
+        let call = Call {
+          value: Ptr::new(value),
+          args: [expr].into_iter().collect(),
+          info: (),
+        };
+        (format!("{code} {arg}"), Expression::Call(call))
+      },
+    )
+  };
+  // calls of values, which can be followed by a method
+  let value_calls =
+    move || (Ident::any(), vec(float(), 1..4)).prop_map(move |(f, args)| call(f, args));
+  // calls ending in a name, which is only an argument at the end of an expression
+  let calls = move || {
+    (Ident::any(), vec(float(), 0..3), var()).prop_map(move |(f, mut args, name)| {
+      args.push(name);
+      call(f, args)
+    })
+  };
+  let method = || {
+    Ident::any().prop_map(|i| Method {
+      name: Name::Literal(i.name),
+      info: (),
+    })
+  };
+  let sign = prop_oneof![Just(Symbol::Plus), Just(Symbol::Minus)].prop_map(|sym| Method {
+    name: Name::Symbol(sym),
+    info: (),
+  });
+
+  prop_oneof![
+    calls(),
+    (var(), method(), var()).prop_map(|(l, name, r)| infix(l, name, r)),
+    (var(), method(), calls()).prop_map(|(l, name, r)| infix(l, name, r)),
+    (var(), method(), var(), method(), calls()).prop_map(|(l, name, m, other, r)| infix(
+      infix(l, name, m),
+      other,
+      r
+    )),
+    (value_calls(), method(), calls()).prop_map(|(l, name, r)| infix(l, name, r)),
+    // a parenthesised call can end in a name
+    (calls(), method(), calls()).prop_map(|((l, value), name, r)| infix(
+      (format!("({l})"), value),
+      name,
+      r
+    )),
+    (float(), method(), calls()).prop_map(|(l, name, r)| infix(l, name, r)),
+    (value_calls(), sign, float()).prop_map(|(l, name, r)| infix(l, name, r)),
+  ]
+}
+
+/// An infix method, along with the code it's written as
+fn infix(
+  (l, value): (std::string::String, Expression<()>),
+  name: Method<()>,
+  (r, other): (std::string::String, Expression<()>),
+) -> (std::string::String, Expression<()>) {
+  let code = format!("{l} {name} {r}");
+  let infix = Infix {
+    value: Ptr::new(value),
+    name,
+    other: Ptr::new(other),
+    info: (),
+  };
+  (code, Expression::Infix(infix))
+}
+
+/// Finds the blocks of a single expression in `expr`, that were added as parentheses by `operators`
+fn added_parens<'a>(expr: &Expression<SpanTokens<'a>>, parens: &mut Vec<SpanTokens<'a>>) {
+  use Expression::*;
+  match expr {
+    Block(b) => match b.statements.first() {
+      Some(Statement::Expression(e)) if b.statements.len() == 1 => {
+        parens.push(b.info);
+        added_parens(e, parens)
+      }
+      _ => {}
+    },
+    Infix(i) => {
+      added_parens(&i.value, parens);
+      added_parens(&i.other, parens)
+    }
+    Prefix(p) => added_parens(&p.value, parens),
+    Call(c) => {
+      added_parens(&c.value, parens);
+      c.args.iter().for_each(|arg| added_parens(arg, parens))
+    }
+    Field(f) => added_parens(&f.value, parens),
+    Index(i) => {
+      added_parens(&i.value, parens);
+      i.keys.iter().for_each(|key| added_parens(key, parens))
+    }
+    Assign(a) => {
+      added_parens(&a.reference, parens);
+      added_parens(&a.value, parens)
+    }
+    Declare(d) => added_parens(&d.value, parens),
+    Return(r) => added_parens(&r.value, parens),
+    _ => {}
+  }
+}
+
+/// Replaces blocks of a single expression with that expression, undoing the parentheses added by `operators`
+fn without_parens(expr: &Expression<()>) -> Expression<()> {
+  use Expression::*;
+  let inner = |e: &Ptr<Expression<()>>| Ptr::new(without_parens(e));
+  match expr {
+    Block(b) => match b.statements.first() {
+      Some(Statement::Expression(e)) if b.statements.len() == 1 => without_parens(e),
+      _ => expr.clone(),
+    },
+    Infix(i) => Infix(diom_syntax::expressions::Infix {
+      value: inner(&i.value),
+      other: inner(&i.other),
+      ..i.clone()
+    }),
+    Prefix(p) => Prefix(diom_syntax::expressions::Prefix {
+      value: inner(&p.value),
+      ..p.clone()
+    }),
+    Call(c) => Call(diom_syntax::expressions::Call {
+      value: inner(&c.value),
+      args: c.args.iter().map(without_parens).collect(),
+      ..c.clone()
+    }),
+    Field(f) => Field(diom_syntax::expressions::Field {
+      value: inner(&f.value),
+      ..f.clone()
+    }),
+    Index(i) => Index(diom_syntax::expressions::Index {
+      value: inner(&i.value),
+      keys: i.keys.iter().map(without_parens).collect(),
+      ..i.clone()
+    }),
+    Assign(a) => Assign(diom_syntax::expressions::Assign {
+      reference: inner(&a.reference),
+      value: inner(&a.value),
+      ..a.clone()
+    }),
+    Declare(d) => Declare(diom_syntax::expressions::Declare {
+      value: inner(&d.value),
+      ..d.clone()
+    }),
+    Return(r) => Return(diom_syntax::expressions::Return {
+      value: inner(&r.value),
+      ..r.clone()
+    }),
+    _ => expr.clone(),
+  }
+}
+
+/// Generates programs of literal expressions and other top level statements
+fn programs() -> impl Strategy<Value = Program<()>> {
+  let default = ExpressionConfig::default();
+  let config = ExpressionConfig {
+    depth: 0,
+    types: TypeConfig {
+      depth: 0,
+      ..default.types
+    },
+    ..default
+  };
+  Program::any(config)
+    .prop_filter("finite", |program| {
+      program.statements.iter().all(|statement| match statement {
+        Statement::Expression(Expression::Float(f)) => f.value.is_finite(),
+        _ => true,
+      })
+    })
+    // negative floats parse as a prefix `-`
+    .prop_map(|mut program| {
+      for statement in program.statements.iter_mut() {
+        if let Statement::Expression(Expression::Float(f)) = statement {
+          f.value = f.value.abs();
+        }
+      }
+      program
+    })
+}
+
+/// Generates literal expressions, which always parse back to themselves
+fn literal() -> impl Strategy<Value = Expression<()>> {
+  prop_oneof![
+    Char::any().prop_map(Expression::Char),
+    // negative floats parse as a prefix `-`
+    Float::any()
+      .prop_filter("positive", |f| f.value.is_sign_positive()
+        && f.value.is_finite())
+      .prop_map(Expression::Float),
+    String::any().prop_map(Expression::String),
+    Ident::any().prop_map(Expression::Var),
+  ]
 }
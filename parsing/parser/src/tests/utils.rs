@@ -30,7 +30,9 @@ where
     .parse(tokens)
     .map_err(|err| err.display_with(on(code)))
     .unwrap();
-  let SyntaxNode::Expression(expr) = node;
+  let SyntaxNode::Expression(expr) = node else {
+    panic!("expected an expression, found a program")
+  };
   expr
 }
 
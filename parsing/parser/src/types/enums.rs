@@ -1,17 +1,12 @@
 use crate::{
   common::{PResult, Token},
   errors::SyntaxError,
-  parsers::{group, matches},
+  parsers::{end, group, matches},
   types::parse_tagged,
   In,
 };
 use diom_syntax::types::Enum;
-use nom::{
-  combinator::{consumed, eof},
-  multi::separated_list0,
-  sequence::terminated,
-  Parser,
-};
+use nom::{combinator::consumed, multi::separated_list0, sequence::terminated, Parser};
 
 /// Parses a enum-like type.
 ///
@@ -35,7 +30,10 @@ use nom::{
 /// { Some(Number), None }
 /// ```
 pub fn parse_enum<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Enum<In<'a>>, E> {
-  let parse_inner = terminated(separated_list0(matches(Token::Comma), parse_tagged), eof);
+  let parse_inner = terminated(
+    separated_list0(matches(Token::Comma), parse_tagged),
+    end([Token::Comma, Token::RCurly]),
+  );
   let parser = group(Token::LCurly, Token::RCurly).and_then(parse_inner);
 
   let (input, (info, variants)) = consumed(parser).parse(input)?;
@@ -2,7 +2,7 @@ use crate::{
   common::{PResult, Token},
   errors::SyntaxError,
   idents::parse_ident,
-  parsers::{group, matches},
+  parsers::{end, group, matches},
   In,
 };
 use diom_syntax::{
@@ -10,7 +10,7 @@ use diom_syntax::{
   Ptr,
 };
 use nom::{
-  combinator::{consumed, eof},
+  combinator::consumed,
   multi::separated_list0,
   sequence::{preceded, terminated},
   Parser,
@@ -33,7 +33,10 @@ fn parse_parameter<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Paramet
 }
 
 fn parse_parameters<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Parameters<In<'a>>, E> {
-  let parse_inner = terminated(separated_list0(matches(Token::Comma), parse_parameter), eof);
+  let parse_inner = terminated(
+    separated_list0(matches(Token::Comma), parse_parameter),
+    end([Token::Comma, Token::RParen]),
+  );
   let parse_params = group(Token::LParen, Token::RParen).and_then(parse_inner);
   let (input, (info, parameters)) = consumed(parse_params).parse(input)?;
   Ok((input, Parameters { parameters, info }))
@@ -2,17 +2,12 @@ use super::parse_type;
 use crate::{
   common::PResult,
   errors::SyntaxError,
-  parsers::{group, matches},
+  parsers::{end, group, matches},
   In,
 };
 use diom_syntax::types::Tuple;
 use diom_tokens::Token;
-use nom::{
-  combinator::{consumed, eof},
-  multi::separated_list1,
-  sequence::terminated,
-  Parser,
-};
+use nom::{combinator::consumed, multi::separated_list1, sequence::terminated, Parser};
 
 /// Parses a tuple-like type.
 ///
@@ -36,7 +31,10 @@ use nom::{
 /// ( Number, Number )
 /// ```
 pub fn parse_tuple<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Tuple<In<'a>>, E> {
-  let parse_inner = terminated(separated_list1(matches(Token::Comma), parse_type), eof);
+  let parse_inner = terminated(
+    separated_list1(matches(Token::Comma), parse_type),
+    end([Token::Comma, Token::RBrace]),
+  );
   let parser = group(Token::LBrace, Token::RBrace).and_then(parse_inner);
 
   let (input, (info, fields)) = consumed(parser).parse(input)?;
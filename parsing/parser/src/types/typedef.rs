@@ -1,5 +1,6 @@
 use super::parse_type;
 use crate::{
+  docs::parse_docs,
   errors::{PResult, SyntaxError},
   idents::parse_ident,
   parsers::matches,
@@ -17,18 +18,28 @@ use nom::{
 };
 
 pub fn parse_alias<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, Alias<In<'a>>, E> {
+  let (input, doc) = parse_docs(input)?;
   let parser =
     delimited(matches(Token::Type), parse_ident, matches(Token::Assign)).and(cut(parse_type));
   let parser = context("type alias", parser);
   let (input, (info, (name, value))) = consumed(parser).parse(input)?;
-  Ok((input, Alias { name, value, info }))
+  Ok((
+    input,
+    Alias {
+      doc,
+      name,
+      value,
+      info,
+    },
+  ))
 }
 
 pub fn parse_newtype<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, NewType<In<'a>>, E> {
+  let (input, doc) = parse_docs(input)?;
   let parser = preceded(matches(Token::Type), parse_tagged);
   let parser = context("newtype", parser);
   let (input, (info, tag)) = consumed(parser).parse(input)?;
-  Ok((input, NewType { tag, info }))
+  Ok((input, NewType { doc, tag, info }))
 }
 
 pub fn parse_typedef<'a, E: SyntaxError<'a>>(input: In<'a>) -> PResult<'a, TypeDef<In<'a>>, E> {
@@ -51,6 +62,7 @@ mod tests {
   #[test]
   fn typedef_eq_safe() {
     let def = TypeDef::Alias(Alias {
+      doc: None,
       name: Ident {
         name: "_".into(),
         info: (),
@@ -1,3 +1,4 @@
+use crate::Ptr;
 use std::fmt::{Display, Write};
 
 /// Displays a list of items separated with a character
@@ -27,3 +28,24 @@ impl<T: Display> Display for Optn<'_, T> {
     self.0.as_ref().map_or(Ok(()), |v| v.fmt(f))
   }
 }
+
+/// Displays doc comments, as a `##` line for each line of the docs
+pub struct Docs<'a>(pub &'a Option<Ptr<str>>);
+
+impl Display for Docs<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let Some(doc) = self.0 else {
+      return Ok(());
+    };
+    for line in doc.split('\n') {
+      writeln!(f, "##{line}")?;
+    }
+    Ok(())
+  }
+}
+
+/// Generates optional doc comments, without any line breaks other than `\n`
+pub(crate) fn any_docs() -> impl proptest::prelude::Strategy<Value = Option<Ptr<str>>> {
+  use proptest::{collection::vec, option, prelude::Strategy};
+  option::of(vec("[^\r\n]*", 1..4).prop_map(|lines| lines.join("\n").into()))
+}
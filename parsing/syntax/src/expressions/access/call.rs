@@ -1,5 +1,9 @@
 use super::Expression;
-use crate::{display::Sep, Ptr, Seq};
+use crate::{
+  display::Sep,
+  expressions::precedence::{Context, Operand, Operator},
+  Ptr, Seq,
+};
 use diom_fmt::{DisplayAs, SpanWriter, Spans};
 use diom_info_traits::{InfoMap, InfoRef, InfoSource};
 use proptest::{collection::vec, prelude::Strategy};
@@ -9,6 +13,7 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Call<I> {
   pub value: Ptr<Expression<I>>,
   pub args: Seq<Expression<I>>,
@@ -17,7 +22,18 @@ pub struct Call<I> {
 
 impl<I> Display for Call<I> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    self.value.fmt(f)?;
+    self.fmt_within(f, Context::default())
+  }
+}
+
+impl<I> Call<I> {
+  /// Displays the call within `context`
+  pub(crate) fn fmt_within(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+    context: Context,
+  ) -> std::fmt::Result {
+    Operand(&self.value, context.before(Operator::POSTFIX)).fmt(f)?;
     f.write_char('(')?;
     Sep(&self.args, ',').fmt(f)?;
     f.write_char(')')
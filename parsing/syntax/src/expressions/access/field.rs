@@ -1,5 +1,9 @@
 use super::Expression;
-use crate::{idents::Method, Ptr};
+use crate::{
+  expressions::precedence::{Context, Operand, Operator},
+  idents::Method,
+  Ptr,
+};
 use diom_fmt::{DisplayAs, SpanWriter, Spans};
 use diom_info_traits::{InfoMap, InfoRef, InfoSource};
 use proptest::prelude::Strategy;
@@ -9,6 +13,7 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Field<I> {
   pub value: Ptr<Expression<I>>,
   pub name: Method<I>,
@@ -17,7 +22,18 @@ pub struct Field<I> {
 
 impl<I> Display for Field<I> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    self.value.fmt(f)?;
+    self.fmt_within(f, Context::default())
+  }
+}
+
+impl<I> Field<I> {
+  /// Displays the field access within `context`
+  pub(crate) fn fmt_within(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+    context: Context,
+  ) -> std::fmt::Result {
+    Operand(&self.value, context.before(Operator::POSTFIX)).fmt(f)?;
     f.write_char('.')?;
     self.name.fmt(f)
   }
@@ -5,7 +5,11 @@
 //! These are only used during parsing!<br>
 //! They will be translated into field calls.
 use super::Expression;
-use crate::{idents::Method, Ptr};
+use crate::{
+  expressions::precedence::{Context, Operand, Operator},
+  idents::Method,
+  Ptr,
+};
 use diom_fmt::{DisplayAs, SpanWriter, Spans};
 use diom_info_traits::{InfoMap, InfoRef, InfoSource};
 use proptest::prelude::Strategy;
@@ -15,6 +19,7 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Infix<I> {
   pub value: Ptr<Expression<I>>,
   pub name: Method<I>,
@@ -24,11 +29,23 @@ pub struct Infix<I> {
 
 impl<I> Display for Infix<I> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    self.value.fmt(f)?;
+    self.fmt_within(f, Context::default())
+  }
+}
+
+impl<I> Infix<I> {
+  /// Displays the infix operation within `context`
+  pub(crate) fn fmt_within(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+    context: Context,
+  ) -> std::fmt::Result {
+    let op = Operator::method(&self.name.name);
+    Operand(&self.value, context.before(op)).fmt(f)?;
     f.write_char(' ')?;
     self.name.fmt(f)?;
     f.write_char(' ')?;
-    self.other.fmt(f)
+    Operand(&self.other, context.after(op)).fmt(f)
   }
 }
 
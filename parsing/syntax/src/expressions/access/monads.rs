@@ -1,5 +1,8 @@
 use super::Expression;
-use crate::Ptr;
+use crate::{
+  expressions::precedence::{Context, Operand, Operator},
+  Ptr,
+};
 use diom_fmt::{DisplayAs, SpanWriter, Spans};
 use diom_info_traits::{InfoMap, InfoRef, InfoSource};
 use proptest::prelude::Strategy;
@@ -48,6 +51,7 @@ use std::{
 /// assert optn_x == None;
 /// ```
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MonadThen<I> {
   pub value: Ptr<Expression<I>>,
   pub info: I,
@@ -55,7 +59,18 @@ pub struct MonadThen<I> {
 
 impl<I> Display for MonadThen<I> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    self.value.fmt(f)?;
+    self.fmt_within(f, Context::default())
+  }
+}
+
+impl<I> MonadThen<I> {
+  /// Displays the unwrap within `context`
+  pub(crate) fn fmt_within(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+    context: Context,
+  ) -> std::fmt::Result {
+    Operand(&self.value, context.before(Operator::POSTFIX)).fmt(f)?;
     f.write_char('?')
   }
 }
@@ -110,6 +125,7 @@ impl MonadThen<()> {
 /// assert optn_x == Some [5];
 /// ```
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MonadResult<I> {
   pub value: Ptr<Expression<I>>,
   pub info: I,
@@ -117,7 +133,18 @@ pub struct MonadResult<I> {
 
 impl<I> Display for MonadResult<I> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    self.value.fmt(f)?;
+    self.fmt_within(f, Context::default())
+  }
+}
+
+impl<I> MonadResult<I> {
+  /// Displays the result within `context`
+  pub(crate) fn fmt_within(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+    context: Context,
+  ) -> std::fmt::Result {
+    Operand(&self.value, context.before(Operator::POSTFIX)).fmt(f)?;
     f.write_char('!')
   }
 }
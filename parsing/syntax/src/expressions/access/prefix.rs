@@ -1,5 +1,9 @@
 use super::Expression;
-use crate::{idents::Op, Ptr};
+use crate::{
+  expressions::precedence::{Context, Operand, Operator},
+  idents::Op,
+  Ptr,
+};
 use diom_fmt::{DisplayAs, SpanWriter, Spans};
 use diom_info_traits::{InfoMap, InfoRef, InfoSource};
 use proptest::prelude::Strategy;
@@ -9,6 +13,7 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Prefix<I> {
   pub name: Op<I>,
   pub value: Ptr<Expression<I>>,
@@ -17,9 +22,20 @@ pub struct Prefix<I> {
 
 impl<I> Display for Prefix<I> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    self.fmt_within(f, Context::default())
+  }
+}
+
+impl<I> Prefix<I> {
+  /// Displays the prefix operation within `context`
+  pub(crate) fn fmt_within(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+    context: Context,
+  ) -> std::fmt::Result {
     self.name.fmt(f)?;
     f.write_char(' ')?;
-    self.value.fmt(f)
+    Operand(&self.value, context.after(Operator::PREFIX)).fmt(f)
   }
 }
 
@@ -1,5 +1,8 @@
 use super::Expression;
-use crate::Ptr;
+use crate::{
+  expressions::precedence::{Context, Operand, Operator},
+  Ptr,
+};
 use diom_fmt::{DisplayAs, SpanWriter, Spans};
 use diom_info_traits::{InfoMap, InfoRef, InfoSource};
 use proptest::prelude::Strategy;
@@ -9,6 +12,7 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Assign<I> {
   pub reference: Ptr<Expression<I>>,
   pub value: Ptr<Expression<I>>,
@@ -17,9 +21,20 @@ pub struct Assign<I> {
 
 impl<I> Display for Assign<I> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    self.reference.fmt(f)?;
+    self.fmt_within(f, Context::default())
+  }
+}
+
+impl<I> Assign<I> {
+  /// Displays the assignment within `context`
+  pub(crate) fn fmt_within(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+    context: Context,
+  ) -> std::fmt::Result {
+    Operand(&self.reference, context.before(Operator::ASSIGN)).fmt(f)?;
     f.write_char('=')?;
-    self.value.fmt(f)
+    Operand(&self.value, context.after(Operator::ASSIGN)).fmt(f)
   }
 }
 
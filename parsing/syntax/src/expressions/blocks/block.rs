@@ -1,6 +1,7 @@
 use super::Expression;
 use crate::{
   display::Sep,
+  modules::Use,
   types::{TypeConfig, TypeDef},
   Seq,
 };
@@ -13,9 +14,11 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Statement<I> {
   Expression(Expression<I>),
   TypeDef(TypeDef<I>),
+  Use(Use<I>),
 }
 
 impl<I> Display for Statement<I> {
@@ -23,6 +26,7 @@ impl<I> Display for Statement<I> {
     match self {
       Self::Expression(e) => e.fmt(f),
       Self::TypeDef(t) => t.fmt(f),
+      Self::Use(u) => u.fmt(f),
     }
   }
 }
@@ -32,6 +36,7 @@ impl DisplayAs<Spans> for Statement<Range<usize>> {
     match self {
       Self::Expression(e) => e.write(w),
       Self::TypeDef(d) => d.write(w),
+      Self::Use(u) => u.write(w),
     }
   }
 }
@@ -50,6 +55,7 @@ impl Statement<()> {
 }
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Block<I> {
   pub statements: Seq<Statement<I>>,
   pub info: I,
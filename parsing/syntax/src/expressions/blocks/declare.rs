@@ -1,5 +1,7 @@
 use super::Expression;
 use crate::{
+  display::{any_docs, Docs},
+  expressions::precedence::{Context, Operand, Operator},
   patterns::{Pattern, PatternConfig},
   types::{Type, TypeConfig},
   Ptr,
@@ -33,7 +35,11 @@ use std::{
 /// then it will allow the return value to remain unused, otherwise if the
 /// return value is not used, it'll throw an compiler error.
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Declare<I> {
+  /// Doc comments written before the declaration, i.e. `## The answer`
+  #[map_ignore]
+  pub doc: Option<Ptr<str>>,
   pub pattern: Pattern<I>,
   pub annotation: Option<Type<I>>,
   pub value: Ptr<Expression<I>>,
@@ -42,6 +48,18 @@ pub struct Declare<I> {
 
 impl<I> Display for Declare<I> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    self.fmt_within(f, Context::default())
+  }
+}
+
+impl<I> Declare<I> {
+  /// Displays the declaration within `context`
+  pub(crate) fn fmt_within(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+    context: Context,
+  ) -> std::fmt::Result {
+    Docs(&self.doc).fmt(f)?;
     f.write_str("let ")?;
     self.pattern.fmt(f)?;
     if let Some(annotation) = &self.annotation {
@@ -49,7 +67,7 @@ impl<I> Display for Declare<I> {
       annotation.fmt(f)?
     }
     f.write_char('=')?;
-    self.value.fmt(f)
+    Operand(&self.value, context.after(Operator::DECLARE)).fmt(f)
   }
 }
 
@@ -75,13 +93,18 @@ impl Declare<()> {
     item: impl Strategy<Value = Expression<()>>,
     args: DeclareConfig,
   ) -> impl Strategy<Value = Self> {
-    (Pattern::any(args.0), option::of(Type::any(args.1)), item).prop_map(
-      |(pattern, annotation, value)| Declare {
+    (
+      any_docs(),
+      Pattern::any(args.0),
+      option::of(Type::any(args.1)),
+      item,
+    )
+      .prop_map(|(doc, pattern, annotation, value)| Declare {
+        doc,
         pattern,
         annotation,
         value: Ptr::new(value),
         info: (),
-      },
-    )
+      })
   }
 }
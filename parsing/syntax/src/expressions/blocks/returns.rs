@@ -1,5 +1,8 @@
 use super::Expression;
-use crate::Ptr;
+use crate::{
+  expressions::precedence::{Context, Operand, Operator},
+  Ptr,
+};
 use diom_fmt::{DisplayAs, SpanWriter, Spans};
 use diom_info_traits::{InfoMap, InfoRef, InfoSource};
 use proptest::prelude::Strategy;
@@ -9,6 +12,7 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Return<I> {
   pub value: Ptr<Expression<I>>,
   pub info: I,
@@ -16,8 +20,19 @@ pub struct Return<I> {
 
 impl<I> Display for Return<I> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    self.fmt_within(f, Context::default())
+  }
+}
+
+impl<I> Return<I> {
+  /// Displays the return within `context`
+  pub(crate) fn fmt_within(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+    context: Context,
+  ) -> std::fmt::Result {
     f.write_str("return ")?;
-    self.value.fmt(f)
+    Operand(&self.value, context.after(Operator::RETURN)).fmt(f)
   }
 }
 
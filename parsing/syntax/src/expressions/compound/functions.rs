@@ -14,6 +14,7 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Parameter<I> {
   pub pattern: Pattern<I>,
   pub annotation: Option<Type<I>>,
@@ -60,6 +61,7 @@ impl Parameter<()> {
 }
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Parameters<I> {
   pub parameters: Seq<Parameter<I>>,
   pub info: I,
@@ -112,6 +114,7 @@ impl Parameters<()> {
 }
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FunctionArm<I> {
   pub parameters: Parameters<I>,
   pub annotation: Option<Type<I>>,
@@ -158,6 +161,7 @@ impl FunctionArm<()> {
 }
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Function<I> {
   pub arms: Vec<FunctionArm<I>>,
   pub info: I,
@@ -6,3 +6,5 @@ pub mod functions;
 pub use functions::{Function, FunctionArm, Parameter, Parameters};
 pub mod structs;
 pub use structs::Struct;
+pub mod templates;
+pub use templates::Template;
@@ -9,6 +9,7 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Struct<I> {
   pub fields: Seq<(Method<I>, Expression<I>)>,
   pub info: I,
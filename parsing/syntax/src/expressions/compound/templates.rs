@@ -0,0 +1,91 @@
+use super::Expression;
+use crate::{Ptr, Seq};
+use diom_fmt::{DisplayAs, SpanWriter, Spans};
+use diom_info_traits::{InfoMap, InfoRef, InfoSource};
+use diom_tokens::templates::Escaped;
+use proptest::{
+  collection::vec,
+  prelude::{any, Strategy},
+};
+use std::{
+  fmt::{Display, Write},
+  ops::Range,
+};
+
+/// A template literal, i.e. `f"hello {name}!"`.\
+/// Interpolated values are kept apart from the text around them,\
+/// so templates are only flattened into strings when explicitly converted.
+#[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Template<I> {
+  /// The text around interpolated values,\
+  /// this always has one more item than `values`.
+  #[map_ignore]
+  pub strings: Seq<Ptr<str>>,
+  pub values: Seq<Expression<I>>,
+  pub info: I,
+}
+
+impl<I> Display for Template<I> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "f\"{}", Escaped(&self.strings[0]))?;
+    for (value, text) in self.values.iter().zip(&self.strings[1..]) {
+      write!(f, "{{{value}}}{}", Escaped(text))?;
+    }
+    f.write_char('"')
+  }
+}
+
+impl DisplayAs<Spans> for Template<Range<usize>> {
+  fn write<W: Write>(&self, w: &mut SpanWriter<W>) -> std::fmt::Result {
+    w.bracket("template", &self.info)?;
+    self.values.write(&mut w.child())
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct TemplateConfig(
+  /// The maximum number of values interpolated in a template
+  pub usize,
+);
+impl Default for TemplateConfig {
+  fn default() -> Self {
+    Self(10)
+  }
+}
+impl Template<()> {
+  /// Generates a generic strategy for generating `Template` expressions
+  pub fn any(
+    item: impl Strategy<Value = Expression<()>>,
+    args: TemplateConfig,
+  ) -> impl Strategy<Value = Self> {
+    let text = any::<String>().prop_map(Ptr::from);
+    (text.clone(), vec((item, text), 0..args.0)).prop_map(|(first, parts)| {
+      let (values, rest): (Seq<_>, Seq<_>) = parts.into_iter().unzip();
+      Template {
+        strings: std::iter::once(first).chain(rest).collect(),
+        values,
+        info: (),
+      }
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Template;
+  use crate::expressions::{Expression, Float};
+
+  #[test]
+  fn formatting() {
+    let template = Template {
+      strings: vec!["{x} = ".into(), "\n".into()],
+      values: vec![Expression::Float(Float {
+        value: 2.0,
+        info: (),
+      })],
+      info: (),
+    };
+    assert_eq!(template.to_string(), r#"f"\{x\} = {2}\n""#);
+  }
+}
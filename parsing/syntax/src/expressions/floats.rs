@@ -7,15 +7,24 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Float<I> {
   #[map_ignore]
   pub value: f64,
   pub info: I,
 }
 
+/// The largest integer that floats can represent exactly, and every integer below it
+const MAX_EXACT: f64 = (1u64 << f64::MANTISSA_DIGITS) as f64;
+
 impl<I> Display for Float<I> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    self.value.fmt(f)
+    // exact integers are written as integers, i.e. `2` not `2.0`,\
+    // anything else keeps its `.` or exponent, so large values don't overflow when lexed
+    if self.value.fract() == 0.0 && self.value.abs() <= MAX_EXACT {
+      return self.value.fmt(f);
+    }
+    write!(f, "{:?}", self.value)
   }
 }
 
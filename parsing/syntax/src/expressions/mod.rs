@@ -2,9 +2,13 @@ use crate::{
   expressions::{
     access::{call::CallConfig, index::IndexConfig},
     blocks::{block::BlockConfig, declare::DeclareConfig},
-    compound::{arrays::ArrayConfig, functions::FunctionConfig, structs::StructConfig},
+    compound::{
+      arrays::ArrayConfig, functions::FunctionConfig, structs::StructConfig,
+      templates::TemplateConfig,
+    },
   },
   idents::Ident,
+  invalid::Invalid,
   patterns::PatternConfig,
   types::TypeConfig,
 };
@@ -23,19 +27,24 @@ mod chars;
 pub use chars::Char;
 mod floats;
 pub use floats::Float;
+mod strings;
+pub use strings::String;
 
 mod access;
 pub use access::{Call, Field, Index, Infix, MonadResult, MonadThen, Prefix};
 mod blocks;
 pub use blocks::{Assign, Block, Declare, Group, Return, Statement};
 mod compound;
-pub use compound::{Array, Function, FunctionArm, Parameter, Parameters, Struct};
+pub use compound::{Array, Function, FunctionArm, Parameter, Parameters, Struct, Template};
+pub mod precedence;
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expression<I> {
   /* base values in the language */
   Char(Char<I>),
   Float(Float<I>),
+  String(String<I>),
   Var(Ident<I>),
   /* block expressions */
   Group(Group<I>),
@@ -47,6 +56,7 @@ pub enum Expression<I> {
   Array(Array<I>),
   Function(Function<I>),
   Struct(Struct<I>),
+  Template(Template<I>),
   /* accessing values in the language */
   Call(Call<I>),
   Field(Field<I>),
@@ -55,6 +65,8 @@ pub enum Expression<I> {
   Monad(MonadThen<I>),
   Prefix(Prefix<I>),
   Result(MonadResult<I>),
+  /* source that failed to parse */
+  Error(Invalid<I>),
 }
 
 impl<I> Display for Expression<I> {
@@ -63,6 +75,7 @@ impl<I> Display for Expression<I> {
     match self {
       Char(c) => c.fmt(f),
       Float(v) => v.fmt(f),
+      String(s) => s.fmt(f),
       Var(v) => v.fmt(f),
       //
       Group(g) => g.fmt(f),
@@ -74,6 +87,7 @@ impl<I> Display for Expression<I> {
       Array(a) => a.fmt(f),
       Function(v) => v.fmt(f),
       Struct(s) => s.fmt(f),
+      Template(t) => t.fmt(f),
       //
       Call(c) => c.fmt(f),
       Field(v) => v.fmt(f),
@@ -82,6 +96,8 @@ impl<I> Display for Expression<I> {
       Monad(m) => m.fmt(f),
       Prefix(p) => p.fmt(f),
       Result(r) => r.fmt(f),
+      //
+      Error(e) => e.fmt(f),
     }
   }
 }
@@ -92,6 +108,7 @@ impl DisplayAs<Spans> for Expression<Range<usize>> {
     match self {
       Char(c) => c.write(w),
       Float(f) => f.write(w),
+      String(s) => s.write(w),
       Var(v) => v.write(w),
       //
       Group(g) => g.write(w),
@@ -103,6 +120,7 @@ impl DisplayAs<Spans> for Expression<Range<usize>> {
       Array(a) => a.write(w),
       Function(f) => f.write(w),
       Struct(s) => s.write(w),
+      Template(t) => t.write(w),
       //
       Call(c) => c.write(w),
       Field(f) => f.write(w),
@@ -111,6 +129,8 @@ impl DisplayAs<Spans> for Expression<Range<usize>> {
       Monad(m) => m.write(w),
       Prefix(p) => p.write(w),
       Result(r) => r.write(w),
+      //
+      Error(e) => e.write(w),
     }
   }
 }
@@ -137,6 +157,8 @@ pub struct ExpressionConfig {
   pub func_arms: usize,
   /// The maximum number of fields in a struct
   pub struct_fields: usize,
+  /// The maximum number of values interpolated in a template
+  pub template_values: usize,
   /// The maximum number of arguments to a function call
   pub call_args: usize,
   /// The maximum number of keys for a value index
@@ -167,6 +189,7 @@ impl Default for ExpressionConfig {
       func_params: func_config.2,
       func_arms: func_config.3,
       struct_fields: StructConfig::default().0,
+      template_values: TemplateConfig::default().0,
       call_args: CallConfig::default().0,
       index_keys: IndexConfig::default().0,
     }
@@ -202,6 +225,11 @@ impl From<ExpressionConfig> for StructConfig {
     Self(value.struct_fields)
   }
 }
+impl From<ExpressionConfig> for TemplateConfig {
+  fn from(value: ExpressionConfig) -> Self {
+    Self(value.template_values)
+  }
+}
 impl From<ExpressionConfig> for CallConfig {
   fn from(value: ExpressionConfig) -> Self {
     Self(value.call_args)
@@ -218,6 +246,7 @@ impl Expression<()> {
     let leaf = prop_oneof![
       Char::any().prop_map(Self::Char),
       Float::any().prop_map(Self::Float),
+      String::any().prop_map(Self::String),
       Ident::any().prop_map(Self::Var)
     ];
     let branch_width = args
@@ -225,6 +254,7 @@ impl Expression<()> {
       .max(args.array_items)
       .max(args.func_arms)
       .max(args.struct_fields)
+      .max(args.template_values)
       .max(args.call_args + 1)
       .max(args.index_keys + 1) as u32;
 
@@ -239,6 +269,7 @@ impl Expression<()> {
         Array::any(item.clone(), args.into()).prop_map(Self::Array),
         Function::any(item.clone(), args.into()).prop_map(Self::Function),
         Struct::any(item.clone(), args.into()).prop_map(Self::Struct),
+        Template::any(item.clone(), args.into()).prop_map(Self::Template),
         //
         Call::any(item.clone(), args.into()).prop_map(Self::Call),
         Field::any(item.clone()).prop_map(Self::Field),
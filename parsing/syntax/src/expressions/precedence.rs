@@ -0,0 +1,172 @@
+//! Operator precedence, so that operands are only parenthesised where they'd otherwise parse differently.
+//!
+//! These mirror the precedences used by the parser, where lower precedences bind tighter:
+//!
+//! | precedence | operators                          | fixity        |
+//! |------------|------------------------------------|---------------|
+//! | 1          | `let ... =`, `return`              | prefix        |
+//! | 2          | `.field`, `[index]`, calls, `?`    | postfix       |
+//! | 3          | named methods, i.e. `a max b`      | left infix    |
+//! | 4          | `*`, `/`                           | left infix    |
+//! | 5          | `+`, `-`                           | prefix, infix |
+//! | 6          | `&`, `\|`                          | left infix    |
+//! | 7          | `<`, `>`, `<=`, `>=`, `==`, `!=`   | left infix    |
+//! | 8          | `=`                                | right infix   |
+use super::Expression;
+use crate::idents::{Name, Symbol};
+use std::fmt::{Display, Formatter};
+
+/// Which operand of an infix operator is applied first when it's chained
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Assoc {
+  Left,
+  Right,
+}
+
+/// Where an operator is written relative to its operands
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fixity {
+  Prefix,
+  Postfix,
+  Infix(Assoc),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Operator {
+  pub precedence: u8,
+  pub fixity: Fixity,
+}
+
+impl Operator {
+  pub const DECLARE: Self = Self::new(1, Fixity::Prefix);
+  pub const RETURN: Self = Self::new(1, Fixity::Prefix);
+  pub const POSTFIX: Self = Self::new(2, Fixity::Postfix);
+  pub const PREFIX: Self = Self::new(5, Fixity::Prefix);
+  pub const ASSIGN: Self = Self::new(8, Fixity::Infix(Assoc::Right));
+
+  pub const fn new(precedence: u8, fixity: Fixity) -> Self {
+    Self { precedence, fixity }
+  }
+
+  /// The operator for an infix method named `name`
+  pub fn method(name: &Name) -> Self {
+    let precedence = match name {
+      Name::Literal(_) => 3,
+      Name::Symbol(sym) => match sym {
+        Symbol::Times | Symbol::Divide => 4,
+        Symbol::Plus | Symbol::Minus => 5,
+        // `!` isn't parsed as an infix operator
+        Symbol::Not | Symbol::And | Symbol::Or => 6,
+        Symbol::Lt | Symbol::Gt | Symbol::LtEq | Symbol::GtEq | Symbol::Eq | Symbol::Ne => 7,
+      },
+    };
+    Self::new(precedence, Fixity::Infix(Assoc::Left))
+  }
+
+  /// Whether `self` is applied before `next`, when `next` is written directly after `self`'s operand.\
+  /// Otherwise, `next` takes the operand from `self`.
+  pub fn applies_before(self, next: Operator) -> bool {
+    match next.fixity {
+      // prefixes are never written directly after an operand
+      Fixity::Prefix => false,
+      Fixity::Postfix => self.precedence <= next.precedence,
+      Fixity::Infix(assoc) => {
+        self.precedence < next.precedence
+          || (assoc == Assoc::Left && self.precedence == next.precedence)
+          || self.fixity == Fixity::Postfix
+      }
+    }
+  }
+}
+
+impl<I> Expression<I> {
+  /// The operator applied at the top of this expression,\
+  /// or `None` when the expression is delimited on both sides.
+  pub fn operator(&self) -> Option<Operator> {
+    use Expression::*;
+    match self {
+      Char(_) | Float(_) | String(_) | Var(_) => None,
+      Group(_) | Block(_) | Array(_) | Function(_) | Struct(_) | Template(_) | Error(_) => None,
+      Assign(_) => Some(Operator::ASSIGN),
+      Declare(_) => Some(Operator::DECLARE),
+      Return(_) => Some(Operator::RETURN),
+      Call(_) | Field(_) | Index(_) | Monad(_) | Result(_) => Some(Operator::POSTFIX),
+      Infix(i) => Some(Operator::method(&i.name.name)),
+      Prefix(_) => Some(Operator::PREFIX),
+    }
+  }
+
+  /// Displays the expression within `context`, without parenthesising the expression itself
+  pub(crate) fn fmt_within(&self, f: &mut Formatter<'_>, context: Context) -> std::fmt::Result {
+    use Expression::*;
+    match self {
+      Assign(a) => a.fmt_within(f, context),
+      Declare(d) => d.fmt_within(f, context),
+      Return(r) => r.fmt_within(f, context),
+      Call(c) => c.fmt_within(f, context),
+      Field(v) => v.fmt_within(f, context),
+      Index(i) => i.fmt_within(f, context),
+      Infix(i) => i.fmt_within(f, context),
+      Monad(m) => m.fmt_within(f, context),
+      Prefix(p) => p.fmt_within(f, context),
+      Result(r) => r.fmt_within(f, context),
+      _ => self.fmt(f),
+    }
+  }
+}
+
+/// The operators written directly before and after an operand
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Context {
+  pub before: Option<Operator>,
+  pub after: Option<Operator>,
+}
+
+impl Context {
+  /// The context of the operand written before `op`
+  pub fn before(self, op: Operator) -> Self {
+    Self {
+      after: Some(op),
+      ..self
+    }
+  }
+
+  /// The context of the operand written after `op`
+  pub fn after(self, op: Operator) -> Self {
+    Self {
+      before: Some(op),
+      ..self
+    }
+  }
+
+  /// Whether an operand with `op` at its top would have its operands taken by the operators around it
+  pub fn needs_parens(self, op: Operator) -> bool {
+    let takes_left = match op.fixity {
+      Fixity::Prefix => false,
+      Fixity::Postfix | Fixity::Infix(_) => self.before.is_some_and(|b| b.applies_before(op)),
+    };
+    let takes_right = match op.fixity {
+      Fixity::Postfix => false,
+      Fixity::Prefix | Fixity::Infix(_) => self.after.is_some_and(|a| !op.applies_before(a)),
+    };
+    takes_left || takes_right
+  }
+}
+
+/// Displays an expression as an operand within `Context`,\
+/// wrapped in parentheses when it'd otherwise parse differently.
+pub struct Operand<'a, I>(pub &'a Expression<I>, pub Context);
+
+impl<I> Display for Operand<'_, I> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let Operand(expr, context) = self;
+    match expr.operator() {
+      Some(op) if context.needs_parens(op) => {
+        f.write_str("(")?;
+        expr.fmt_within(f, Context::default())?;
+        f.write_str(")")
+      }
+      _ => expr.fmt_within(f, *context),
+    }
+  }
+}
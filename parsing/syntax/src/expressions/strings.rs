@@ -0,0 +1,81 @@
+use crate::Ptr;
+use diom_fmt::{DisplayAs, SpanWriter, Spans};
+use diom_info_traits::{InfoMap, InfoRef, InfoSource};
+use diom_tokens::strings::StringLiteral;
+use proptest::prelude::{any, Arbitrary, BoxedStrategy, Strategy};
+use std::{
+  fmt::{Display, Write},
+  ops::Range,
+};
+
+/// A string literal, i.e. `"hello world"`, `r#"say "hi""#` or a `"""` text block.\
+/// This is kept intact in the syntax tree and is only evaluated to a `[Char]` array.
+#[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct String<I> {
+  #[map_ignore]
+  pub value: Ptr<str>,
+  pub info: I,
+}
+
+impl<I> Display for String<I> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    StringLiteral(&self.value).fmt(f)
+  }
+}
+
+impl DisplayAs<Spans> for String<Range<usize>> {
+  fn write<W: Write>(&self, w: &mut SpanWriter<W>) -> std::fmt::Result {
+    w.bracket("string", &self.info)
+  }
+}
+
+impl String<()> {
+  /// Generates a generic strategy for generating `String` expressions
+  pub fn any() -> impl Strategy<Value = Self> {
+    any::<std::string::String>().prop_map(|value| String {
+      value: value.into(),
+      info: (),
+    })
+  }
+}
+impl Arbitrary for String<()> {
+  type Parameters = ();
+  type Strategy = BoxedStrategy<Self>;
+
+  fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+    Self::any().boxed()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::String;
+
+  #[test]
+  fn formatting() {
+    let s = String {
+      value: "hello".into(),
+      info: (),
+    };
+    assert_eq!(s.to_string(), r#""hello""#);
+
+    let s = String {
+      value: "say \"hi\"\n".into(),
+      info: (),
+    };
+    assert_eq!(s.to_string(), r#""say \"hi\"\n""#);
+
+    let s = String {
+      value: r#"{"key": "\d"}"#.into(),
+      info: (),
+    };
+    assert_eq!(s.to_string(), r##"r#"{"key": "\d"}"#"##);
+
+    let s = String {
+      value: "line 1\n  line 2".into(),
+      info: (),
+    };
+    assert_eq!(s.to_string(), "\"\"\"\nline 1\n  line 2\n\"\"\"");
+  }
+}
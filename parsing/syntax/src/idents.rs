@@ -1,7 +1,7 @@
 use crate::{from_box, Ptr};
 use diom_fmt::{DisplayAs, SpanWriter, Spans};
 use diom_info_traits::{InfoMap, InfoRef, InfoSource};
-use diom_tokens::Token;
+use diom_tokens::{keywords::is_keyword, Token};
 use proptest::{
   prelude::{Arbitrary, BoxedStrategy, Just, Strategy},
   prop_oneof,
@@ -10,8 +10,9 @@ use std::{
   fmt::{Display, Write},
   ops::Range,
 };
+use unicode_normalization::UnicodeNormalization;
 
-/// A literal alphanumeric name
+/// A literal identifier name, normalised to NFC
 pub type LitName = Ptr<str>;
 
 /// Helper to convert a literal name to a `Box<str>`\
@@ -20,8 +21,19 @@ fn into_box_str(lit: LitName) -> Box<str> {
   lit.to_string().into_boxed_str()
 }
 
-/// An alphanumeric identifier for use in variable definitions and tags
+/// Generates Unicode identifier names in NFC, that aren't keywords
+pub(crate) fn any_name() -> impl Strategy<Value = String> {
+  prop_oneof![
+    3 => "[_a-zA-Z][_a-zA-Z0-9]*",
+    1 => r"[_\p{XID_Start}]\p{XID_Continue}*",
+  ]
+  .prop_map(|name| name.nfc().collect())
+  .prop_filter("keywords aren't names", |name: &String| !is_keyword(name))
+}
+
+/// An identifier for use in variable definitions and tags
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ident<I> {
   #[map_ignore]
   pub name: LitName,
@@ -42,7 +54,7 @@ impl DisplayAs<Spans> for Ident<Range<usize>> {
 
 impl Ident<()> {
   pub fn any() -> impl Strategy<Value = Self> {
-    "[_a-zA-Z][_a-zA-Z0-9]*".prop_map(|name| Ident {
+    any_name().prop_map(|name| Ident {
       name: name.into(),
       info: (),
     })
@@ -51,6 +63,7 @@ impl Ident<()> {
 
 /// Possible symbol types in diom, i.e. `!`, `+`, `>=`
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Symbol {
   /// `!`
   Not,
@@ -151,6 +164,7 @@ impl Arbitrary for Symbol {
 }
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Op<I> {
   #[map_ignore]
   pub sym: Symbol,
@@ -177,6 +191,7 @@ impl Op<()> {
 
 /// A method name that can be either a literal name or a symbol
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Name {
   Literal(LitName),
   Symbol(Symbol),
@@ -237,7 +252,7 @@ impl Display for Name {
 impl Name {
   /// Generates a generic strategy for generating `Name`s
   pub fn any() -> impl Strategy<Value = Self> {
-    let lit = r"[_a-zA-Z][_a-zA-Z0-9]*"
+    let lit = any_name()
       .prop_map(|s| s.into_boxed_str())
       .prop_map(from_box)
       .prop_map(Name::Literal);
@@ -255,6 +270,7 @@ impl Arbitrary for Name {
 
 /// An identifier used for method names and operators
 #[derive(Clone, InfoSource, InfoRef, InfoMap, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Method<I> {
   #[map_ignore]
   pub name: Name,
@@ -0,0 +1,28 @@
+//! Placeholder nodes for source that failed to parse.
+//!
+//! When parsing recovers from an error, the unparsable section is kept in the tree as an `Invalid` node,\
+//! so that tools still get a tree for the rest of the code.
+use diom_fmt::{DisplayAs, SpanWriter, Spans};
+use diom_info_traits::{InfoMap, InfoRef, InfoSource};
+use std::{
+  fmt::{Display, Write},
+  ops::Range,
+};
+
+#[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Invalid<I> {
+  pub info: I,
+}
+
+impl<I> Display for Invalid<I> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str("<error>")
+  }
+}
+
+impl DisplayAs<Spans> for Invalid<Range<usize>> {
+  fn write<W: Write>(&self, w: &mut SpanWriter<W>) -> std::fmt::Result {
+    w.bracket("error", &self.info)
+  }
+}
@@ -3,18 +3,28 @@
 //! The nodes here fall into 2 broad categories:
 //! 1. nodes for Diom typing
 //! 2. nodes for Diom values
+//!
+//! ## Features
+//! - `sync`: shares syntax nodes with `Arc` rather than `Rc`, so that trees are `Send + Sync`.\
+//!   This is off by default, so that evaluating a tree doesn't pay for atomic reference counts.
 
-use std::rc::Rc;
 mod display;
 pub mod expressions;
 pub mod idents;
+pub mod invalid;
+pub mod modules;
 pub mod path;
 pub mod patterns;
+pub mod program;
 pub mod types;
 pub mod var;
 
 /// The ptr type used for indirection in syntax nodes
-pub type Ptr<T> = Rc<T>;
+#[cfg(not(feature = "sync"))]
+pub type Ptr<T> = std::rc::Rc<T>;
+/// The ptr type used for indirection in syntax nodes
+#[cfg(feature = "sync")]
+pub type Ptr<T> = std::sync::Arc<T>;
 /// The owned sequence used for indirection in syntax nodes
 pub type Seq<T> = Vec<T>;
 
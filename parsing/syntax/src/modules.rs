@@ -0,0 +1,110 @@
+//! Imports of other Diom files.
+use crate::{expressions::String, idents::Ident, path::Path, Seq};
+use diom_fmt::{DisplayAs, SpanWriter, Spans};
+use diom_info_traits::{InfoMap, InfoRef, InfoSource};
+use proptest::{
+  collection::vec,
+  prelude::{Arbitrary, BoxedStrategy, Strategy},
+  prop_oneof,
+};
+use std::{
+  fmt::{Display, Write},
+  ops::Range,
+};
+
+/// How an imported module is referred to
+#[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ModulePath<I> {
+  /// A module found on the search path, i.e. `math.vector` for `math/vector.diom`
+  Name(Path<I>),
+  /// A file relative to the importing file, i.e. `"./shapes.diom"`
+  File(String<I>),
+}
+
+impl<I> ModulePath<I> {
+  /// The name the module's exports are bound to,\
+  /// the last segment of a module name or the stem of a file.\
+  /// A file's stem might not be a name, i.e. for `"./my-lib.diom"`.
+  ///
+  /// ```
+  /// # use diom_syntax::{expressions::String, modules::ModulePath};
+  /// let file = ModulePath::File(String { value: "../lib/shapes.diom".into(), info: () });
+  /// assert_eq!(file.binding(), Some("shapes"));
+  /// ```
+  pub fn binding(&self) -> Option<&str> {
+    match self {
+      Self::Name(path) => path.segments.last().map(|s| &*s.name),
+      Self::File(file) => std::path::Path::new(&*file.value).file_stem()?.to_str(),
+    }
+  }
+}
+
+impl<I> Display for ModulePath<I> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Name(path) => path.fmt(f),
+      Self::File(file) => file.fmt(f),
+    }
+  }
+}
+
+impl DisplayAs<Spans> for ModulePath<Range<usize>> {
+  fn write<W: Write>(&self, w: &mut SpanWriter<W>) -> std::fmt::Result {
+    match self {
+      Self::Name(path) => path.write(w),
+      Self::File(file) => file.write(w),
+    }
+  }
+}
+
+/// An import of another module's top level bindings, i.e.
+///
+/// ```_
+/// use math.vector;
+/// use "./shapes.diom";
+/// vector.add(shapes.origin, shapes.unit)
+/// ```
+#[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Use<I> {
+  pub module: ModulePath<I>,
+  pub info: I,
+}
+
+impl<I> Display for Use<I> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "use {}", self.module)
+  }
+}
+
+impl DisplayAs<Spans> for Use<Range<usize>> {
+  fn write<W: Write>(&self, w: &mut SpanWriter<W>) -> std::fmt::Result {
+    w.bracket("use", &self.info)?;
+    self.module.write(&mut w.child())
+  }
+}
+
+impl Use<()> {
+  /// Generates a generic strategy for generating `Use` statements
+  pub fn any() -> impl Strategy<Value = Self> {
+    let name = vec(Ident::any(), 1..4)
+      .prop_map(Seq::from_iter)
+      .prop_map(|segments| ModulePath::Name(Path { segments, info: () }));
+    let file = r"(\.\./|\./)([a-z_]+/)*[a-z_]+\.diom".prop_map(|path| {
+      ModulePath::File(String {
+        value: path.into(),
+        info: (),
+      })
+    });
+    prop_oneof![name, file].prop_map(|module| Use { module, info: () })
+  }
+}
+impl Arbitrary for Use<()> {
+  type Parameters = ();
+  type Strategy = BoxedStrategy<Self>;
+
+  fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+    Self::any().boxed()
+  }
+}
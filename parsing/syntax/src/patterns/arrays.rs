@@ -9,6 +9,7 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ArrayItem<I> {
   Item(Pattern<I>),
   Rest(Rest<I>),
@@ -40,6 +41,7 @@ impl ArrayItem<()> {
 }
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Array<I> {
   pub items: Seq<ArrayItem<I>>,
   pub info: I,
@@ -1,5 +1,6 @@
 use crate::{
   idents::Ident,
+  invalid::Invalid,
   patterns::{arrays::ArrayConfig, structs::StructConfig, tuples::TupleConfig},
 };
 use diom_fmt::{DisplayAs, SpanWriter, Spans};
@@ -27,6 +28,7 @@ pub mod tuples;
 use tuples::Tuple;
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Pattern<I> {
   Array(Array<I>),
   Struct(Struct<I>),
@@ -34,6 +36,8 @@ pub enum Pattern<I> {
   Tuple(Tuple<I>),
   Ignored(Ignored<I>),
   Var(Ident<I>),
+  /// A pattern that failed to parse
+  Error(Invalid<I>),
 }
 
 impl<I> Display for Pattern<I> {
@@ -46,6 +50,7 @@ impl<I> Display for Pattern<I> {
       Tuple(t) => t.fmt(f),
       Ignored(i) => i.fmt(f),
       Var(v) => v.fmt(f),
+      Error(e) => e.fmt(f),
     }
   }
 }
@@ -60,6 +65,7 @@ impl DisplayAs<Spans> for Pattern<Range<usize>> {
       Tuple(t) => t.write(w),
       Ignored(i) => i.write(w),
       Var(v) => v.write(w),
+      Error(e) => e.write(w),
     }
   }
 }
@@ -32,6 +32,7 @@ use std::{
 /// // that `sx` cannot be bound to an empty structure
 /// ```
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rest<I> {
   pub name: Option<Ident<I>>,
   pub info: I,
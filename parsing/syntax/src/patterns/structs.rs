@@ -9,6 +9,7 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StructField<I> {
   pub name: Method<I>,
   pub pattern: Pattern<I>,
@@ -43,6 +44,7 @@ impl StructField<()> {
 }
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StructItem<I> {
   Field(StructField<I>),
   Rest(Rest<I>),
@@ -77,6 +79,7 @@ impl StructItem<()> {
 }
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Struct<I> {
   pub fields: Seq<StructItem<I>>,
   pub info: I,
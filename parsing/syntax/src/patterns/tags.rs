@@ -10,6 +10,7 @@ use std::{
 /// A type with a unique tag attached.\
 /// This effectively converts duck typing into static typing.
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tagged<I> {
   pub name: Ident<I>,
   pub value: Ptr<Pattern<I>>,
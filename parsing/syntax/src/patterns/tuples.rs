@@ -9,6 +9,7 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TupleItem<I> {
   Field(Pattern<I>),
   Rest(Rest<I>),
@@ -40,6 +41,7 @@ impl TupleItem<()> {
 }
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tuple<I> {
   pub fields: Seq<TupleItem<I>>,
   pub info: I,
@@ -0,0 +1,74 @@
+//! The top level node of a Diom file.
+use crate::{
+  expressions::{Expression, ExpressionConfig, Statement},
+  modules::Use,
+  Seq,
+};
+use diom_fmt::{DisplayAs, SpanWriter, Spans};
+use diom_info_traits::{InfoMap, InfoRef, InfoSource};
+use proptest::{
+  collection::vec,
+  prelude::{Arbitrary, BoxedStrategy, Strategy},
+  prop_oneof,
+};
+use std::{
+  fmt::{Display, Write},
+  ops::Range,
+};
+
+/// A sequence of `;` separated statements, i.e.
+///
+/// ```_
+/// type Point = {x: Float, y: Float};
+/// let origin = {x: 0, y: 0};
+/// origin.x
+/// ```
+#[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Program<I> {
+  pub statements: Seq<Statement<I>>,
+  pub info: I,
+}
+
+impl<I> Display for Program<I> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    for (i, statement) in self.statements.iter().enumerate() {
+      if i > 0 {
+        f.write_char('\n')?;
+      }
+      write!(f, "{statement};")?;
+    }
+    Ok(())
+  }
+}
+
+impl DisplayAs<Spans> for Program<Range<usize>> {
+  fn write<W: Write>(&self, w: &mut SpanWriter<W>) -> std::fmt::Result {
+    w.bracket("program", &self.info)?;
+    self.statements.write(&mut w.child())
+  }
+}
+
+impl Program<()> {
+  /// Generates a generic strategy for generating `Program` nodes
+  pub fn any(args: ExpressionConfig) -> impl Strategy<Value = Self> {
+    let statement = prop_oneof![
+      9 => Statement::any(Expression::any(args), args.types),
+      1 => Use::any().prop_map(Statement::Use),
+    ];
+    vec(statement, 0..args.block_stmts)
+      .prop_map(Seq::from_iter)
+      .prop_map(|statements| Program {
+        statements,
+        info: (),
+      })
+  }
+}
+impl Arbitrary for Program<()> {
+  type Parameters = ExpressionConfig;
+  type Strategy = BoxedStrategy<Self>;
+
+  fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+    Self::any(args).boxed()
+  }
+}
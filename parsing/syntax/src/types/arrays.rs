@@ -18,6 +18,7 @@ use std::{
 /// let xs: Nums = [1, 2, 3];
 /// ```
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Array<I> {
   pub item: Ptr<Type<I>>,
   pub info: I,
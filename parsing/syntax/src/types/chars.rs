@@ -13,6 +13,7 @@ use std::{
 /// let c: Char = 'v';
 /// ```
 #[derive(Debug, Clone, InfoSource, InfoRef, InfoMap)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Char<I> {
   pub info: I,
 }
@@ -30,6 +30,7 @@ use super::{Tagged, Type};
 /// let bool = Boolean.False;
 /// ```
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Enum<I> {
   pub variants: Seq<Tagged<I>>,
   pub info: I,
@@ -16,6 +16,7 @@ use std::{
 /// let x: Float = -1e-1;
 /// ```
 #[derive(Debug, Clone, InfoSource, InfoRef, InfoMap)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Float<I> {
   pub info: I,
 }
@@ -9,6 +9,7 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Parameter<I> {
   pub name: Ident<I>,
   pub annotation: Type<I>,
@@ -43,6 +44,7 @@ impl Parameter<()> {
 }
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Parameters<I> {
   pub parameters: Seq<Parameter<I>>,
   pub info: I,
@@ -87,6 +89,7 @@ impl Parameters<()> {
 /// let add: Binary = (x) => {(y) => {x + y}};
 /// ```
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Function<I> {
   pub parameters: Parameters<I>,
   pub returned: Ptr<Type<I>>,
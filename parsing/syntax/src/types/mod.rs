@@ -35,6 +35,7 @@ mod tags;
 pub use tags::Tagged;
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Type<I> {
   /* type variables */
   Var(Ident<I>),
@@ -25,6 +25,7 @@ use super::Type;
 /// let bobs_email: Email = { name: "bob.jones", domain: "hotmail.com" };
 /// ```
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Struct<I> {
   pub fields: Seq<(Method<I>, Type<I>)>,
   pub info: I,
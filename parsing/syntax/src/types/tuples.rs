@@ -18,6 +18,7 @@ use std::{
 /// let bobs_email: Email = ["bob.jones", "hotmail.com"];
 /// ```
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tuple<I> {
   pub fields: Seq<Type<I>>,
   pub info: I,
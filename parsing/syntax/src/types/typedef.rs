@@ -1,7 +1,9 @@
 use super::Type;
 use crate::{
+  display::{any_docs, Docs},
   idents::Ident,
   types::{Tagged, TypeConfig},
+  Ptr,
 };
 use diom_fmt::{DisplayAs, SpanWriter, Spans};
 use diom_info_traits::{InfoMap, InfoRef, InfoSource};
@@ -21,7 +23,11 @@ use std::{
 /// type VecN_0 = [Float; _];
 /// ```
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Alias<I> {
+  /// Doc comments written before the alias, i.e. `## A number`
+  #[map_ignore]
+  pub doc: Option<Ptr<str>>,
   pub name: Ident<I>,
   pub value: Type<I>,
   pub info: I,
@@ -29,6 +35,7 @@ pub struct Alias<I> {
 
 impl<I> Display for Alias<I> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    Docs(&self.doc).fmt(f)?;
     f.write_str("type ")?;
     self.name.fmt(f)?;
     f.write_str(" = ")?;
@@ -46,7 +53,8 @@ impl DisplayAs<Spans> for Alias<Range<usize>> {
 
 impl Alias<()> {
   pub fn any(args: TypeConfig) -> impl Strategy<Value = Self> {
-    (Ident::any(), Type::any(args)).prop_map(|(name, value)| Alias {
+    (any_docs(), Ident::any(), Type::any(args)).prop_map(|(doc, name, value)| Alias {
+      doc,
       name,
       value,
       info: (),
@@ -62,13 +70,18 @@ impl Alias<()> {
 /// type VecN_1 [Float; _];
 /// ```
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NewType<I> {
+  /// Doc comments written before the type, i.e. `## A 2d vector`
+  #[map_ignore]
+  pub doc: Option<Ptr<str>>,
   pub tag: Tagged<I>,
   pub info: I,
 }
 
 impl<I> Display for NewType<I> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    Docs(&self.doc).fmt(f)?;
     f.write_str("type ")?;
     self.tag.fmt(f)
   }
@@ -83,7 +96,7 @@ impl DisplayAs<Spans> for NewType<Range<usize>> {
 
 impl NewType<()> {
   pub fn any(args: TypeConfig) -> impl Strategy<Value = Self> {
-    Tagged::any(Type::any(args)).prop_map(|tag| NewType { tag, info: () })
+    (any_docs(), Tagged::any(Type::any(args))).prop_map(|(doc, tag)| NewType { doc, tag, info: () })
   }
 }
 
@@ -103,6 +116,7 @@ impl NewType<()> {
 /// type VecN_1 [Float; _];
 /// ```
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TypeDef<I> {
   Alias(Alias<I>),
   New(NewType<I>),
@@ -1,4 +1,4 @@
-use crate::idents::LitName;
+use crate::idents::{any_name, LitName};
 use diom_fmt::{DisplayAs, SpanWriter, Spans};
 use diom_info_traits::{InfoMap, InfoRef, InfoSource};
 use proptest::prelude::Strategy;
@@ -8,6 +8,7 @@ use std::{
 };
 
 #[derive(Clone, InfoSource, InfoRef, InfoMap, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Var<I> {
   #[map_ignore]
   pub name: LitName,
@@ -28,7 +29,7 @@ impl DisplayAs<Spans> for Var<Range<usize>> {
 
 impl Var<()> {
   pub fn any() -> impl Strategy<Value = Self> {
-    "[_a-zA-Z][_a-zA-Z0-9]*".prop_map(|name| Var {
+    any_name().prop_map(|name| Var {
       name: name.into(),
       info: (),
     })
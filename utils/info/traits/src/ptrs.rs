@@ -0,0 +1,48 @@
+//! Info traits for shared pointers, which pass through to the value they point to.
+//!
+//! `Rc` and `Arc` are implemented the same way, so both come from one macro.
+use std::{ops::Deref, rc::Rc, sync::Arc};
+
+use crate::InfoRef;
+
+use super::{InfoMap, InfoSource};
+
+macro_rules! impl_ptr {
+  ($ptr:ident) => {
+    impl<I: InfoSource + ?Sized> InfoSource for $ptr<I> {
+      type Info = I::Info;
+    }
+
+    impl<I: InfoRef + ?Sized> InfoRef for $ptr<I> {
+      fn info(&self) -> &Self::Info {
+        self.deref().info()
+      }
+    }
+
+    unsafe impl<I: InfoMap> InfoMap for $ptr<I> {
+      /// ## Safety
+      ///
+      /// ```_
+      /// Self::GenericSelf<Self::Info>
+      ///   "`InfoMap` implementation"
+      /// => Ptr<<I as InfoMap>::GenericSelf<Self::Info>>
+      ///   "`InfoSource` implementation"
+      /// => Ptr<<I as InfoMap>::GenericSelf<I::Info>>
+      ///   "Induction"
+      /// => Ptr<I>
+      ///   "Implemetation"
+      /// => Self
+      /// ```
+      type GenericSelf<T> = $ptr<<I as InfoMap>::GenericSelf<T>>;
+
+      fn map_dyn<R>(self, f: &mut dyn FnMut(Self::Info) -> R) -> Self::GenericSelf<R> {
+        // @todo this is not the best, ideally we'd have a stronger guarantee on uniqueness
+        let inner = $ptr::into_inner(self).expect("InfoMap called alongside mutable borrow");
+        $ptr::new(InfoMap::map(inner, f))
+      }
+    }
+  };
+}
+
+impl_ptr!(Rc);
+impl_ptr!(Arc);
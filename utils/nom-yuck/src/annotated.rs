@@ -1,4 +1,5 @@
 use diom_fmt::{Flush, Format, IndentWriter, Indented};
+use diom_tokens::source_map::{LineIndex, Unit};
 use nom::Offset;
 use std::{fmt::Write, ops::Range};
 
@@ -49,6 +50,7 @@ impl<I: Clone> Format for Annotated<I> {
       config: self.clone(),
       write: Indented::from(self.fill).writer(w),
       span: None,
+      index: None,
     }
   }
 }
@@ -62,33 +64,40 @@ pub struct AnnotationWriter<I, W> {
   /// This lets us keep track of when to reprint the input\
   /// when displaying a new annotation / error
   span: Option<Range<usize>>,
+  /// Line positions within the input, calculated on the first annotation
+  index: Option<LineIndex>,
 }
 
 pub const LINE_LENGTH: usize = 80;
 
-/// Calculates the range within which the `index` fits in `input`
-fn range_for(input: &str, index: usize) -> Range<usize> {
-  let mut start = input[..index].rfind('\n').unwrap_or(0);
-  let mut end = input[start..].find('\n').unwrap_or(input.len());
-
+/// Calculates the byte range of the line that `offset` is on,\
+/// narrowed to around `LINE_LENGTH` bytes surrounding `offset` for long lines.
+pub(crate) fn range_for(index: &LineIndex, offset: usize) -> Range<usize> {
+  let (line, _) = index.position(offset, Unit::Utf8);
+  let Range { start, end } = index.line(line);
   if end - start <= LINE_LENGTH {
     return start..end;
   }
-  start = start
-    .min(index.saturating_sub(LINE_LENGTH / 2))
-    .min(end.saturating_sub(LINE_LENGTH));
-  end = end
-    .max(index.saturating_add(LINE_LENGTH / 2))
-    .max(start.saturating_add(LINE_LENGTH));
+
+  let source = index.source();
+  let mut start = offset
+    .saturating_sub(LINE_LENGTH / 2)
+    .clamp(start, end - LINE_LENGTH);
+  let mut end = start + LINE_LENGTH;
+  while !source.is_char_boundary(start) {
+    start -= 1;
+  }
+  while !source.is_char_boundary(end) {
+    end += 1;
+  }
   start..end
 }
 
 /// Calculates the position of a cursor within the input `str`
-fn cursor_position(input: &str, rest: &str) -> (Range<usize>, usize) {
-  let index = input.offset(rest);
-  let Range { start, end } = range_for(input, index);
-  let offset = input[start..index].chars().count() + 2;
-  (start..end, offset)
+pub(crate) fn cursor_position(index: &LineIndex, offset: usize) -> (Range<usize>, usize) {
+  let range = range_for(index, offset);
+  let column = Unit::Char.len(&index.source()[range.start..offset]);
+  (range, column + 2)
 }
 
 // @todo maybe we shouldn't be using `AsRef` here?\
@@ -114,7 +123,9 @@ impl<I: AsRef<str>, W: Write> AnnotationWriter<I, W> {
   /// Sets the location of the next annotation to write
   pub fn set_origin(&mut self, rest: impl AsRef<str>) -> std::fmt::Result {
     let i_str = self.config.input.as_ref();
-    let (range, idx) = cursor_position(i_str, rest.as_ref());
+    let offset = i_str.offset(rest.as_ref());
+    let index = self.index.get_or_insert_with(|| LineIndex::new(i_str));
+    let (range, idx) = cursor_position(index, offset);
 
     // first annotation made => new cursor
     let Some(span) = self.span.take() else {
@@ -145,3 +156,26 @@ impl<I, W: Write> Write for AnnotationWriter<I, W> {
 }
 
 impl<I, W> Flush for AnnotationWriter<I, W> {}
+
+#[cfg(test)]
+mod test {
+  use super::{cursor_position, LINE_LENGTH};
+  use diom_tokens::source_map::LineIndex;
+
+  #[test]
+  fn cursor_lines() {
+    let index = LineIndex::new("let x = 1;\nlet ÿ = x + z;");
+    assert_eq!(cursor_position(&index, 4), (0..10, 6));
+    // columns are counted in characters, after the opening backtick
+    assert_eq!(cursor_position(&index, 24), (11..26, 14));
+  }
+
+  #[test]
+  fn cursor_long_lines() {
+    let line = "x + ".repeat(50);
+    let index = LineIndex::new(&line);
+    let (range, idx) = cursor_position(&index, 100);
+    assert_eq!(range, 60..60 + LINE_LENGTH);
+    assert_eq!(idx, 42);
+  }
+}
@@ -1,6 +1,7 @@
-use crate::annotated::AnnotationWriter;
+use crate::annotated::{cursor_position, AnnotationWriter};
 use crate::Annotated;
 use diom_fmt::DisplayAs;
+use diom_tokens::source_map::LineIndex;
 use nom::error::ContextError;
 use nom::error::ErrorKind;
 use nom::error::FromExternalError;
@@ -12,7 +13,6 @@ use std::fmt::from_fn;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Write;
-use std::ops::Range;
 
 #[derive(Clone, Eq, PartialEq)]
 /// Error context for `ParserError`
@@ -113,47 +113,22 @@ impl<I> DebugError<I> {
   }
 }
 
-pub(crate) const LINE_LENGTH: usize = 80;
-
-/// Calculates the range within which the `index` fits in `input`
-pub(crate) fn range_for(input: &str, index: usize) -> Range<usize> {
-  let mut start = input[..index].rfind('\n').unwrap_or(0);
-  let mut end = input[start..].find('\n').unwrap_or(input.len());
-
-  if end - start <= LINE_LENGTH {
-    return start..end;
-  }
-  start = start
-    .min(index.saturating_sub(LINE_LENGTH / 2))
-    .min(end.saturating_sub(LINE_LENGTH));
-  end = end
-    .max(index.saturating_add(LINE_LENGTH / 2))
-    .max(start.saturating_add(LINE_LENGTH));
-  start..end
-}
-
-/// Calculates the position of a cursor within the input `str`
-pub(crate) fn cursor_position(input: &str, rest: &str) -> (Range<usize>, usize) {
-  let index = input.offset(rest);
-  let Range { start, end } = range_for(input, index);
-  let offset = input[start..index].chars().count() + 2;
-  (start..end, offset)
-}
-
 impl DebugError<&str> {
   /// Displays a stack trace for the parsing input
   pub fn display<'a>(&'a self, input: &'a str) -> impl Debug + 'a {
     from_fn(move |f| {
+      let index = LineIndex::new(input);
+      let cursor_position = |rest| cursor_position(&index, input.offset(rest));
       let mut iter = self.errors.iter();
       let Some((rest, kind)) = iter.next() else {
         return Ok(());
       };
-      let (mut range, idx) = cursor_position(input, rest);
+      let (mut range, idx) = cursor_position(rest);
       writeln!(f, "`{}`", &input[range.clone()])?;
       write!(f, "{:>idx$} {kind:?}", '^')?;
 
       for (rest, kind) in iter {
-        let (nrange, idx) = cursor_position(input, rest);
+        let (nrange, idx) = cursor_position(rest);
         if nrange == range {
           write!(f, "\n{:>idx$} {kind:?}", '^')?;
           continue;